@@ -228,7 +228,7 @@ mod test_bit_math {
         fn test_mul_div_large_ok() {
             assert_eq!(
                 checked_mul_div(u128::MAX, 1, u128::from(u64::MAX) + 1).unwrap(),
-                u64::MAX.into()
+                u128::from(u64::MAX)
             );
             assert_eq!(checked_mul_div(u128::MAX - 1, 1, u128::MAX).unwrap(), 0);
         }
@@ -0,0 +1,175 @@
+//! Compares the token math of the core crate of the rust sdk with the token math of the program
+//! over random sqrt prices, liquidities and amounts. Both must round the same way and fail for
+//! the same inputs, so that quotes never diverge from the swaps of the program.
+
+use crate::errors::ErrorCode;
+use crate::math::*;
+use orca_whirlpools_core as whirlpools_core;
+use proptest::prelude::*;
+
+type CoreErrorCode = whirlpools_core::ErrorCode;
+
+/// Asserts that both results are equal, and that an error of the program is returned as the
+/// expected error of the core crate.
+fn assert_parity<T: PartialEq + std::fmt::Debug>(
+    program: Result<T, ErrorCode>,
+    core: Result<T, CoreErrorCode>,
+    expected_errors: &[(ErrorCode, CoreErrorCode)],
+) {
+    match program {
+        Ok(value) => assert_eq!(core, Ok(value)),
+        Err(error) => {
+            let expected_error = expected_errors
+                .iter()
+                .find(|(program_error, _)| *program_error == error)
+                .map(|(_, core_error)| *core_error);
+            assert!(
+                expected_error.is_some(),
+                "Unexpected program error - {:?}",
+                error
+            );
+            assert_eq!(core, Err(expected_error.unwrap()));
+        }
+    }
+}
+
+fn sqrt_price() -> impl Strategy<Value = u128> {
+    MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64
+}
+
+fn liquidity() -> impl Strategy<Value = u128> {
+    prop_oneof![
+        Just(0u128),
+        1..=u64::MAX as u128,
+        any::<u128>(),
+    ]
+}
+
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0u64), 1..=u32::MAX as u64, any::<u64>()]
+}
+
+proptest! {
+    #[test]
+    fn test_amount_delta_a_parity(
+        sqrt_price_0 in sqrt_price(),
+        sqrt_price_1 in sqrt_price(),
+        liquidity in liquidity(),
+        round_up in any::<bool>(),
+    ) {
+        assert_parity(
+            get_amount_delta_a(sqrt_price_0, sqrt_price_1, liquidity, round_up),
+            whirlpools_core::try_get_amount_delta_a(sqrt_price_0, sqrt_price_1, liquidity, round_up),
+            &[
+                (ErrorCode::MultiplicationOverflow, whirlpools_core::ARITHMETIC_OVERFLOW),
+                (ErrorCode::TokenMaxExceeded, whirlpools_core::AMOUNT_EXCEEDS_MAX_U64),
+                (ErrorCode::NumberDownCastError, whirlpools_core::AMOUNT_EXCEEDS_MAX_U64),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_amount_delta_b_parity(
+        sqrt_price_0 in sqrt_price(),
+        sqrt_price_1 in sqrt_price(),
+        liquidity in liquidity(),
+        round_up in any::<bool>(),
+    ) {
+        assert_parity(
+            get_amount_delta_b(sqrt_price_0, sqrt_price_1, liquidity, round_up),
+            whirlpools_core::try_get_amount_delta_b(sqrt_price_0, sqrt_price_1, liquidity, round_up),
+            &[
+                (ErrorCode::MultiplicationShiftRightOverflow, whirlpools_core::MULTIPLICATION_SHIFT_RIGHT_OVERFLOW),
+                (ErrorCode::MultiplicationOverflow, whirlpools_core::ARITHMETIC_OVERFLOW),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_next_sqrt_price_from_a_parity(
+        sqrt_price in sqrt_price(),
+        liquidity in liquidity(),
+        amount in amount(),
+        amount_specified_is_input in any::<bool>(),
+    ) {
+        assert_parity(
+            get_next_sqrt_price_from_a_round_up(sqrt_price, liquidity, amount, amount_specified_is_input),
+            whirlpools_core::try_get_next_sqrt_price_from_a(sqrt_price, liquidity, amount, amount_specified_is_input),
+            &[
+                (ErrorCode::MultiplicationOverflow, whirlpools_core::ARITHMETIC_OVERFLOW),
+                (ErrorCode::DivideByZero, whirlpools_core::SQRT_PRICE_OUT_OF_BOUNDS),
+                (ErrorCode::NumberDownCastError, whirlpools_core::SQRT_PRICE_OUT_OF_BOUNDS),
+                (ErrorCode::TokenMinSubceeded, whirlpools_core::SQRT_PRICE_OUT_OF_BOUNDS),
+                (ErrorCode::TokenMaxExceeded, whirlpools_core::SQRT_PRICE_OUT_OF_BOUNDS),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_next_sqrt_price_from_b_parity(
+        sqrt_price in sqrt_price(),
+        liquidity in liquidity(),
+        amount in amount(),
+        amount_specified_is_input in any::<bool>(),
+    ) {
+        // The program leaves the bounds check to the swap, the core crate checks the result
+        let program = get_next_sqrt_price_from_b_round_down(sqrt_price, liquidity, amount, amount_specified_is_input)
+            .and_then(|next_sqrt_price| {
+                if (MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&next_sqrt_price) {
+                    Ok(next_sqrt_price)
+                } else {
+                    Err(ErrorCode::SqrtPriceOutOfBounds)
+                }
+            });
+        assert_parity(
+            program,
+            whirlpools_core::try_get_next_sqrt_price_from_b(sqrt_price, liquidity, amount, amount_specified_is_input),
+            &[
+                (ErrorCode::DivideByZero, whirlpools_core::SQRT_PRICE_OUT_OF_BOUNDS),
+                (ErrorCode::SqrtPriceOutOfBounds, whirlpools_core::SQRT_PRICE_OUT_OF_BOUNDS),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_swap_step_parity(
+        sqrt_price in sqrt_price(),
+        liquidity in 1..=u64::MAX as u128,
+        amount in 1..u64::MAX,
+        amount_specified_is_input in any::<bool>(),
+        a_to_b in any::<bool>(),
+    ) {
+        // The price a partial swap step moves to and the amounts that are swapped for it, with
+        // the rounding of `compute_swap`. Swaps that fail are covered by the tests above.
+        let next_sqrt_price = match get_next_sqrt_price(
+            sqrt_price,
+            liquidity,
+            amount,
+            amount_specified_is_input,
+            a_to_b,
+        ) {
+            Ok(next_sqrt_price)
+                if (MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&next_sqrt_price) =>
+            {
+                next_sqrt_price
+            }
+            _ => return Ok(()),
+        };
+
+        let core_next_sqrt_price = if a_to_b == amount_specified_is_input {
+            whirlpools_core::try_get_next_sqrt_price_from_a(sqrt_price, liquidity, amount, amount_specified_is_input)
+        } else {
+            whirlpools_core::try_get_next_sqrt_price_from_b(sqrt_price, liquidity, amount, amount_specified_is_input)
+        };
+        prop_assert_eq!(core_next_sqrt_price, Ok(next_sqrt_price));
+
+        prop_assert_eq!(
+            get_amount_delta_a(sqrt_price, next_sqrt_price, liquidity, a_to_b).ok(),
+            whirlpools_core::try_get_amount_delta_a(sqrt_price, next_sqrt_price, liquidity, a_to_b).ok()
+        );
+        prop_assert_eq!(
+            get_amount_delta_b(sqrt_price, next_sqrt_price, liquidity, !a_to_b).ok(),
+            whirlpools_core::try_get_amount_delta_b(sqrt_price, next_sqrt_price, liquidity, !a_to_b).ok()
+        );
+    }
+}
@@ -1,2 +1,4 @@
 #[cfg(test)]
+mod core_token_math_parity_tests;
+#[cfg(test)]
 mod swap_integration_tests;
@@ -0,0 +1,215 @@
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::accounts::{
+    FeeTier, Position, PositionBundle, TickArray, TokenBadge, Whirlpool, WhirlpoolsConfig,
+    WhirlpoolsConfigExtension,
+};
+use crate::generated::programs::WHIRLPOOL_ID;
+
+/// The length of the Anchor discriminator at the start of the account data.
+pub(crate) const DISCRIMINATOR_LEN: usize = 8;
+
+/// The discriminator at the start of the data of `Whirlpool` accounts.
+pub const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+/// The discriminator at the start of the data of `Position` accounts.
+pub const POSITION_DISCRIMINATOR: [u8; 8] = [170, 188, 143, 228, 122, 64, 247, 208];
+/// The discriminator at the start of the data of `TickArray` accounts.
+pub const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+/// The discriminator at the start of the data of `FeeTier` accounts.
+pub const FEE_TIER_DISCRIMINATOR: [u8; 8] = [56, 75, 159, 76, 142, 68, 190, 105];
+/// The discriminator at the start of the data of `WhirlpoolsConfig` accounts.
+pub const WHIRLPOOLS_CONFIG_DISCRIMINATOR: [u8; 8] = [157, 20, 49, 224, 217, 87, 193, 254];
+/// The discriminator at the start of the data of `WhirlpoolsConfigExtension` accounts.
+pub const WHIRLPOOLS_CONFIG_EXTENSION_DISCRIMINATOR: [u8; 8] = [2, 99, 215, 163, 240, 26, 153, 58];
+/// The discriminator at the start of the data of `TokenBadge` accounts.
+pub const TOKEN_BADGE_DISCRIMINATOR: [u8; 8] = [116, 219, 204, 229, 249, 116, 255, 150];
+/// The discriminator at the start of the data of `PositionBundle` accounts.
+pub const POSITION_BUNDLE_DISCRIMINATOR: [u8; 8] = [129, 169, 175, 65, 185, 95, 32, 100];
+
+#[derive(Debug, Error)]
+pub enum AccountDecodeError {
+    #[error("Account is owned by {0}, not by the whirlpool program")]
+    InvalidOwner(Pubkey),
+    #[error("Account data is too short to contain a discriminator")]
+    MissingDiscriminator,
+    #[error("Unknown account discriminator {0:?}")]
+    UnknownDiscriminator([u8; DISCRIMINATOR_LEN]),
+    #[error("{name} account must be {expected} bytes, got {actual}")]
+    InvalidSize {
+        name: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Account data could not be decoded: {0}")]
+    InvalidData(#[from] std::io::Error),
+}
+
+/// Declares the `WhirlpoolAccount` enum from the list of accounts of the whirlpool program,
+/// together with the code that decodes them.
+macro_rules! whirlpool_accounts {
+    ($(
+        $name:ident($ty:ty) = $discriminator:ident,
+    )*) => {
+        /// A decoded account of the whirlpool program.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum WhirlpoolAccount {
+            $($name($ty),)*
+        }
+
+        impl WhirlpoolAccount {
+            /// The name of the account type, as declared in the whirlpool program.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
+            }
+
+            fn decode(data: &[u8]) -> Result<Self, AccountDecodeError> {
+                if data.len() < DISCRIMINATOR_LEN {
+                    return Err(AccountDecodeError::MissingDiscriminator);
+                }
+                let discriminator: [u8; DISCRIMINATOR_LEN] =
+                    data[..DISCRIMINATOR_LEN].try_into().unwrap();
+                match discriminator {
+                    $(
+                        $discriminator => {
+                            if data.len() != $name::LEN {
+                                return Err(AccountDecodeError::InvalidSize {
+                                    name: stringify!($name),
+                                    expected: $name::LEN,
+                                    actual: data.len(),
+                                });
+                            }
+                            Ok(Self::$name($name::from_bytes(data)?.into()))
+                        }
+                    )*
+                    _ => Err(AccountDecodeError::UnknownDiscriminator(discriminator)),
+                }
+            }
+        }
+    };
+}
+
+whirlpool_accounts! {
+    Whirlpool(Box<Whirlpool>) = WHIRLPOOL_DISCRIMINATOR,
+    Position(Position) = POSITION_DISCRIMINATOR,
+    TickArray(Box<TickArray>) = TICK_ARRAY_DISCRIMINATOR,
+    FeeTier(FeeTier) = FEE_TIER_DISCRIMINATOR,
+    WhirlpoolsConfig(WhirlpoolsConfig) = WHIRLPOOLS_CONFIG_DISCRIMINATOR,
+    WhirlpoolsConfigExtension(WhirlpoolsConfigExtension) =
+        WHIRLPOOLS_CONFIG_EXTENSION_DISCRIMINATOR,
+    TokenBadge(TokenBadge) = TOKEN_BADGE_DISCRIMINATOR,
+    PositionBundle(PositionBundle) = POSITION_BUNDLE_DISCRIMINATOR,
+}
+
+/// Decode an account of the whirlpool program without knowing its type up front. The type is
+/// detected from the discriminator at the start of the account data.
+///
+/// # Parameters
+/// - `owner` - The owner of the account, which must be the whirlpool program
+/// - `data` - The data of the account
+///
+/// # Returns
+/// - The decoded account
+pub fn decode_whirlpool_account(
+    owner: &Pubkey,
+    data: &[u8],
+) -> Result<WhirlpoolAccount, AccountDecodeError> {
+    if *owner != WHIRLPOOL_ID {
+        return Err(AccountDecodeError::InvalidOwner(*owner));
+    }
+    WhirlpoolAccount::decode(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_data(discriminator: [u8; 8], len: usize) -> Vec<u8> {
+        let mut data = vec![0u8; len];
+        data[..8].copy_from_slice(&discriminator);
+        data
+    }
+
+    #[test]
+    fn test_decode_whirlpool_account() {
+        let whirlpool = Pubkey::new_unique();
+        let mut data = account_data(POSITION_DISCRIMINATOR, Position::LEN);
+        data[8..40].copy_from_slice(whirlpool.as_ref());
+        data[72..88].copy_from_slice(&1000u128.to_le_bytes());
+
+        let account = decode_whirlpool_account(&WHIRLPOOL_ID, &data).unwrap();
+        assert_eq!(account.name(), "Position");
+        let WhirlpoolAccount::Position(position) = account else {
+            panic!("Expected a position account");
+        };
+        assert_eq!(position.whirlpool, whirlpool);
+        assert_eq!(position.liquidity, 1000);
+    }
+
+    #[test]
+    fn test_decode_whirlpool_account_all_types() {
+        let accounts = [
+            (WHIRLPOOL_DISCRIMINATOR, Whirlpool::LEN, "Whirlpool"),
+            (TICK_ARRAY_DISCRIMINATOR, TickArray::LEN, "TickArray"),
+            (FEE_TIER_DISCRIMINATOR, FeeTier::LEN, "FeeTier"),
+            (
+                WHIRLPOOLS_CONFIG_DISCRIMINATOR,
+                WhirlpoolsConfig::LEN,
+                "WhirlpoolsConfig",
+            ),
+            (
+                WHIRLPOOLS_CONFIG_EXTENSION_DISCRIMINATOR,
+                WhirlpoolsConfigExtension::LEN,
+                "WhirlpoolsConfigExtension",
+            ),
+            (TOKEN_BADGE_DISCRIMINATOR, TokenBadge::LEN, "TokenBadge"),
+            (
+                POSITION_BUNDLE_DISCRIMINATOR,
+                PositionBundle::LEN,
+                "PositionBundle",
+            ),
+        ];
+        for (discriminator, len, name) in accounts {
+            let data = account_data(discriminator, len);
+            let account = decode_whirlpool_account(&WHIRLPOOL_ID, &data).unwrap();
+            assert_eq!(account.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_decode_whirlpool_account_invalid_owner() {
+        let owner = Pubkey::new_unique();
+        let data = account_data(WHIRLPOOL_DISCRIMINATOR, Whirlpool::LEN);
+        assert!(matches!(
+            decode_whirlpool_account(&owner, &data),
+            Err(AccountDecodeError::InvalidOwner(address)) if address == owner
+        ));
+    }
+
+    #[test]
+    fn test_decode_whirlpool_account_invalid_size() {
+        let data = account_data(WHIRLPOOL_DISCRIMINATOR, Whirlpool::LEN - 1);
+        assert!(matches!(
+            decode_whirlpool_account(&WHIRLPOOL_ID, &data),
+            Err(AccountDecodeError::InvalidSize {
+                name: "Whirlpool",
+                expected: 653,
+                actual: 652,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decode_whirlpool_account_unknown_discriminator() {
+        assert!(matches!(
+            decode_whirlpool_account(&WHIRLPOOL_ID, &[1, 2, 3]),
+            Err(AccountDecodeError::MissingDiscriminator)
+        ));
+        assert!(matches!(
+            decode_whirlpool_account(&WHIRLPOOL_ID, &[0; 100]),
+            Err(AccountDecodeError::UnknownDiscriminator(discriminator)) if discriminator == [0; 8]
+        ));
+    }
+}
@@ -0,0 +1,844 @@
+use borsh::BorshDeserialize;
+use solana_program::instruction::{CompiledInstruction, Instruction};
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::generated::instructions::*;
+use crate::generated::programs::WHIRLPOOL_ID;
+use crate::generated::types::{AccountsType, RemainingAccountsInfo};
+
+/// The length of the Anchor discriminator at the start of the instruction data.
+const DISCRIMINATOR_LEN: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum InstructionDecodeError {
+    #[error("Instruction belongs to program {0}, not to the whirlpool program")]
+    InvalidProgramId(Pubkey),
+    #[error("Instruction data is too short to contain a discriminator")]
+    MissingDiscriminator,
+    #[error("Unknown instruction discriminator {0:?}")]
+    UnknownDiscriminator([u8; DISCRIMINATOR_LEN]),
+    #[error("Instruction {name} expects {expected} accounts, got {actual}")]
+    NotEnoughAccounts {
+        name: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Instruction arguments could not be decoded: {0}")]
+    InvalidData(#[from] std::io::Error),
+    #[error("Account index {0} is out of bounds of the account keys")]
+    InvalidAccountIndex(u8),
+    #[error("Remaining accounts do not match the remaining accounts info")]
+    InvalidRemainingAccounts,
+}
+
+/// Declares the accounts structs and the `WhirlpoolInstruction` enum from the list of
+/// instructions of the whirlpool program, together with the code that decodes them.
+macro_rules! whirlpool_instructions {
+    ($(
+        $name:ident {
+            discriminator: [$($discriminator:literal),*],
+            accounts: $accounts:ident { $($account:ident),* $(,)? },
+            $(args: $args:ident,)?
+        },
+    )*) => {
+        $(
+            #[doc = concat!("The accounts of the `", stringify!($name), "` instruction.")]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $accounts {
+                $(pub $account: Pubkey,)*
+            }
+
+            impl $accounts {
+                /// The number of accounts the instruction expects, excluding remaining accounts.
+                pub const LEN: usize = [$(stringify!($account)),*].len();
+            }
+        )*
+
+        /// A decoded whirlpool instruction with its named accounts and decoded arguments.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum WhirlpoolInstruction {
+            $(
+                $name {
+                    accounts: $accounts,
+                    $(args: $args,)?
+                },
+            )*
+        }
+
+        impl WhirlpoolInstruction {
+            /// The name of the instruction, as declared in the whirlpool program.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name { .. } => stringify!($name),)*
+                }
+            }
+
+            fn decode<'a>(
+                account_keys: &'a [Pubkey],
+                data: &[u8],
+            ) -> Result<(Self, &'a [Pubkey]), InstructionDecodeError> {
+                if data.len() < DISCRIMINATOR_LEN {
+                    return Err(InstructionDecodeError::MissingDiscriminator);
+                }
+                let (discriminator, mut args_data) = data.split_at(DISCRIMINATOR_LEN);
+                match discriminator {
+                    $(
+                        [$($discriminator),*] => {
+                            let [$($account,)* remaining_accounts @ ..] = account_keys else {
+                                return Err(InstructionDecodeError::NotEnoughAccounts {
+                                    name: stringify!($name),
+                                    expected: $accounts::LEN,
+                                    actual: account_keys.len(),
+                                });
+                            };
+                            let instruction = Self::$name {
+                                accounts: $accounts {
+                                    $($account: *$account,)*
+                                },
+                                $(args: $args::deserialize(&mut args_data)?,)?
+                            };
+                            Ok((instruction, remaining_accounts))
+                        }
+                    )*
+                    _ => Err(InstructionDecodeError::UnknownDiscriminator(
+                        discriminator.try_into().unwrap(),
+                    )),
+                }
+            }
+        }
+    };
+}
+
+whirlpool_instructions! {
+    InitializeConfig {
+        discriminator: [208, 127, 21, 1, 194, 190, 196, 70],
+        accounts: InitializeConfigAccounts { config, funder, system_program },
+        args: InitializeConfigInstructionArgs,
+    },
+    InitializePool {
+        discriminator: [95, 180, 10, 172, 84, 174, 232, 40],
+        accounts: InitializePoolAccounts {
+            whirlpools_config, token_mint_a, token_mint_b, funder, whirlpool, token_vault_a,
+            token_vault_b, fee_tier, token_program, system_program, rent,
+        },
+        args: InitializePoolInstructionArgs,
+    },
+    InitializeTickArray {
+        discriminator: [11, 188, 193, 214, 141, 91, 149, 184],
+        accounts: InitializeTickArrayAccounts { whirlpool, funder, tick_array, system_program },
+        args: InitializeTickArrayInstructionArgs,
+    },
+    InitializeFeeTier {
+        discriminator: [183, 74, 156, 160, 112, 2, 42, 30],
+        accounts: InitializeFeeTierAccounts {
+            config, fee_tier, funder, fee_authority, system_program,
+        },
+        args: InitializeFeeTierInstructionArgs,
+    },
+    InitializeReward {
+        discriminator: [95, 135, 192, 196, 242, 129, 230, 68],
+        accounts: InitializeRewardAccounts {
+            reward_authority, funder, whirlpool, reward_mint, reward_vault, token_program,
+            system_program, rent,
+        },
+        args: InitializeRewardInstructionArgs,
+    },
+    SetRewardEmissions {
+        discriminator: [13, 197, 86, 168, 109, 176, 27, 244],
+        accounts: SetRewardEmissionsAccounts { whirlpool, reward_authority, reward_vault },
+        args: SetRewardEmissionsInstructionArgs,
+    },
+    OpenPosition {
+        discriminator: [135, 128, 47, 77, 15, 152, 240, 49],
+        accounts: OpenPositionAccounts {
+            funder, owner, position, position_mint, position_token_account, whirlpool,
+            token_program, system_program, rent, associated_token_program,
+        },
+        args: OpenPositionInstructionArgs,
+    },
+    OpenPositionWithMetadata {
+        discriminator: [242, 29, 134, 48, 58, 110, 14, 60],
+        accounts: OpenPositionWithMetadataAccounts {
+            funder, owner, position, position_mint, position_metadata_account,
+            position_token_account, whirlpool, token_program, system_program, rent,
+            associated_token_program, metadata_program, metadata_update_auth,
+        },
+        args: OpenPositionWithMetadataInstructionArgs,
+    },
+    IncreaseLiquidity {
+        discriminator: [46, 156, 243, 118, 13, 205, 251, 178],
+        accounts: IncreaseLiquidityAccounts {
+            whirlpool, token_program, position_authority, position, position_token_account,
+            token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b,
+            tick_array_lower, tick_array_upper,
+        },
+        args: IncreaseLiquidityInstructionArgs,
+    },
+    DecreaseLiquidity {
+        discriminator: [160, 38, 208, 111, 104, 91, 44, 1],
+        accounts: DecreaseLiquidityAccounts {
+            whirlpool, token_program, position_authority, position, position_token_account,
+            token_owner_account_a, token_owner_account_b, token_vault_a, token_vault_b,
+            tick_array_lower, tick_array_upper,
+        },
+        args: DecreaseLiquidityInstructionArgs,
+    },
+    UpdateFeesAndRewards {
+        discriminator: [154, 230, 250, 13, 236, 209, 75, 223],
+        accounts: UpdateFeesAndRewardsAccounts {
+            whirlpool, position, tick_array_lower, tick_array_upper,
+        },
+    },
+    CollectFees {
+        discriminator: [164, 152, 207, 99, 30, 186, 19, 182],
+        accounts: CollectFeesAccounts {
+            whirlpool, position_authority, position, position_token_account, token_owner_account_a,
+            token_vault_a, token_owner_account_b, token_vault_b, token_program,
+        },
+    },
+    CollectReward {
+        discriminator: [70, 5, 132, 87, 86, 235, 177, 34],
+        accounts: CollectRewardAccounts {
+            whirlpool, position_authority, position, position_token_account, reward_owner_account,
+            reward_vault, token_program,
+        },
+        args: CollectRewardInstructionArgs,
+    },
+    CollectProtocolFees {
+        discriminator: [22, 67, 23, 98, 150, 178, 70, 220],
+        accounts: CollectProtocolFeesAccounts {
+            whirlpools_config, whirlpool, collect_protocol_fees_authority, token_vault_a,
+            token_vault_b, token_destination_a, token_destination_b, token_program,
+        },
+    },
+    Swap {
+        discriminator: [248, 198, 158, 145, 225, 117, 135, 200],
+        accounts: SwapAccounts {
+            token_program, token_authority, whirlpool, token_owner_account_a, token_vault_a,
+            token_owner_account_b, token_vault_b, tick_array0, tick_array1, tick_array2, oracle,
+        },
+        args: SwapInstructionArgs,
+    },
+    ClosePosition {
+        discriminator: [123, 134, 81, 0, 49, 68, 98, 98],
+        accounts: ClosePositionAccounts {
+            position_authority, receiver, position, position_mint, position_token_account,
+            token_program,
+        },
+    },
+    SetDefaultFeeRate {
+        discriminator: [118, 215, 214, 157, 182, 229, 208, 228],
+        accounts: SetDefaultFeeRateAccounts { whirlpools_config, fee_tier, fee_authority },
+        args: SetDefaultFeeRateInstructionArgs,
+    },
+    SetDefaultProtocolFeeRate {
+        discriminator: [107, 205, 249, 226, 151, 35, 86, 0],
+        accounts: SetDefaultProtocolFeeRateAccounts { whirlpools_config, fee_authority },
+        args: SetDefaultProtocolFeeRateInstructionArgs,
+    },
+    SetFeeRate {
+        discriminator: [53, 243, 137, 65, 8, 140, 158, 6],
+        accounts: SetFeeRateAccounts { whirlpools_config, whirlpool, fee_authority },
+        args: SetFeeRateInstructionArgs,
+    },
+    SetProtocolFeeRate {
+        discriminator: [95, 7, 4, 50, 154, 79, 156, 131],
+        accounts: SetProtocolFeeRateAccounts { whirlpools_config, whirlpool, fee_authority },
+        args: SetProtocolFeeRateInstructionArgs,
+    },
+    SetFeeAuthority {
+        discriminator: [31, 1, 50, 87, 237, 101, 97, 132],
+        accounts: SetFeeAuthorityAccounts { whirlpools_config, fee_authority, new_fee_authority },
+    },
+    SetCollectProtocolFeesAuthority {
+        discriminator: [34, 150, 93, 244, 139, 225, 233, 67],
+        accounts: SetCollectProtocolFeesAuthorityAccounts {
+            whirlpools_config, collect_protocol_fees_authority, new_collect_protocol_fees_authority,
+        },
+    },
+    SetRewardAuthority {
+        discriminator: [34, 39, 183, 252, 83, 28, 85, 127],
+        accounts: SetRewardAuthorityAccounts { whirlpool, reward_authority, new_reward_authority },
+        args: SetRewardAuthorityInstructionArgs,
+    },
+    SetRewardAuthorityBySuperAuthority {
+        discriminator: [240, 154, 201, 198, 148, 93, 56, 25],
+        accounts: SetRewardAuthorityBySuperAuthorityAccounts {
+            whirlpools_config, whirlpool, reward_emissions_super_authority, new_reward_authority,
+        },
+        args: SetRewardAuthorityBySuperAuthorityInstructionArgs,
+    },
+    SetRewardEmissionsSuperAuthority {
+        discriminator: [207, 5, 200, 209, 122, 56, 82, 183],
+        accounts: SetRewardEmissionsSuperAuthorityAccounts {
+            whirlpools_config, reward_emissions_super_authority,
+            new_reward_emissions_super_authority,
+        },
+    },
+    TwoHopSwap {
+        discriminator: [195, 96, 237, 108, 68, 162, 219, 230],
+        accounts: TwoHopSwapAccounts {
+            token_program, token_authority, whirlpool_one, whirlpool_two, token_owner_account_one_a,
+            token_vault_one_a, token_owner_account_one_b, token_vault_one_b,
+            token_owner_account_two_a, token_vault_two_a, token_owner_account_two_b,
+            token_vault_two_b, tick_array_one0, tick_array_one1, tick_array_one2, tick_array_two0,
+            tick_array_two1, tick_array_two2, oracle_one, oracle_two,
+        },
+        args: TwoHopSwapInstructionArgs,
+    },
+    InitializePositionBundle {
+        discriminator: [117, 45, 241, 149, 24, 18, 194, 65],
+        accounts: InitializePositionBundleAccounts {
+            position_bundle, position_bundle_mint, position_bundle_token_account,
+            position_bundle_owner, funder, token_program, system_program, rent,
+            associated_token_program,
+        },
+    },
+    InitializePositionBundleWithMetadata {
+        discriminator: [93, 124, 16, 179, 249, 131, 115, 245],
+        accounts: InitializePositionBundleWithMetadataAccounts {
+            position_bundle, position_bundle_mint, position_bundle_metadata,
+            position_bundle_token_account, position_bundle_owner, funder, metadata_update_auth,
+            token_program, system_program, rent, associated_token_program, metadata_program,
+        },
+    },
+    DeletePositionBundle {
+        discriminator: [100, 25, 99, 2, 217, 239, 124, 173],
+        accounts: DeletePositionBundleAccounts {
+            position_bundle, position_bundle_mint, position_bundle_token_account,
+            position_bundle_owner, receiver, token_program,
+        },
+    },
+    OpenBundledPosition {
+        discriminator: [169, 113, 126, 171, 213, 172, 212, 49],
+        accounts: OpenBundledPositionAccounts {
+            bundled_position, position_bundle, position_bundle_token_account,
+            position_bundle_authority, whirlpool, funder, system_program, rent,
+        },
+        args: OpenBundledPositionInstructionArgs,
+    },
+    CloseBundledPosition {
+        discriminator: [41, 36, 216, 245, 27, 85, 103, 67],
+        accounts: CloseBundledPositionAccounts {
+            bundled_position, position_bundle, position_bundle_token_account,
+            position_bundle_authority, receiver,
+        },
+        args: CloseBundledPositionInstructionArgs,
+    },
+    OpenPositionWithTokenExtensions {
+        discriminator: [212, 47, 95, 92, 114, 102, 131, 250],
+        accounts: OpenPositionWithTokenExtensionsAccounts {
+            funder, owner, position, position_mint, position_token_account, whirlpool,
+            token2022_program, system_program, associated_token_program, metadata_update_auth,
+        },
+        args: OpenPositionWithTokenExtensionsInstructionArgs,
+    },
+    ClosePositionWithTokenExtensions {
+        discriminator: [1, 182, 135, 59, 155, 25, 99, 223],
+        accounts: ClosePositionWithTokenExtensionsAccounts {
+            position_authority, receiver, position, position_mint, position_token_account,
+            token2022_program,
+        },
+    },
+    CollectFeesV2 {
+        discriminator: [207, 117, 95, 191, 229, 180, 226, 15],
+        accounts: CollectFeesV2Accounts {
+            whirlpool, position_authority, position, position_token_account, token_mint_a,
+            token_mint_b, token_owner_account_a, token_vault_a, token_owner_account_b,
+            token_vault_b, token_program_a, token_program_b, memo_program,
+        },
+        args: CollectFeesV2InstructionArgs,
+    },
+    CollectProtocolFeesV2 {
+        discriminator: [103, 128, 222, 134, 114, 200, 22, 200],
+        accounts: CollectProtocolFeesV2Accounts {
+            whirlpools_config, whirlpool, collect_protocol_fees_authority, token_mint_a,
+            token_mint_b, token_vault_a, token_vault_b, token_destination_a, token_destination_b,
+            token_program_a, token_program_b, memo_program,
+        },
+        args: CollectProtocolFeesV2InstructionArgs,
+    },
+    CollectRewardV2 {
+        discriminator: [177, 107, 37, 180, 160, 19, 49, 209],
+        accounts: CollectRewardV2Accounts {
+            whirlpool, position_authority, position, position_token_account, reward_owner_account,
+            reward_mint, reward_vault, reward_token_program, memo_program,
+        },
+        args: CollectRewardV2InstructionArgs,
+    },
+    DecreaseLiquidityV2 {
+        discriminator: [58, 127, 188, 62, 79, 82, 196, 96],
+        accounts: DecreaseLiquidityV2Accounts {
+            whirlpool, token_program_a, token_program_b, memo_program, position_authority, position,
+            position_token_account, token_mint_a, token_mint_b, token_owner_account_a,
+            token_owner_account_b, token_vault_a, token_vault_b, tick_array_lower, tick_array_upper,
+        },
+        args: DecreaseLiquidityV2InstructionArgs,
+    },
+    IncreaseLiquidityV2 {
+        discriminator: [133, 29, 89, 223, 69, 238, 176, 10],
+        accounts: IncreaseLiquidityV2Accounts {
+            whirlpool, token_program_a, token_program_b, memo_program, position_authority, position,
+            position_token_account, token_mint_a, token_mint_b, token_owner_account_a,
+            token_owner_account_b, token_vault_a, token_vault_b, tick_array_lower, tick_array_upper,
+        },
+        args: IncreaseLiquidityV2InstructionArgs,
+    },
+    InitializePoolV2 {
+        discriminator: [207, 45, 87, 242, 27, 63, 204, 67],
+        accounts: InitializePoolV2Accounts {
+            whirlpools_config, token_mint_a, token_mint_b, token_badge_a, token_badge_b, funder,
+            whirlpool, token_vault_a, token_vault_b, fee_tier, token_program_a, token_program_b,
+            system_program, rent,
+        },
+        args: InitializePoolV2InstructionArgs,
+    },
+    InitializeRewardV2 {
+        discriminator: [91, 1, 77, 50, 235, 229, 133, 49],
+        accounts: InitializeRewardV2Accounts {
+            reward_authority, funder, whirlpool, reward_mint, reward_token_badge, reward_vault,
+            reward_token_program, system_program, rent,
+        },
+        args: InitializeRewardV2InstructionArgs,
+    },
+    SetRewardEmissionsV2 {
+        discriminator: [114, 228, 72, 32, 193, 48, 160, 102],
+        accounts: SetRewardEmissionsV2Accounts { whirlpool, reward_authority, reward_vault },
+        args: SetRewardEmissionsV2InstructionArgs,
+    },
+    SwapV2 {
+        discriminator: [43, 4, 237, 11, 26, 201, 30, 98],
+        accounts: SwapV2Accounts {
+            token_program_a, token_program_b, memo_program, token_authority, whirlpool,
+            token_mint_a, token_mint_b, token_owner_account_a, token_vault_a, token_owner_account_b,
+            token_vault_b, tick_array0, tick_array1, tick_array2, oracle,
+        },
+        args: SwapV2InstructionArgs,
+    },
+    TwoHopSwapV2 {
+        discriminator: [186, 143, 209, 29, 254, 2, 194, 117],
+        accounts: TwoHopSwapV2Accounts {
+            whirlpool_one, whirlpool_two, token_mint_input, token_mint_intermediate,
+            token_mint_output, token_program_input, token_program_intermediate,
+            token_program_output, token_owner_account_input, token_vault_one_input,
+            token_vault_one_intermediate, token_vault_two_intermediate, token_vault_two_output,
+            token_owner_account_output, token_authority, tick_array_one0, tick_array_one1,
+            tick_array_one2, tick_array_two0, tick_array_two1, tick_array_two2, oracle_one,
+            oracle_two, memo_program,
+        },
+        args: TwoHopSwapV2InstructionArgs,
+    },
+    InitializeConfigExtension {
+        discriminator: [55, 9, 53, 9, 114, 57, 209, 52],
+        accounts: InitializeConfigExtensionAccounts {
+            config, config_extension, funder, fee_authority, system_program,
+        },
+    },
+    SetConfigExtensionAuthority {
+        discriminator: [44, 94, 241, 116, 24, 188, 60, 143],
+        accounts: SetConfigExtensionAuthorityAccounts {
+            whirlpools_config, whirlpools_config_extension, config_extension_authority,
+            new_config_extension_authority,
+        },
+    },
+    SetTokenBadgeAuthority {
+        discriminator: [207, 202, 4, 32, 205, 79, 13, 178],
+        accounts: SetTokenBadgeAuthorityAccounts {
+            whirlpools_config, whirlpools_config_extension, config_extension_authority,
+            new_token_badge_authority,
+        },
+    },
+    InitializeTokenBadge {
+        discriminator: [253, 77, 205, 95, 27, 224, 89, 223],
+        accounts: InitializeTokenBadgeAccounts {
+            whirlpools_config, whirlpools_config_extension, token_badge_authority, token_mint,
+            token_badge, funder, system_program,
+        },
+    },
+    DeleteTokenBadge {
+        discriminator: [53, 146, 68, 8, 18, 117, 17, 185],
+        accounts: DeleteTokenBadgeAccounts {
+            whirlpools_config, whirlpools_config_extension, token_badge_authority, token_mint,
+            token_badge, receiver,
+        },
+    },
+}
+
+impl WhirlpoolInstruction {
+    /// The remaining accounts info of the instruction, only present on the v2 instructions.
+    pub fn remaining_accounts_info(&self) -> Option<&RemainingAccountsInfo> {
+        match self {
+            Self::CollectFeesV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            Self::CollectProtocolFeesV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            Self::CollectRewardV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            Self::DecreaseLiquidityV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            Self::IncreaseLiquidityV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            Self::SwapV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            Self::TwoHopSwapV2 { args, .. } => args.remaining_accounts_info.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded whirlpool instruction together with the accounts passed after the named accounts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedInstruction {
+    pub instruction: WhirlpoolInstruction,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+impl DecodedInstruction {
+    /// Split the remaining accounts into the slices described by the remaining accounts info,
+    /// the same way the program does. Slices with a length of zero are returned empty.
+    ///
+    /// # Returns
+    /// - The type and the accounts of each slice, empty if the instruction has no remaining
+    ///   accounts info
+    pub fn remaining_accounts_slices(
+        &self,
+    ) -> Result<Vec<(AccountsType, &[Pubkey])>, InstructionDecodeError> {
+        let Some(remaining_accounts_info) = self.instruction.remaining_accounts_info() else {
+            return Ok(Vec::new());
+        };
+        let mut remaining_accounts = self.remaining_accounts.as_slice();
+        let mut slices = Vec::with_capacity(remaining_accounts_info.slices.len());
+        for slice in &remaining_accounts_info.slices {
+            let length = slice.length as usize;
+            if length > remaining_accounts.len() {
+                return Err(InstructionDecodeError::InvalidRemainingAccounts);
+            }
+            let (accounts, rest) = remaining_accounts.split_at(length);
+            slices.push((slice.accounts_type, accounts));
+            remaining_accounts = rest;
+        }
+        Ok(slices)
+    }
+}
+
+/// Decode the data and accounts of a whirlpool instruction without checking the program id.
+///
+/// # Parameters
+/// - `account_keys` - The accounts of the instruction, in order
+/// - `data` - The data of the instruction
+///
+/// # Returns
+/// - The decoded instruction
+pub fn decode_instruction_data(
+    account_keys: &[Pubkey],
+    data: &[u8],
+) -> Result<DecodedInstruction, InstructionDecodeError> {
+    let (instruction, remaining_accounts) = WhirlpoolInstruction::decode(account_keys, data)?;
+    Ok(DecodedInstruction {
+        instruction,
+        remaining_accounts: remaining_accounts.to_vec(),
+    })
+}
+
+/// Decode a whirlpool instruction.
+///
+/// # Parameters
+/// - `instruction` - The instruction, which must target the whirlpool program
+///
+/// # Returns
+/// - The decoded instruction
+pub fn decode_instruction(
+    instruction: &Instruction,
+) -> Result<DecodedInstruction, InstructionDecodeError> {
+    if instruction.program_id != WHIRLPOOL_ID {
+        return Err(InstructionDecodeError::InvalidProgramId(
+            instruction.program_id,
+        ));
+    }
+    let account_keys: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|account| account.pubkey)
+        .collect();
+    decode_instruction_data(&account_keys, &instruction.data)
+}
+
+/// Decode a compiled whirlpool instruction, as found in a transaction message or in the inner
+/// instructions of a transaction.
+///
+/// # Parameters
+/// - `account_keys` - The account keys of the transaction, including the loaded addresses
+/// - `instruction` - The compiled instruction, which must target the whirlpool program
+///
+/// # Returns
+/// - The decoded instruction
+pub fn decode_compiled_instruction(
+    account_keys: &[Pubkey],
+    instruction: &CompiledInstruction,
+) -> Result<DecodedInstruction, InstructionDecodeError> {
+    let resolve = |index: u8| {
+        account_keys
+            .get(index as usize)
+            .copied()
+            .ok_or(InstructionDecodeError::InvalidAccountIndex(index))
+    };
+    let program_id = resolve(instruction.program_id_index)?;
+    if program_id != WHIRLPOOL_ID {
+        return Err(InstructionDecodeError::InvalidProgramId(program_id));
+    }
+    let instruction_keys = instruction
+        .accounts
+        .iter()
+        .map(|index| resolve(*index))
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+    decode_instruction_data(&instruction_keys, &instruction.data)
+}
+
+/// Decode all the whirlpool instructions in a list of compiled instructions. Instructions of
+/// other programs are skipped, which makes this suitable for the inner instructions of a
+/// transaction where the whirlpool program is invoked through CPI.
+///
+/// # Parameters
+/// - `account_keys` - The account keys of the transaction, including the loaded addresses
+/// - `instructions` - The compiled instructions
+///
+/// # Returns
+/// - The decoded whirlpool instructions, in order
+pub fn decode_compiled_instructions<'a>(
+    account_keys: &[Pubkey],
+    instructions: impl IntoIterator<Item = &'a CompiledInstruction>,
+) -> Result<Vec<DecodedInstruction>, InstructionDecodeError> {
+    instructions
+        .into_iter()
+        .filter(|instruction| {
+            account_keys.get(instruction.program_id_index as usize) == Some(&WHIRLPOOL_ID)
+        })
+        .map(|instruction| decode_compiled_instruction(account_keys, instruction))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::types::RemainingAccountsSlice;
+    use solana_program::instruction::AccountMeta;
+
+    fn swap_v2() -> SwapV2 {
+        SwapV2 {
+            token_program_a: Pubkey::new_unique(),
+            token_program_b: Pubkey::new_unique(),
+            memo_program: Pubkey::new_unique(),
+            token_authority: Pubkey::new_unique(),
+            whirlpool: Pubkey::new_unique(),
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            token_owner_account_a: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_owner_account_b: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_array0: Pubkey::new_unique(),
+            tick_array1: Pubkey::new_unique(),
+            tick_array2: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+        }
+    }
+
+    fn swap_v2_args(
+        remaining_accounts_info: Option<RemainingAccountsInfo>,
+    ) -> SwapV2InstructionArgs {
+        SwapV2InstructionArgs {
+            amount: 1000,
+            other_amount_threshold: 900,
+            sqrt_price_limit: 1 << 64,
+            amount_specified_is_input: true,
+            a_to_b: false,
+            remaining_accounts_info,
+        }
+    }
+
+    #[test]
+    fn test_decode_instruction() {
+        let accounts = swap_v2();
+        let instruction = accounts.instruction(swap_v2_args(None));
+        let decoded = decode_instruction(&instruction).unwrap();
+
+        assert_eq!(decoded.instruction.name(), "SwapV2");
+        assert!(decoded.remaining_accounts.is_empty());
+        let WhirlpoolInstruction::SwapV2 {
+            accounts: decoded_accounts,
+            args,
+        } = decoded.instruction
+        else {
+            panic!("Expected a SwapV2 instruction");
+        };
+        assert_eq!(decoded_accounts.whirlpool, accounts.whirlpool);
+        assert_eq!(decoded_accounts.tick_array2, accounts.tick_array2);
+        assert_eq!(decoded_accounts.oracle, accounts.oracle);
+        assert_eq!(args, swap_v2_args(None));
+    }
+
+    #[test]
+    fn test_decode_instruction_without_args() {
+        let accounts = UpdateFeesAndRewards {
+            whirlpool: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            tick_array_lower: Pubkey::new_unique(),
+            tick_array_upper: Pubkey::new_unique(),
+        };
+        let decoded = decode_instruction(&accounts.instruction()).unwrap();
+        assert_eq!(
+            decoded.instruction,
+            WhirlpoolInstruction::UpdateFeesAndRewards {
+                accounts: UpdateFeesAndRewardsAccounts {
+                    whirlpool: accounts.whirlpool,
+                    position: accounts.position,
+                    tick_array_lower: accounts.tick_array_lower,
+                    tick_array_upper: accounts.tick_array_upper,
+                },
+            }
+        );
+        assert_eq!(decoded.instruction.remaining_accounts_info(), None);
+    }
+
+    #[test]
+    fn test_decode_instruction_remaining_accounts_slices() {
+        let remaining_accounts_info = RemainingAccountsInfo {
+            slices: vec![
+                RemainingAccountsSlice {
+                    accounts_type: AccountsType::TransferHookA,
+                    length: 2,
+                },
+                RemainingAccountsSlice {
+                    accounts_type: AccountsType::TransferHookB,
+                    length: 0,
+                },
+                RemainingAccountsSlice {
+                    accounts_type: AccountsType::SupplementalTickArrays,
+                    length: 1,
+                },
+            ],
+        };
+        let remaining_accounts: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let remaining_account_metas: Vec<AccountMeta> = remaining_accounts
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false))
+            .collect();
+        let instruction = swap_v2().instruction_with_remaining_accounts(
+            swap_v2_args(Some(remaining_accounts_info)),
+            &remaining_account_metas,
+        );
+        let decoded = decode_instruction(&instruction).unwrap();
+
+        assert_eq!(decoded.remaining_accounts, remaining_accounts);
+        assert_eq!(
+            decoded.remaining_accounts_slices().unwrap(),
+            vec![
+                (AccountsType::TransferHookA, &remaining_accounts[0..2]),
+                (AccountsType::TransferHookB, &remaining_accounts[2..2]),
+                (
+                    AccountsType::SupplementalTickArrays,
+                    &remaining_accounts[2..3]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_instruction_insufficient_remaining_accounts() {
+        let remaining_accounts_info = RemainingAccountsInfo {
+            slices: vec![RemainingAccountsSlice {
+                accounts_type: AccountsType::TransferHookA,
+                length: 2,
+            }],
+        };
+        let instruction = swap_v2().instruction_with_remaining_accounts(
+            swap_v2_args(Some(remaining_accounts_info)),
+            &[AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+        );
+        let decoded = decode_instruction(&instruction).unwrap();
+        assert!(matches!(
+            decoded.remaining_accounts_slices(),
+            Err(InstructionDecodeError::InvalidRemainingAccounts)
+        ));
+    }
+
+    #[test]
+    fn test_decode_instruction_errors() {
+        let mut instruction = swap_v2().instruction(swap_v2_args(None));
+        instruction.program_id = Pubkey::new_unique();
+        assert!(matches!(
+            decode_instruction(&instruction),
+            Err(InstructionDecodeError::InvalidProgramId(_))
+        ));
+
+        assert!(matches!(
+            decode_instruction_data(&[], &[1, 2, 3]),
+            Err(InstructionDecodeError::MissingDiscriminator)
+        ));
+        assert!(matches!(
+            decode_instruction_data(&[], &[0; 8]),
+            Err(InstructionDecodeError::UnknownDiscriminator(discriminator)) if discriminator == [0; 8]
+        ));
+
+        let instruction = swap_v2().instruction(swap_v2_args(None));
+        let account_keys: Vec<Pubkey> = instruction.accounts[..14]
+            .iter()
+            .map(|account| account.pubkey)
+            .collect();
+        assert!(matches!(
+            decode_instruction_data(&account_keys, &instruction.data),
+            Err(InstructionDecodeError::NotEnoughAccounts {
+                name: "SwapV2",
+                expected: 15,
+                actual: 14,
+            })
+        ));
+        assert!(matches!(
+            decode_instruction_data(&account_keys, &instruction.data[..20]),
+            Err(InstructionDecodeError::NotEnoughAccounts { .. })
+        ));
+        let account_keys: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .map(|account| account.pubkey)
+            .collect();
+        assert!(matches!(
+            decode_instruction_data(&account_keys, &instruction.data[..20]),
+            Err(InstructionDecodeError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_compiled_instructions() {
+        let instruction = swap_v2().instruction(swap_v2_args(None));
+        let other_program = Pubkey::new_unique();
+        let mut account_keys = vec![WHIRLPOOL_ID, other_program];
+        account_keys.extend(instruction.accounts.iter().map(|account| account.pubkey));
+        let whirlpool_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: (2..17).collect(),
+            data: instruction.data.clone(),
+        };
+        let other_instruction = CompiledInstruction {
+            program_id_index: 1,
+            accounts: vec![2],
+            data: vec![1, 2, 3],
+        };
+
+        let decoded = decode_compiled_instructions(
+            &account_keys,
+            [&other_instruction, &whirlpool_instruction],
+        )
+        .unwrap();
+        assert_eq!(decoded, vec![decode_instruction(&instruction).unwrap()]);
+
+        assert!(matches!(
+            decode_compiled_instruction(&account_keys, &other_instruction),
+            Err(InstructionDecodeError::InvalidProgramId(program_id)) if program_id == other_program
+        ));
+        let invalid_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![2, 100],
+            data: instruction.data,
+        };
+        assert!(matches!(
+            decode_compiled_instruction(&account_keys, &invalid_instruction),
+            Err(InstructionDecodeError::InvalidAccountIndex(100))
+        ));
+    }
+}
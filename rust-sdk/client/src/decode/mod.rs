@@ -0,0 +1,5 @@
+mod account;
+mod instruction;
+
+pub use account::*;
+pub use instruction::*;
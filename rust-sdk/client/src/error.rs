@@ -0,0 +1,178 @@
+use num_traits::FromPrimitive;
+use solana_program::instruction::InstructionError;
+
+use crate::generated::errors::WhirlpoolError;
+
+#[cfg(feature = "fetch")]
+use solana_sdk::transaction::TransactionError;
+
+/// How a client should react to an error of the whirlpool program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhirlpoolErrorKind {
+    /// The accounts changed between building and executing the transaction, for example because
+    /// the price moved to other tick arrays. Rebuilding the transaction can succeed.
+    Retryable,
+    /// The price moved beyond the slippage tolerance of the transaction.
+    Slippage,
+    /// The transaction is invalid and keeps failing until its inputs change.
+    User,
+}
+
+impl WhirlpoolError {
+    /// The custom program error code of the error.
+    pub fn code(&self) -> u32 {
+        self.clone() as u32
+    }
+
+    /// Classify the error by how a client should react to it.
+    pub fn kind(&self) -> WhirlpoolErrorKind {
+        match self {
+            WhirlpoolError::TokenMaxExceeded
+            | WhirlpoolError::TokenMinSubceeded
+            | WhirlpoolError::AmountOutBelowMinimum
+            | WhirlpoolError::AmountInAboveMaximum
+            | WhirlpoolError::PartialFillError => WhirlpoolErrorKind::Slippage,
+            WhirlpoolError::TickArrayExistInPool
+            | WhirlpoolError::TickArrayIndexOutofBounds
+            | WhirlpoolError::InvalidTickArraySequence
+            | WhirlpoolError::TickArraySequenceInvalidIndex
+            | WhirlpoolError::InvalidTimestamp => WhirlpoolErrorKind::Retryable,
+            _ => WhirlpoolErrorKind::User,
+        }
+    }
+}
+
+impl TryFrom<u32> for WhirlpoolError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        WhirlpoolError::from_u32(code).ok_or(code)
+    }
+}
+
+/// Extract the whirlpool error from the error of an instruction.
+///
+/// The custom error codes of the whirlpool program overlap with those of other Anchor programs,
+/// so the instruction that failed should target the whirlpool program, directly or through CPI.
+///
+/// # Parameters
+/// - `error` - The error of the instruction
+///
+/// # Returns
+/// - The whirlpool error, `None` if the error is not a custom error of the whirlpool program
+pub fn get_instruction_whirlpool_error(error: &InstructionError) -> Option<WhirlpoolError> {
+    match error {
+        InstructionError::Custom(code) => WhirlpoolError::try_from(*code).ok(),
+        _ => None,
+    }
+}
+
+/// Extract the whirlpool error from the error of a failed transaction.
+///
+/// The custom error codes of the whirlpool program overlap with those of other Anchor programs,
+/// so the instruction at the returned index should target the whirlpool program, directly or
+/// through CPI.
+///
+/// # Parameters
+/// - `error` - The error of the transaction
+///
+/// # Returns
+/// - The index of the instruction that failed and the whirlpool error, `None` if the
+///   transaction did not fail with a custom error of the whirlpool program
+#[cfg(feature = "fetch")]
+pub fn get_transaction_whirlpool_error(error: &TransactionError) -> Option<(u8, WhirlpoolError)> {
+    match error {
+        TransactionError::InstructionError(index, error) => {
+            get_instruction_whirlpool_error(error).map(|error| (*index, error))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whirlpool_error_try_from() {
+        assert_eq!(
+            WhirlpoolError::try_from(6000),
+            Ok(WhirlpoolError::InvalidEnum)
+        );
+        assert_eq!(
+            WhirlpoolError::try_from(6023),
+            Ok(WhirlpoolError::InvalidTickArraySequence)
+        );
+        assert_eq!(
+            WhirlpoolError::try_from(6057),
+            Ok(WhirlpoolError::PartialFillError)
+        );
+        assert_eq!(WhirlpoolError::try_from(5999), Err(5999));
+        assert_eq!(WhirlpoolError::try_from(6058), Err(6058));
+        for code in 6000..=6057 {
+            assert_eq!(WhirlpoolError::try_from(code).unwrap().code(), code);
+        }
+    }
+
+    #[test]
+    fn test_whirlpool_error_message() {
+        assert_eq!(
+            WhirlpoolError::AmountOutBelowMinimum.to_string(),
+            "Amount out below minimum threshold"
+        );
+    }
+
+    #[test]
+    fn test_whirlpool_error_kind() {
+        assert_eq!(
+            WhirlpoolError::AmountOutBelowMinimum.kind(),
+            WhirlpoolErrorKind::Slippage
+        );
+        assert_eq!(
+            WhirlpoolError::TokenMaxExceeded.kind(),
+            WhirlpoolErrorKind::Slippage
+        );
+        assert_eq!(
+            WhirlpoolError::InvalidTickArraySequence.kind(),
+            WhirlpoolErrorKind::Retryable
+        );
+        assert_eq!(
+            WhirlpoolError::InvalidTickSpacing.kind(),
+            WhirlpoolErrorKind::User
+        );
+        assert_eq!(
+            WhirlpoolError::ZeroTradableAmount.kind(),
+            WhirlpoolErrorKind::User
+        );
+    }
+
+    #[test]
+    fn test_get_instruction_whirlpool_error() {
+        assert_eq!(
+            get_instruction_whirlpool_error(&InstructionError::Custom(6036)),
+            Some(WhirlpoolError::AmountOutBelowMinimum)
+        );
+        assert_eq!(
+            get_instruction_whirlpool_error(&InstructionError::Custom(1)),
+            None
+        );
+        assert_eq!(
+            get_instruction_whirlpool_error(&InstructionError::InvalidAccountData),
+            None
+        );
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_get_transaction_whirlpool_error() {
+        let error = TransactionError::InstructionError(2, InstructionError::Custom(6057));
+        assert_eq!(
+            get_transaction_whirlpool_error(&error),
+            Some((2, WhirlpoolError::PartialFillError))
+        );
+        assert_eq!(
+            get_transaction_whirlpool_error(&TransactionError::AccountInUse),
+            None
+        );
+    }
+}
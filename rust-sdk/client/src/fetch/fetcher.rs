@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::RpcClient;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use thiserror::Error;
+
+use crate::AccountDecodeError;
+
+/// The maximum number of accounts that can be requested in a single `getMultipleAccounts` call.
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("Account {0} does not exist")]
+    AccountNotFound(Pubkey),
+    #[error("Account {0} is not owned by the expected program")]
+    InvalidOwner(Pubkey),
+    #[error("Account {0} could not be decoded: {1}")]
+    InvalidData(Pubkey, AccountDecodeError),
+    #[error("Account {address} is a {actual} account, not a {expected} account")]
+    UnexpectedAccountType {
+        address: Pubkey,
+        expected: &'static str,
+        actual: &'static str,
+    },
+    #[error(transparent)]
+    Program(#[from] ProgramError),
+    #[error(transparent)]
+    Rpc(Box<ClientError>),
+}
+
+impl From<ClientError> for FetchError {
+    fn from(error: ClientError) -> Self {
+        FetchError::Rpc(Box::new(error))
+    }
+}
+
+/// A source of on-chain accounts.
+///
+/// Implemented for `RpcClient` to fetch accounts from a cluster and for `HashMap<Pubkey, Account>`
+/// to serve accounts from memory, for example in tests.
+pub trait AccountFetcher {
+    /// Fetch multiple accounts at once.
+    ///
+    /// # Parameters
+    /// - `addresses` - The addresses of the accounts to fetch
+    ///
+    /// # Returns
+    /// - The accounts in the same order as the addresses, `None` for accounts that do not exist
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, FetchError>;
+
+    /// Fetch a single account.
+    ///
+    /// # Parameters
+    /// - `address` - The address of the account to fetch
+    ///
+    /// # Returns
+    /// - The account, `None` if it does not exist
+    fn get_account(&self, address: &Pubkey) -> Result<Option<Account>, FetchError> {
+        let mut accounts = self.get_multiple_accounts(&[*address])?;
+        Ok(accounts.pop().flatten())
+    }
+}
+
+impl AccountFetcher for RpcClient {
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, FetchError> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+            accounts.extend(RpcClient::get_multiple_accounts(self, chunk)?);
+        }
+        Ok(accounts)
+    }
+}
+
+impl AccountFetcher for HashMap<Pubkey, Account> {
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, FetchError> {
+        Ok(addresses
+            .iter()
+            .map(|address| self.get(address).cloned())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_account(lamports: u64) -> Account {
+        Account {
+            lamports,
+            data: vec![],
+            owner: Pubkey::default(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_get_multiple_accounts_from_memory() {
+        let address_1 = Pubkey::new_unique();
+        let address_2 = Pubkey::new_unique();
+        let missing = Pubkey::new_unique();
+        let accounts = HashMap::from([(address_1, test_account(1)), (address_2, test_account(2))]);
+
+        let result = accounts
+            .get_multiple_accounts(&[address_2, missing, address_1])
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![Some(test_account(2)), None, Some(test_account(1))]
+        );
+    }
+
+    #[test]
+    fn test_get_account_from_memory() {
+        let address = Pubkey::new_unique();
+        let accounts = HashMap::from([(address, test_account(1))]);
+
+        assert_eq!(
+            accounts.get_account(&address).unwrap(),
+            Some(test_account(1))
+        );
+        assert_eq!(accounts.get_account(&Pubkey::new_unique()).unwrap(), None);
+    }
+}
@@ -0,0 +1,5 @@
+mod fetcher;
+mod whirlpool;
+
+pub use fetcher::*;
+pub use whirlpool::*;
@@ -0,0 +1,332 @@
+use orca_whirlpools_core::{
+    get_tick_array_start_tick_index, TickArrayFacade, TickArrays, TickFacade, WhirlpoolFacade,
+    TICK_ARRAY_SIZE,
+};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+
+use crate::accounts::{Position, PositionBundle, TickArray, Whirlpool};
+use crate::{
+    decode_whirlpool_account, get_tick_array_address, AccountDecodeError, WhirlpoolAccount,
+};
+
+use super::{AccountFetcher, FetchError};
+
+/// The number of tick arrays fetched around the current tick index of a whirlpool.
+const SWAP_TICK_ARRAY_COUNT: usize = 5;
+
+fn decode_account<T>(
+    address: &Pubkey,
+    account: Option<Account>,
+    expected: &'static str,
+    extract: impl FnOnce(WhirlpoolAccount) -> Option<T>,
+) -> Result<T, FetchError> {
+    let account = account.ok_or(FetchError::AccountNotFound(*address))?;
+    let decoded =
+        decode_whirlpool_account(&account.owner, &account.data).map_err(|error| match error {
+            AccountDecodeError::InvalidOwner(_) => FetchError::InvalidOwner(*address),
+            error => FetchError::InvalidData(*address, error),
+        })?;
+    let actual = decoded.name();
+    extract(decoded).ok_or(FetchError::UnexpectedAccountType {
+        address: *address,
+        expected,
+        actual,
+    })
+}
+
+fn decode_tick_array(address: &Pubkey, account: Option<Account>) -> Result<TickArray, FetchError> {
+    decode_account(address, account, "TickArray", |account| match account {
+        WhirlpoolAccount::TickArray(tick_array) => Some(*tick_array),
+        _ => None,
+    })
+}
+
+/// Fetch and decode a whirlpool account.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `address` - The address of the whirlpool
+///
+/// # Returns
+/// - The decoded whirlpool
+pub fn fetch_whirlpool<F: AccountFetcher>(
+    fetcher: &F,
+    address: &Pubkey,
+) -> Result<Whirlpool, FetchError> {
+    let account = fetcher.get_account(address)?;
+    decode_account(address, account, "Whirlpool", |account| match account {
+        WhirlpoolAccount::Whirlpool(whirlpool) => Some(*whirlpool),
+        _ => None,
+    })
+}
+
+/// Fetch and decode a position account.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `address` - The address of the position
+///
+/// # Returns
+/// - The decoded position
+pub fn fetch_position<F: AccountFetcher>(
+    fetcher: &F,
+    address: &Pubkey,
+) -> Result<Position, FetchError> {
+    let account = fetcher.get_account(address)?;
+    decode_account(address, account, "Position", |account| match account {
+        WhirlpoolAccount::Position(position) => Some(position),
+        _ => None,
+    })
+}
+
+/// Fetch and decode a tick array account.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `address` - The address of the tick array
+///
+/// # Returns
+/// - The decoded tick array
+pub fn fetch_tick_array<F: AccountFetcher>(
+    fetcher: &F,
+    address: &Pubkey,
+) -> Result<TickArray, FetchError> {
+    let account = fetcher.get_account(address)?;
+    decode_tick_array(address, account)
+}
+
+/// Fetch and decode a position bundle account.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `address` - The address of the position bundle
+///
+/// # Returns
+/// - The decoded position bundle
+pub fn fetch_position_bundle<F: AccountFetcher>(
+    fetcher: &F,
+    address: &Pubkey,
+) -> Result<PositionBundle, FetchError> {
+    let account = fetcher.get_account(address)?;
+    decode_account(
+        address,
+        account,
+        "PositionBundle",
+        |account| match account {
+            WhirlpoolAccount::PositionBundle(position_bundle) => Some(position_bundle),
+            _ => None,
+        },
+    )
+}
+
+/// Fetch the tick arrays around the current tick index of a whirlpool: the tick array that
+/// contains the current tick index and the two tick arrays on either side of it.
+/// Tick arrays that are not initialized are returned without any initialized ticks.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `whirlpool_address` - The address of the whirlpool
+/// - `whirlpool` - The whirlpool
+///
+/// # Returns
+/// - The addresses and the tick arrays
+pub fn fetch_tick_arrays_or_default<F: AccountFetcher>(
+    fetcher: &F,
+    whirlpool_address: &Pubkey,
+    whirlpool: &Whirlpool,
+) -> Result<[(Pubkey, TickArrayFacade); SWAP_TICK_ARRAY_COUNT], FetchError> {
+    let start_tick_index =
+        get_tick_array_start_tick_index(whirlpool.tick_current_index, whirlpool.tick_spacing);
+    let offset = whirlpool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let start_tick_indexes = [
+        start_tick_index,
+        start_tick_index + offset,
+        start_tick_index + offset * 2,
+        start_tick_index - offset,
+        start_tick_index - offset * 2,
+    ];
+
+    let addresses = start_tick_indexes
+        .iter()
+        .map(|start_tick_index| {
+            get_tick_array_address(whirlpool_address, *start_tick_index).map(|(address, _)| address)
+        })
+        .collect::<Result<Vec<Pubkey>, _>>()?;
+    let accounts = fetcher.get_multiple_accounts(&addresses)?;
+
+    let tick_arrays = accounts
+        .into_iter()
+        .zip(&addresses)
+        .map(|(account, address)| {
+            account
+                .map(|account| decode_tick_array(address, Some(account)))
+                .transpose()
+                .map(|tick_array| tick_array.map(TickArrayFacade::from))
+        })
+        .collect::<Result<Vec<Option<TickArrayFacade>>, _>>()?;
+
+    Ok(core::array::from_fn(|i| {
+        let tick_array = tick_arrays[i].unwrap_or(TickArrayFacade {
+            start_tick_index: start_tick_indexes[i],
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        });
+        (addresses[i], tick_array)
+    }))
+}
+
+/// Fetch a whirlpool together with all the tick arrays needed to quote a swap.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `address` - The address of the whirlpool
+///
+/// # Returns
+/// - The whirlpool and the tick arrays, ready to be passed to the swap quote functions
+pub fn fetch_whirlpool_with_tick_arrays<F: AccountFetcher>(
+    fetcher: &F,
+    address: &Pubkey,
+) -> Result<(WhirlpoolFacade, TickArrays), FetchError> {
+    let whirlpool = fetch_whirlpool(fetcher, address)?;
+    let tick_arrays = fetch_tick_arrays_or_default(fetcher, address, &whirlpool)?;
+    let tick_arrays = tick_arrays.map(|(_, tick_array)| tick_array);
+    Ok((whirlpool.into(), tick_arrays.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::decode::{
+        POSITION_DISCRIMINATOR, TICK_ARRAY_DISCRIMINATOR, WHIRLPOOL_DISCRIMINATOR,
+    };
+    use crate::test_accounts::{test_account, zeroed_account};
+
+    fn whirlpool_account(tick_spacing: u16, sqrt_price: u128, tick_current_index: i32) -> Account {
+        let mut whirlpool: Whirlpool = zeroed_account(WHIRLPOOL_DISCRIMINATOR, Whirlpool::LEN);
+        whirlpool.tick_spacing = tick_spacing;
+        whirlpool.tick_spacing_seed = tick_spacing.to_le_bytes();
+        whirlpool.liquidity = 1000;
+        whirlpool.sqrt_price = sqrt_price;
+        whirlpool.tick_current_index = tick_current_index;
+        test_account(&whirlpool, Whirlpool::LEN)
+    }
+
+    fn tick_array_account(
+        whirlpool: &Pubkey,
+        start_tick_index: i32,
+        liquidity_net: i128,
+    ) -> Account {
+        let mut tick_array: TickArray = zeroed_account(TICK_ARRAY_DISCRIMINATOR, TickArray::LEN);
+        tick_array.start_tick_index = start_tick_index;
+        tick_array.ticks[0].initialized = true;
+        tick_array.ticks[0].liquidity_net = liquidity_net;
+        tick_array.whirlpool = *whirlpool;
+        test_account(&tick_array, TickArray::LEN)
+    }
+
+    #[test]
+    fn test_fetch_whirlpool() {
+        let address = Pubkey::new_unique();
+        let accounts = HashMap::from([(address, whirlpool_account(64, 1 << 64, 0))]);
+        let whirlpool = fetch_whirlpool(&accounts, &address).unwrap();
+        assert_eq!(whirlpool.tick_spacing, 64);
+        assert_eq!(whirlpool.liquidity, 1000);
+        assert_eq!(whirlpool.sqrt_price, 1 << 64);
+    }
+
+    #[test]
+    fn test_fetch_whirlpool_not_found() {
+        let accounts: HashMap<Pubkey, Account> = HashMap::new();
+        let result = fetch_whirlpool(&accounts, &Pubkey::new_unique());
+        assert!(matches!(result, Err(FetchError::AccountNotFound(_))));
+    }
+
+    #[test]
+    fn test_fetch_whirlpool_invalid_owner() {
+        let address = Pubkey::new_unique();
+        let mut account = whirlpool_account(64, 1 << 64, 0);
+        account.owner = Pubkey::new_unique();
+        let accounts = HashMap::from([(address, account)]);
+        let result = fetch_whirlpool(&accounts, &address);
+        assert!(matches!(result, Err(FetchError::InvalidOwner(_))));
+    }
+
+    #[test]
+    fn test_fetch_whirlpool_other_account_type() {
+        let address = Pubkey::new_unique();
+        let position: Position = zeroed_account(POSITION_DISCRIMINATOR, Position::LEN);
+        let accounts = HashMap::from([(address, test_account(&position, Position::LEN))]);
+        let result = fetch_whirlpool(&accounts, &address);
+        assert!(matches!(
+            result,
+            Err(FetchError::UnexpectedAccountType {
+                expected: "Whirlpool",
+                actual: "Position",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_fetch_whirlpool_invalid_size() {
+        let address = Pubkey::new_unique();
+        let mut account = whirlpool_account(64, 1 << 64, 0);
+        account.data.truncate(600);
+        let accounts = HashMap::from([(address, account)]);
+        let result = fetch_whirlpool(&accounts, &address);
+        assert!(matches!(
+            result,
+            Err(FetchError::InvalidData(
+                _,
+                AccountDecodeError::InvalidSize { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_fetch_tick_array() {
+        let whirlpool = Pubkey::new_unique();
+        let (address, _) = get_tick_array_address(&whirlpool, 5632).unwrap();
+        let accounts = HashMap::from([(address, tick_array_account(&whirlpool, 5632, -500))]);
+        let tick_array = fetch_tick_array(&accounts, &address).unwrap();
+        assert_eq!(tick_array.start_tick_index, 5632);
+        assert_eq!(tick_array.whirlpool, whirlpool);
+        assert_eq!(tick_array.ticks[0].liquidity_net, -500);
+    }
+
+    #[test]
+    fn test_fetch_whirlpool_with_tick_arrays() {
+        let address = Pubkey::new_unique();
+        let (tick_array_address, _) = get_tick_array_address(&address, -5632).unwrap();
+        let accounts = HashMap::from([
+            (address, whirlpool_account(64, 1 << 64, 0)),
+            (tick_array_address, tick_array_account(&address, -5632, 500)),
+        ]);
+
+        let (whirlpool, tick_arrays) =
+            fetch_whirlpool_with_tick_arrays(&accounts, &address).unwrap();
+        assert_eq!(whirlpool.tick_spacing, 64);
+        assert_eq!(whirlpool.tick_current_index, 0);
+
+        let tick_arrays: [Option<TickArrayFacade>; 6] = tick_arrays.into();
+        let start_tick_indexes: Vec<i32> = tick_arrays
+            .iter()
+            .map(|tick_array| tick_array.as_ref().map_or(i32::MAX, |x| x.start_tick_index))
+            .collect();
+        assert_eq!(
+            start_tick_indexes,
+            vec![0, 5632, 11264, -5632, -11264, i32::MAX]
+        );
+
+        let initialized = tick_arrays[3].unwrap().ticks[0];
+        assert!(initialized.initialized);
+        assert_eq!(initialized.liquidity_net, 500);
+        assert!(tick_arrays[0]
+            .unwrap()
+            .ticks
+            .iter()
+            .all(|tick| !tick.initialized));
+    }
+}
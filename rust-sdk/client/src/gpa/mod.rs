@@ -0,0 +1,10 @@
+mod position;
+mod tick_array;
+mod token_badge;
+mod utils;
+mod whirlpool;
+
+pub use position::*;
+pub use tick_array::*;
+pub use token_badge::*;
+pub use whirlpool::*;
@@ -0,0 +1,98 @@
+use std::mem::size_of;
+
+use solana_client::rpc_filter::RpcFilterType;
+use solana_program::pubkey::Pubkey;
+
+use crate::accounts::Position;
+use crate::decode::{DISCRIMINATOR_LEN, POSITION_DISCRIMINATOR};
+
+use super::utils::{account_filters, field_filter};
+
+// The offsets of the fields in the account data, from the sizes of the fields before them in the
+// `Position` layout
+const WHIRLPOOL_OFFSET: usize = DISCRIMINATOR_LEN;
+// whirlpool
+const POSITION_MINT_OFFSET: usize = WHIRLPOOL_OFFSET + size_of::<Pubkey>();
+// position_mint, liquidity
+const TICK_LOWER_INDEX_OFFSET: usize =
+    POSITION_MINT_OFFSET + size_of::<Pubkey>() + size_of::<u128>();
+// tick_lower_index
+const TICK_UPPER_INDEX_OFFSET: usize = TICK_LOWER_INDEX_OFFSET + size_of::<i32>();
+
+/// A filter on a field of position accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionFilter {
+    Whirlpool(Pubkey),
+    PositionMint(Pubkey),
+    TickLowerIndex(i32),
+    TickUpperIndex(i32),
+}
+
+impl From<PositionFilter> for RpcFilterType {
+    fn from(filter: PositionFilter) -> Self {
+        match filter {
+            PositionFilter::Whirlpool(address) => field_filter(WHIRLPOOL_OFFSET, address.as_ref()),
+            PositionFilter::PositionMint(address) => {
+                field_filter(POSITION_MINT_OFFSET, address.as_ref())
+            }
+            PositionFilter::TickLowerIndex(tick_index) => {
+                field_filter(TICK_LOWER_INDEX_OFFSET, &tick_index.to_le_bytes())
+            }
+            PositionFilter::TickUpperIndex(tick_index) => {
+                field_filter(TICK_UPPER_INDEX_OFFSET, &tick_index.to_le_bytes())
+            }
+        }
+    }
+}
+
+/// Build the `getProgramAccounts` filters that select the positions matching all of the given
+/// filters.
+///
+/// # Parameters
+/// - `filters` - The filters on the fields of the position
+///
+/// # Returns
+/// - The filters to pass to `getProgramAccounts` for the whirlpool program
+pub fn position_filters(filters: &[PositionFilter]) -> Vec<RpcFilterType> {
+    account_filters(
+        POSITION_DISCRIMINATOR,
+        Position::LEN,
+        filters.iter().map(|filter| (*filter).into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_accounts::{test_account, zeroed_account};
+    use solana_sdk::account::AccountSharedData;
+
+    #[test]
+    fn test_position_filters() {
+        let mut position: Position = zeroed_account(POSITION_DISCRIMINATOR, Position::LEN);
+        position.whirlpool = Pubkey::new_unique();
+        position.position_mint = Pubkey::new_unique();
+        position.liquidity = 1000;
+        position.tick_lower_index = -128;
+        position.tick_upper_index = 256;
+        let account = AccountSharedData::from(test_account(&position, Position::LEN));
+        let matches = |filters: &[PositionFilter]| {
+            position_filters(filters)
+                .iter()
+                .all(|filter| filter.verify().is_ok() && filter.allows(&account))
+        };
+
+        assert!(matches(&[PositionFilter::Whirlpool(position.whirlpool)]));
+        assert!(matches(&[PositionFilter::PositionMint(
+            position.position_mint
+        )]));
+        assert!(matches(&[
+            PositionFilter::TickLowerIndex(-128),
+            PositionFilter::TickUpperIndex(256),
+        ]));
+        assert!(!matches(&[PositionFilter::Whirlpool(
+            position.position_mint
+        )]));
+        assert!(!matches(&[PositionFilter::TickUpperIndex(-128)]));
+    }
+}
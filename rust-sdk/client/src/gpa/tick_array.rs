@@ -0,0 +1,74 @@
+use std::mem::size_of;
+
+use solana_client::rpc_filter::RpcFilterType;
+use solana_program::pubkey::Pubkey;
+
+use crate::accounts::TickArray;
+use crate::decode::{DISCRIMINATOR_LEN, TICK_ARRAY_DISCRIMINATOR};
+
+use super::utils::{account_filters, field_filter};
+
+const START_TICK_INDEX_OFFSET: usize = DISCRIMINATOR_LEN;
+// The whirlpool is stored after the ticks, as the last field of the tick array
+const WHIRLPOOL_OFFSET: usize = TickArray::LEN - size_of::<Pubkey>();
+
+/// A filter on a field of tick array accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickArrayFilter {
+    StartTickIndex(i32),
+    Whirlpool(Pubkey),
+}
+
+impl From<TickArrayFilter> for RpcFilterType {
+    fn from(filter: TickArrayFilter) -> Self {
+        match filter {
+            TickArrayFilter::StartTickIndex(start_tick_index) => {
+                field_filter(START_TICK_INDEX_OFFSET, &start_tick_index.to_le_bytes())
+            }
+            TickArrayFilter::Whirlpool(address) => field_filter(WHIRLPOOL_OFFSET, address.as_ref()),
+        }
+    }
+}
+
+/// Build the `getProgramAccounts` filters that select the tick arrays matching all of the given
+/// filters.
+///
+/// # Parameters
+/// - `filters` - The filters on the fields of the tick array
+///
+/// # Returns
+/// - The filters to pass to `getProgramAccounts` for the whirlpool program
+pub fn tick_array_filters(filters: &[TickArrayFilter]) -> Vec<RpcFilterType> {
+    account_filters(
+        TICK_ARRAY_DISCRIMINATOR,
+        TickArray::LEN,
+        filters.iter().map(|filter| (*filter).into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_accounts::{test_account, zeroed_account};
+    use solana_sdk::account::AccountSharedData;
+
+    #[test]
+    fn test_tick_array_filters() {
+        let mut tick_array: TickArray = zeroed_account(TICK_ARRAY_DISCRIMINATOR, TickArray::LEN);
+        tick_array.start_tick_index = -5632;
+        tick_array.whirlpool = Pubkey::new_unique();
+        let account = AccountSharedData::from(test_account(&tick_array, TickArray::LEN));
+        let matches = |filters: &[TickArrayFilter]| {
+            tick_array_filters(filters)
+                .iter()
+                .all(|filter| filter.verify().is_ok() && filter.allows(&account))
+        };
+
+        assert!(matches(&[TickArrayFilter::Whirlpool(tick_array.whirlpool)]));
+        assert!(matches(&[TickArrayFilter::StartTickIndex(-5632)]));
+        assert!(!matches(&[
+            TickArrayFilter::Whirlpool(Pubkey::new_unique())
+        ]));
+        assert!(!matches(&[TickArrayFilter::StartTickIndex(0)]));
+    }
+}
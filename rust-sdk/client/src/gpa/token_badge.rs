@@ -0,0 +1,83 @@
+use std::mem::size_of;
+
+use solana_client::rpc_filter::RpcFilterType;
+use solana_program::pubkey::Pubkey;
+
+use crate::accounts::TokenBadge;
+use crate::decode::{DISCRIMINATOR_LEN, TOKEN_BADGE_DISCRIMINATOR};
+
+use super::utils::{account_filters, field_filter};
+
+// The offsets of the fields in the account data, from the sizes of the fields before them in the
+// `TokenBadge` layout
+const WHIRLPOOLS_CONFIG_OFFSET: usize = DISCRIMINATOR_LEN;
+// whirlpools_config
+const TOKEN_MINT_OFFSET: usize = WHIRLPOOLS_CONFIG_OFFSET + size_of::<Pubkey>();
+
+/// A filter on a field of token badge accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenBadgeFilter {
+    WhirlpoolsConfig(Pubkey),
+    TokenMint(Pubkey),
+}
+
+impl From<TokenBadgeFilter> for RpcFilterType {
+    fn from(filter: TokenBadgeFilter) -> Self {
+        match filter {
+            TokenBadgeFilter::WhirlpoolsConfig(address) => {
+                field_filter(WHIRLPOOLS_CONFIG_OFFSET, address.as_ref())
+            }
+            TokenBadgeFilter::TokenMint(address) => {
+                field_filter(TOKEN_MINT_OFFSET, address.as_ref())
+            }
+        }
+    }
+}
+
+/// Build the `getProgramAccounts` filters that select the token badges matching all of the given
+/// filters.
+///
+/// # Parameters
+/// - `filters` - The filters on the fields of the token badge
+///
+/// # Returns
+/// - The filters to pass to `getProgramAccounts` for the whirlpool program
+pub fn token_badge_filters(filters: &[TokenBadgeFilter]) -> Vec<RpcFilterType> {
+    account_filters(
+        TOKEN_BADGE_DISCRIMINATOR,
+        TokenBadge::LEN,
+        filters.iter().map(|filter| (*filter).into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_accounts::{test_account, zeroed_account};
+    use solana_sdk::account::AccountSharedData;
+
+    #[test]
+    fn test_token_badge_filters() {
+        let mut token_badge: TokenBadge =
+            zeroed_account(TOKEN_BADGE_DISCRIMINATOR, TokenBadge::LEN);
+        token_badge.whirlpools_config = Pubkey::new_unique();
+        token_badge.token_mint = Pubkey::new_unique();
+        // The token badge is padded with reserved space after its fields
+        let account = AccountSharedData::from(test_account(&token_badge, TokenBadge::LEN));
+        let matches = |filters: &[TokenBadgeFilter]| {
+            token_badge_filters(filters)
+                .iter()
+                .all(|filter| filter.verify().is_ok() && filter.allows(&account))
+        };
+
+        assert!(matches(&[TokenBadgeFilter::WhirlpoolsConfig(
+            token_badge.whirlpools_config
+        )]));
+        assert!(matches(&[TokenBadgeFilter::TokenMint(
+            token_badge.token_mint
+        )]));
+        assert!(!matches(&[TokenBadgeFilter::WhirlpoolsConfig(
+            token_badge.token_mint
+        )]));
+    }
+}
@@ -0,0 +1,21 @@
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+/// Build the filters that select the accounts of one type: the size of the account, its
+/// discriminator and the field filters.
+pub(crate) fn account_filters(
+    discriminator: [u8; 8],
+    len: usize,
+    filters: impl IntoIterator<Item = RpcFilterType>,
+) -> Vec<RpcFilterType> {
+    let mut account_filters = vec![
+        RpcFilterType::DataSize(len as u64),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, discriminator.to_vec())),
+    ];
+    account_filters.extend(filters);
+    account_filters
+}
+
+/// A filter that matches the bytes of an account field at the given offset.
+pub(crate) fn field_filter(offset: usize, bytes: &[u8]) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, bytes.to_vec()))
+}
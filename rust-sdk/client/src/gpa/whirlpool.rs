@@ -0,0 +1,162 @@
+use std::mem::size_of;
+
+use solana_client::rpc_filter::RpcFilterType;
+use solana_program::pubkey::Pubkey;
+
+use crate::accounts::Whirlpool;
+use crate::decode::{DISCRIMINATOR_LEN, WHIRLPOOL_DISCRIMINATOR};
+
+use super::utils::{account_filters, field_filter};
+
+// The offsets of the fields in the account data, from the sizes of the fields before them in the
+// `Whirlpool` layout
+const WHIRLPOOLS_CONFIG_OFFSET: usize = DISCRIMINATOR_LEN;
+// whirlpools_config, whirlpool_bump
+const TICK_SPACING_OFFSET: usize = WHIRLPOOLS_CONFIG_OFFSET + size_of::<Pubkey>() + 1;
+// tick_spacing, tick_spacing_seed
+const FEE_RATE_OFFSET: usize = TICK_SPACING_OFFSET + size_of::<u16>() + 2;
+// fee_rate
+const PROTOCOL_FEE_RATE_OFFSET: usize = FEE_RATE_OFFSET + size_of::<u16>();
+// protocol_fee_rate, liquidity, sqrt_price, tick_current_index, protocol_fee_owed_a,
+// protocol_fee_owed_b
+const TOKEN_MINT_A_OFFSET: usize = PROTOCOL_FEE_RATE_OFFSET
+    + size_of::<u16>()
+    + size_of::<u128>()
+    + size_of::<u128>()
+    + size_of::<i32>()
+    + size_of::<u64>()
+    + size_of::<u64>();
+// token_mint_a
+const TOKEN_VAULT_A_OFFSET: usize = TOKEN_MINT_A_OFFSET + size_of::<Pubkey>();
+// token_vault_a, fee_growth_global_a
+const TOKEN_MINT_B_OFFSET: usize = TOKEN_VAULT_A_OFFSET + size_of::<Pubkey>() + size_of::<u128>();
+// token_mint_b
+const TOKEN_VAULT_B_OFFSET: usize = TOKEN_MINT_B_OFFSET + size_of::<Pubkey>();
+
+/// A filter on a field of whirlpool accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhirlpoolFilter {
+    WhirlpoolsConfig(Pubkey),
+    TickSpacing(u16),
+    FeeRate(u16),
+    ProtocolFeeRate(u16),
+    TokenMintA(Pubkey),
+    TokenVaultA(Pubkey),
+    TokenMintB(Pubkey),
+    TokenVaultB(Pubkey),
+}
+
+impl From<WhirlpoolFilter> for RpcFilterType {
+    fn from(filter: WhirlpoolFilter) -> Self {
+        match filter {
+            WhirlpoolFilter::WhirlpoolsConfig(address) => {
+                field_filter(WHIRLPOOLS_CONFIG_OFFSET, address.as_ref())
+            }
+            WhirlpoolFilter::TickSpacing(tick_spacing) => {
+                field_filter(TICK_SPACING_OFFSET, &tick_spacing.to_le_bytes())
+            }
+            WhirlpoolFilter::FeeRate(fee_rate) => {
+                field_filter(FEE_RATE_OFFSET, &fee_rate.to_le_bytes())
+            }
+            WhirlpoolFilter::ProtocolFeeRate(protocol_fee_rate) => {
+                field_filter(PROTOCOL_FEE_RATE_OFFSET, &protocol_fee_rate.to_le_bytes())
+            }
+            WhirlpoolFilter::TokenMintA(address) => {
+                field_filter(TOKEN_MINT_A_OFFSET, address.as_ref())
+            }
+            WhirlpoolFilter::TokenVaultA(address) => {
+                field_filter(TOKEN_VAULT_A_OFFSET, address.as_ref())
+            }
+            WhirlpoolFilter::TokenMintB(address) => {
+                field_filter(TOKEN_MINT_B_OFFSET, address.as_ref())
+            }
+            WhirlpoolFilter::TokenVaultB(address) => {
+                field_filter(TOKEN_VAULT_B_OFFSET, address.as_ref())
+            }
+        }
+    }
+}
+
+/// Build the `getProgramAccounts` filters that select the whirlpools matching all of the given
+/// filters.
+///
+/// A whirlpool holds a mint either as token A or as token B, so finding all the whirlpools of a
+/// mint takes two requests: one with `WhirlpoolFilter::TokenMintA` and one with
+/// `WhirlpoolFilter::TokenMintB`.
+///
+/// # Parameters
+/// - `filters` - The filters on the fields of the whirlpool
+///
+/// # Returns
+/// - The filters to pass to `getProgramAccounts` for the whirlpool program
+pub fn whirlpool_filters(filters: &[WhirlpoolFilter]) -> Vec<RpcFilterType> {
+    account_filters(
+        WHIRLPOOL_DISCRIMINATOR,
+        Whirlpool::LEN,
+        filters.iter().map(|filter| (*filter).into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_accounts::{test_account, zeroed_account};
+    use solana_sdk::account::AccountSharedData;
+
+    fn test_whirlpool() -> Whirlpool {
+        let mut whirlpool: Whirlpool = zeroed_account(WHIRLPOOL_DISCRIMINATOR, Whirlpool::LEN);
+        whirlpool.whirlpools_config = Pubkey::new_unique();
+        whirlpool.tick_spacing = 64;
+        whirlpool.fee_rate = 3000;
+        whirlpool.protocol_fee_rate = 300;
+        whirlpool.token_mint_a = Pubkey::new_unique();
+        whirlpool.token_vault_a = Pubkey::new_unique();
+        whirlpool.token_mint_b = Pubkey::new_unique();
+        whirlpool.token_vault_b = Pubkey::new_unique();
+        whirlpool
+    }
+
+    #[test]
+    fn test_whirlpool_filters() {
+        let whirlpool = test_whirlpool();
+        let account = AccountSharedData::from(test_account(&whirlpool, Whirlpool::LEN));
+        let matches = |filters: &[WhirlpoolFilter]| {
+            whirlpool_filters(filters)
+                .iter()
+                .all(|filter| filter.verify().is_ok() && filter.allows(&account))
+        };
+
+        assert!(matches(&[]));
+        assert!(matches(&[WhirlpoolFilter::WhirlpoolsConfig(
+            whirlpool.whirlpools_config
+        )]));
+        assert!(matches(&[
+            WhirlpoolFilter::TickSpacing(64),
+            WhirlpoolFilter::FeeRate(3000),
+            WhirlpoolFilter::ProtocolFeeRate(300),
+        ]));
+        assert!(matches(&[
+            WhirlpoolFilter::TokenMintA(whirlpool.token_mint_a),
+            WhirlpoolFilter::TokenVaultA(whirlpool.token_vault_a),
+        ]));
+        assert!(matches(&[
+            WhirlpoolFilter::TokenMintB(whirlpool.token_mint_b),
+            WhirlpoolFilter::TokenVaultB(whirlpool.token_vault_b),
+        ]));
+        assert!(!matches(&[WhirlpoolFilter::TokenMintA(
+            whirlpool.token_mint_b
+        )]));
+        assert!(!matches(&[WhirlpoolFilter::FeeRate(300)]));
+        assert!(!matches(&[WhirlpoolFilter::WhirlpoolsConfig(
+            Pubkey::new_unique()
+        )]));
+    }
+
+    #[test]
+    fn test_whirlpool_filters_other_account() {
+        let account = AccountSharedData::new(1, Whirlpool::LEN, &crate::ID);
+        assert!(!whirlpool_filters(&[])
+            .iter()
+            .all(|filter| filter.allows(&account)));
+    }
+}
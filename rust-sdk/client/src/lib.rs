@@ -1,9 +1,28 @@
+mod decode;
+mod error;
 mod generated;
 mod pda;
 
 #[cfg(feature = "core-types")]
 mod core_types;
 
+#[cfg(feature = "fetch")]
+mod fetch;
+
+#[cfg(feature = "fetch")]
+mod gpa;
+
+#[cfg(all(feature = "fetch", any(test, feature = "test-utils")))]
+pub mod test_accounts;
+
+pub use decode::*;
+pub use error::*;
 pub use generated::programs::WHIRLPOOL_ID as ID;
 pub use generated::*;
 pub use pda::*;
+
+#[cfg(feature = "fetch")]
+pub use fetch::*;
+
+#[cfg(feature = "fetch")]
+pub use gpa::*;
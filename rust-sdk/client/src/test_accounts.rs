@@ -0,0 +1,59 @@
+//! Helpers to build whirlpool program accounts in tests from the generated account types, so
+//! that the tests never depend on the offsets of the fields. They are shared with the tests of
+//! the crates that depend on this one through the `test-utils` feature.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::account::Account;
+
+use crate::ID;
+
+/// Build a whirlpool program account for a test from a generated account, padded with zeroes
+/// to the size of the account.
+///
+/// # Parameters
+/// - `account` - The generated account
+/// - `len` - The size of the account
+///
+/// # Returns
+/// - The account, owned by the whirlpool program
+pub fn test_account<T: BorshSerialize>(account: &T, len: usize) -> Account {
+    let mut data = account.try_to_vec().unwrap();
+    data.resize(len, 0);
+    Account {
+        lamports: 1,
+        data,
+        owner: ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Decode a generated account from zeroed data with the given discriminator, for a test to fill
+/// in the fields it needs.
+///
+/// # Parameters
+/// - `discriminator` - The discriminator of the account type
+/// - `len` - The size of the account
+///
+/// # Returns
+/// - The generated account with all other fields zeroed
+pub fn zeroed_account<T: BorshDeserialize>(discriminator: [u8; 8], len: usize) -> T {
+    let mut data = vec![0u8; len];
+    data[..discriminator.len()].copy_from_slice(&discriminator);
+    T::deserialize(&mut data.as_slice()).unwrap()
+}
+
+/// Decode the generated account of a test account, update it and encode it back.
+///
+/// # Parameters
+/// - `account` - The test account
+/// - `update` - Updates the fields of the generated account
+pub fn update_account<T: BorshSerialize + BorshDeserialize>(
+    account: &mut Account,
+    update: impl FnOnce(&mut T),
+) {
+    let mut decoded = T::deserialize(&mut account.data.as_slice()).unwrap();
+    update(&mut decoded);
+    let data = decoded.try_to_vec().unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+}
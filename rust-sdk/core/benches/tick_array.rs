@@ -0,0 +1,127 @@
+// The benches build facades with native `u128` values, which the wasm feature replaces.
+#[cfg(not(feature = "wasm"))]
+mod tick_array {
+    use criterion::{black_box, criterion_group, Criterion};
+    use orca_whirlpools_core::{
+        swap_quote_by_input_token, tick_index_to_sqrt_price, TickArrayFacade, TickArraySequence,
+        TickFacade, WhirlpoolFacade, TICK_ARRAY_SIZE,
+    };
+
+    const TICK_SPACING: u16 = 1;
+
+    // Five tick arrays where only the outermost ticks are initialized. This is the worst case
+    // for the search, as every tick in between has to be skipped.
+    fn sparse_tick_arrays() -> [TickArrayFacade; 5] {
+        let tick_array_span = TICK_ARRAY_SIZE as i32 * TICK_SPACING as i32;
+        [-2, -1, 0, 1, 2].map(|i| {
+            let mut ticks = [TickFacade::default(); TICK_ARRAY_SIZE];
+            let liquidity_net = 1_000_000_000;
+            match i {
+                -2 => {
+                    ticks[0] = TickFacade {
+                        initialized: true,
+                        liquidity_net,
+                        liquidity_gross: liquidity_net as u128,
+                        ..TickFacade::default()
+                    }
+                }
+                2 => {
+                    ticks[TICK_ARRAY_SIZE - 1] = TickFacade {
+                        initialized: true,
+                        liquidity_net: -liquidity_net,
+                        liquidity_gross: liquidity_net as u128,
+                        ..TickFacade::default()
+                    }
+                }
+                _ => {}
+            }
+            TickArrayFacade {
+                start_tick_index: i * tick_array_span,
+                ticks,
+            }
+        })
+    }
+
+    // The search as it was done before initialized-tick bitmaps: one tick at a time.
+    fn linear_next_initialized_tick(sequence: &TickArraySequence<5>, tick_index: i32) -> i32 {
+        let end_index = sequence.end_index();
+        let mut next_index = tick_index + TICK_SPACING as i32;
+        while next_index <= end_index {
+            if sequence.tick(next_index).unwrap().initialized {
+                return next_index;
+            }
+            next_index += TICK_SPACING as i32;
+        }
+        end_index
+    }
+
+    fn linear_prev_initialized_tick(sequence: &TickArraySequence<5>, tick_index: i32) -> i32 {
+        let start_index = sequence.start_index();
+        let mut prev_index = tick_index;
+        while prev_index >= start_index {
+            if sequence.tick(prev_index).unwrap().initialized {
+                return prev_index;
+            }
+            prev_index -= TICK_SPACING as i32;
+        }
+        start_index
+    }
+
+    fn bench_initialized_tick_search(c: &mut Criterion) {
+        let sequence =
+            TickArraySequence::new(sparse_tick_arrays().map(Some), TICK_SPACING).unwrap();
+        let start_index = sequence.start_index();
+        let end_index = sequence.end_index();
+
+        let mut group = c.benchmark_group("next_initialized_tick");
+        group.bench_function("linear", |b| {
+            b.iter(|| linear_next_initialized_tick(&sequence, black_box(start_index)))
+        });
+        group.bench_function("bitmap", |b| {
+            b.iter(|| sequence.next_initialized_tick(black_box(start_index)))
+        });
+        group.finish();
+
+        let mut group = c.benchmark_group("prev_initialized_tick");
+        group.bench_function("linear", |b| {
+            b.iter(|| linear_prev_initialized_tick(&sequence, black_box(end_index - 1)))
+        });
+        group.bench_function("bitmap", |b| {
+            b.iter(|| sequence.prev_initialized_tick(black_box(end_index - 1)))
+        });
+        group.finish();
+    }
+
+    fn bench_swap_quote(c: &mut Criterion) {
+        let whirlpool = WhirlpoolFacade {
+            tick_spacing: TICK_SPACING,
+            fee_rate: 3000,
+            liquidity: 1_000_000_000,
+            sqrt_price: tick_index_to_sqrt_price(0),
+            tick_current_index: 0,
+            ..WhirlpoolFacade::default()
+        };
+
+        c.bench_function("swap_quote_by_input_token", |b| {
+            b.iter(|| {
+                swap_quote_by_input_token(
+                    black_box(5_000_000),
+                    true,
+                    100,
+                    whirlpool,
+                    sparse_tick_arrays().into(),
+                    None,
+                    None,
+                )
+            })
+        });
+    }
+
+    criterion_group!(benches, bench_initialized_tick_search, bench_swap_quote);
+}
+
+#[cfg(not(feature = "wasm"))]
+criterion::criterion_main!(tick_array::benches);
+
+#[cfg(feature = "wasm")]
+fn main() {}
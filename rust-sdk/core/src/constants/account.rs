@@ -0,0 +1,148 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+/// The size of a position account (`Position::LEN` = 8 + 136 + 72).
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const POSITION_ACCOUNT_LEN: usize = 216;
+
+/// The size of a tick array account (`TickArray::LEN` = 8 + 36 + 113 * 88).
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TICK_ARRAY_ACCOUNT_LEN: usize = 9988;
+
+/// The size of a Token program mint account.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MINT_ACCOUNT_LEN: usize = 82;
+
+/// The size of a Token program token account.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// The maximum size of a Metaplex metadata account.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const METADATA_ACCOUNT_LEN: usize = 679;
+
+// A Token-2022 account with extensions is the base account (a mint is padded to the size of a
+// token account), the account type and a type-length-value entry for each extension.
+const TOKEN_2022_BASE_ACCOUNT_LEN: usize = 165;
+const TOKEN_2022_ACCOUNT_TYPE_LEN: usize = 1;
+const TOKEN_2022_EXTENSION_HEADER_LEN: usize = 4;
+const MINT_CLOSE_AUTHORITY_EXTENSION_LEN: usize = 32;
+const METADATA_POINTER_EXTENSION_LEN: usize = 64;
+const IMMUTABLE_OWNER_EXTENSION_LEN: usize = 0;
+
+// The `TokenMetadata` extension is borsh encoded: the update authority, the mint, the name,
+// symbol and uri strings and the (empty) additional metadata vec, each prefixed with its length.
+const BORSH_LEN_PREFIX_LEN: usize = 4;
+// "OWP xxxx...yyyy" with the first and last 4 characters of the position mint address
+const POSITION_METADATA_NAME_LEN: usize = 15;
+// "OWP"
+const POSITION_METADATA_SYMBOL_LEN: usize = 3;
+// "https://position-nft.orca.so/meta" + "/" + whirlpool address + "/" + position address
+const POSITION_METADATA_URI_MAX_LEN: usize = 33 + 1 + 44 + 1 + 44;
+const POSITION_TOKEN_METADATA_EXTENSION_MAX_LEN: usize = 32
+    + 32
+    + BORSH_LEN_PREFIX_LEN
+    + POSITION_METADATA_NAME_LEN
+    + BORSH_LEN_PREFIX_LEN
+    + POSITION_METADATA_SYMBOL_LEN
+    + BORSH_LEN_PREFIX_LEN
+    + POSITION_METADATA_URI_MAX_LEN
+    + BORSH_LEN_PREFIX_LEN;
+
+/// The size of a Token-2022 position mint with the `MintCloseAuthority` extension.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOKEN_2022_POSITION_MINT_LEN: usize = TOKEN_2022_BASE_ACCOUNT_LEN
+    + TOKEN_2022_ACCOUNT_TYPE_LEN
+    + TOKEN_2022_EXTENSION_HEADER_LEN
+    + MINT_CLOSE_AUTHORITY_EXTENSION_LEN;
+
+/// The maximum size of a Token-2022 position mint with the `MintCloseAuthority`,
+/// `MetadataPointer` and `TokenMetadata` extensions.
+/// The metadata uri contains the base58 whirlpool and position addresses,
+/// so the actual size can be a few bytes smaller.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOKEN_2022_POSITION_MINT_WITH_METADATA_LEN: usize = TOKEN_2022_POSITION_MINT_LEN
+    + TOKEN_2022_EXTENSION_HEADER_LEN
+    + METADATA_POINTER_EXTENSION_LEN
+    + TOKEN_2022_EXTENSION_HEADER_LEN
+    + POSITION_TOKEN_METADATA_EXTENSION_MAX_LEN;
+
+/// The size of a Token-2022 associated token account with the `ImmutableOwner` extension.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOKEN_2022_TOKEN_ACCOUNT_LEN: usize = TOKEN_2022_BASE_ACCOUNT_LEN
+    + TOKEN_2022_ACCOUNT_TYPE_LEN
+    + TOKEN_2022_EXTENSION_HEADER_LEN
+    + IMMUTABLE_OWNER_EXTENSION_LEN;
+
+/// The number of bytes that is added to the data size of an account to calculate its rent.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+/// The lamports per byte needed for an account to be rent exempt.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const RENT_EXEMPT_LAMPORTS_PER_BYTE: u64 = 6960;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token_2022::extension::metadata_pointer::MetadataPointer;
+    use spl_token_2022::extension::mint_close_authority::MintCloseAuthority;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions,
+        StateWithExtensionsMut,
+    };
+    use spl_token_2022::solana_program::pubkey::Pubkey;
+    use spl_token_2022::state::{Account, Mint};
+    use spl_token_metadata_interface::state::TokenMetadata;
+
+    #[test]
+    fn test_token_2022_position_mint_len() {
+        let len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MintCloseAuthority])
+                .unwrap();
+        assert_eq!(TOKEN_2022_POSITION_MINT_LEN, len);
+        assert_eq!(TOKEN_2022_POSITION_MINT_LEN, 202);
+    }
+
+    #[test]
+    fn test_token_2022_position_mint_with_metadata_len() {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::MetadataPointer,
+        ])
+        .unwrap();
+        let mut data = vec![0u8; len];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        mint.init_extension::<MintCloseAuthority>(true).unwrap();
+        mint.init_extension::<MetadataPointer>(true).unwrap();
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+
+        // Addresses with the longest base58 encoding
+        let address = Pubkey::new_from_array([255; 32]).to_string();
+        let metadata = TokenMetadata {
+            name: format!("OWP {}...{}", &address[0..4], &address[address.len() - 4..]),
+            symbol: "OWP".to_string(),
+            uri: format!("https://position-nft.orca.so/meta/{address}/{address}"),
+            ..Default::default()
+        };
+        let len = StateWithExtensions::<Mint>::unpack(&data)
+            .unwrap()
+            .try_get_new_account_len_for_variable_len_extension(&metadata)
+            .unwrap();
+        assert_eq!(TOKEN_2022_POSITION_MINT_WITH_METADATA_LEN, len);
+        assert_eq!(TOKEN_2022_POSITION_MINT_WITH_METADATA_LEN, 495);
+    }
+
+    #[test]
+    fn test_token_2022_token_account_len() {
+        let len =
+            ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::ImmutableOwner])
+                .unwrap();
+        assert_eq!(TOKEN_2022_TOKEN_ACCOUNT_LEN, len);
+        assert_eq!(TOKEN_2022_TOKEN_ACCOUNT_LEN, 170);
+    }
+}
@@ -43,3 +43,39 @@ pub const INVALID_TRANSFER_FEE: ErrorCode = 9011;
 
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const INVALID_SLIPPAGE_TOLERANCE: ErrorCode = 9012;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const ROUTE_NOT_FOUND: ErrorCode = 9013;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOO_MANY_TICK_ARRAYS: ErrorCode = 9014;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INSUFFICIENT_PRICE_HISTORY: ErrorCode = 9015;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const POSITION_NOT_FOUND: ErrorCode = 9016;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const POSITION_NOT_EMPTY: ErrorCode = 9017;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const ZERO_LIQUIDITY: ErrorCode = 9018;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INVALID_REWARD_INDEX: ErrorCode = 9019;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const INTERMEDIATE_TOKEN_AMOUNT_MISMATCH: ErrorCode = 9020;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const TOKEN_MAX_EXCEEDED: ErrorCode = 9021;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const MULTIPLICATION_SHIFT_RIGHT_OVERFLOW: ErrorCode = 9022;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const AMOUNT_CALC_OVERFLOW: ErrorCode = 9023;
+
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const AMOUNT_REMAINING_OVERFLOW: ErrorCode = 9024;
@@ -1,3 +1,4 @@
+mod account;
 mod bundle;
 mod error;
 mod pool;
@@ -5,6 +6,7 @@ mod swap;
 mod tick;
 mod token;
 
+pub use account::*;
 pub use bundle::*;
 pub use error::*;
 pub use pool::*;
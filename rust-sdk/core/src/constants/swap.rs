@@ -7,6 +7,10 @@ use orca_whirlpools_macros::wasm_expose;
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
 
+/// The denominator of the protocol fee rate value.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub const PROTOCOL_FEE_RATE_DENOMINATOR: u16 = 10_000;
+
 // TODO: WASM export (which doesn't work with u128 yet)
 
 /// The minimum sqrt price for a whirlpool.
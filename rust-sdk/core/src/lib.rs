@@ -7,9 +7,11 @@
 mod constants;
 mod math;
 mod quote;
+mod simulation;
 mod types;
 
 pub use constants::*;
 pub use math::*;
 pub use quote::*;
+pub use simulation::*;
 pub use types::*;
@@ -3,7 +3,7 @@ use orca_whirlpools_macros::wasm_expose;
 
 use ethnum::U256;
 
-use crate::POSITION_BUNDLE_SIZE;
+use crate::{PositionBundleDiff, POSITION_BUNDLE_SIZE};
 
 const POSITION_BUNDLE_BYTES: usize = POSITION_BUNDLE_SIZE / 8;
 
@@ -52,8 +52,86 @@ pub fn is_position_bundle_empty(bitmap: &[u8]) -> bool {
     value == U256::MIN
 }
 
+/// Get all occupied positions in a bundle
+///
+/// # Arguments
+/// * `bundle` - The bundle to check
+///
+/// # Returns
+/// * `Vec<u32>` - The occupied positions in ascending order
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn occupied_positions_in_bundle(bitmap: &[u8]) -> Vec<u32> {
+    positions_in_bundle(bitmap_to_u256(bitmap)).collect()
+}
+
+/// Get all unoccupied positions in a bundle
+///
+/// # Arguments
+/// * `bundle` - The bundle to check
+///
+/// # Returns
+/// * `Vec<u32>` - The unoccupied positions in ascending order
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn unoccupied_positions_in_bundle(bitmap: &[u8]) -> Vec<u32> {
+    positions_in_bundle(!bitmap_to_u256(bitmap)).collect()
+}
+
+/// Count the occupied positions in a bundle
+///
+/// # Arguments
+/// * `bundle` - The bundle to check
+///
+/// # Returns
+/// * `u32` - The number of occupied positions
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn occupied_position_count_in_bundle(bitmap: &[u8]) -> u32 {
+    bitmap_to_u256(bitmap).count_ones()
+}
+
+/// Get the first unoccupied positions in a bundle for opening multiple positions at once
+///
+/// # Arguments
+/// * `bundle` - The bundle to check
+/// * `count` - The number of positions to allocate
+///
+/// # Returns
+/// * `Vec<u32>` - The allocated positions in ascending order (None if not enough are unoccupied)
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn allocate_positions_in_bundle(bitmap: &[u8], count: u32) -> Option<Vec<u32>> {
+    let positions: Vec<u32> = positions_in_bundle(!bitmap_to_u256(bitmap))
+        .take(count as usize)
+        .collect();
+    if positions.len() == count as usize {
+        Some(positions)
+    } else {
+        None
+    }
+}
+
+/// Get the positions that were opened and closed between two snapshots of a bundle
+///
+/// # Arguments
+/// * `bitmap_before` - The earlier snapshot of the bundle
+/// * `bitmap_after` - The later snapshot of the bundle
+///
+/// # Returns
+/// * `PositionBundleDiff` - The opened and closed positions in ascending order
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn diff_position_bundles(bitmap_before: &[u8], bitmap_after: &[u8]) -> PositionBundleDiff {
+    let before = bitmap_to_u256(bitmap_before);
+    let after = bitmap_to_u256(bitmap_after);
+    PositionBundleDiff {
+        opened_positions: positions_in_bundle(after & !before).collect(),
+        closed_positions: positions_in_bundle(before & !after).collect(),
+    }
+}
+
 // Private functions
 
+fn positions_in_bundle(value: U256) -> impl Iterator<Item = u32> {
+    (0..POSITION_BUNDLE_SIZE as u32).filter(move |i| value & (U256::ONE << i) != 0)
+}
+
 #[allow(clippy::needless_range_loop)]
 fn bitmap_to_u256(bitmap: &[u8]) -> U256 {
     let mut u256 = <U256>::from(0u32);
@@ -110,4 +188,66 @@ mod tests {
         bundle[0] = 0b111111;
         assert!(!is_position_bundle_empty(&bundle));
     }
+
+    #[test]
+    fn test_occupied_positions_in_bundle() {
+        let bundle: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        assert!(occupied_positions_in_bundle(&bundle).is_empty());
+        assert_eq!(unoccupied_positions_in_bundle(&bundle).len(), 256);
+
+        let mut bundle: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        bundle[0] = 0b00010010;
+        bundle[31] = 0b10000000;
+        assert_eq!(occupied_positions_in_bundle(&bundle), vec![1, 4, 255]);
+        let unoccupied = unoccupied_positions_in_bundle(&bundle);
+        assert_eq!(unoccupied.len(), 253);
+        assert_eq!(unoccupied[..4], [0, 2, 3, 5]);
+        assert_eq!(unoccupied.last(), Some(&254));
+    }
+
+    #[test]
+    fn test_occupied_position_count_in_bundle() {
+        let bundle: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        assert_eq!(occupied_position_count_in_bundle(&bundle), 0);
+
+        let bundle: [u8; POSITION_BUNDLE_BYTES] = [255; POSITION_BUNDLE_BYTES];
+        assert_eq!(occupied_position_count_in_bundle(&bundle), 256);
+
+        let mut bundle: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        bundle[3] = 0b10100001;
+        assert_eq!(occupied_position_count_in_bundle(&bundle), 3);
+    }
+
+    #[test]
+    fn test_allocate_positions_in_bundle() {
+        let mut bundle: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        bundle[0] = 0b11101011;
+        assert_eq!(allocate_positions_in_bundle(&bundle, 0), Some(vec![]));
+        assert_eq!(
+            allocate_positions_in_bundle(&bundle, 3),
+            Some(vec![2, 4, 8])
+        );
+
+        let mut bundle: [u8; POSITION_BUNDLE_BYTES] = [255; POSITION_BUNDLE_BYTES];
+        bundle[10] = 0b10111110;
+        assert_eq!(allocate_positions_in_bundle(&bundle, 2), Some(vec![80, 86]));
+        assert_eq!(allocate_positions_in_bundle(&bundle, 3), None);
+    }
+
+    #[test]
+    fn test_diff_position_bundles() {
+        let mut before: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        before[0] = 0b00001111;
+        let mut after: [u8; POSITION_BUNDLE_BYTES] = [0; POSITION_BUNDLE_BYTES];
+        after[0] = 0b00110011;
+        after[20] = 0b00000001;
+
+        let diff = diff_position_bundles(&before, &after);
+        assert_eq!(diff.opened_positions, vec![4, 5, 160]);
+        assert_eq!(diff.closed_positions, vec![2, 3]);
+
+        let diff = diff_position_bundles(&after, &after);
+        assert!(diff.opened_positions.is_empty());
+        assert!(diff.closed_positions.is_empty());
+    }
 }
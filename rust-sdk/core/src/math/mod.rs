@@ -3,6 +3,7 @@ mod position;
 mod tick;
 mod tick_array;
 mod token;
+mod twap;
 
 #[cfg(feature = "floats")]
 mod price;
@@ -12,6 +13,7 @@ pub use position::*;
 pub use tick::*;
 pub use tick_array::*;
 pub use token::*;
+pub use twap::*;
 
 #[cfg(feature = "floats")]
 pub use price::*;
@@ -3,15 +3,15 @@ use crate::{
     TICK_ARRAY_NOT_EVENLY_SPACED, TICK_ARRAY_SIZE, TICK_INDEX_OUT_OF_BOUNDS, TICK_SEQUENCE_EMPTY,
 };
 
-use super::{
-    get_initializable_tick_index, get_next_initializable_tick_index,
-    get_prev_initializable_tick_index,
-};
+use super::{get_initializable_tick_index, get_next_initializable_tick_index};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TickArraySequence<const SIZE: usize> {
     tick_arrays: [Option<TickArrayFacade>; SIZE],
     tick_spacing: u16,
+    // One bit per tick slot (bit `i` is set if `ticks[i]` is initialized). A tick array
+    // holds 88 ticks so a single u128 is enough to cover each array.
+    initialized_bitmaps: [u128; SIZE],
 }
 
 impl<const SIZE: usize> TickArraySequence<SIZE> {
@@ -30,16 +30,19 @@ impl<const SIZE: usize> TickArraySequence<SIZE> {
         for i in 0..tick_arrays.len() - 1 {
             let current_start_tick_index = start_tick_index(&tick_arrays[i]);
             let next_start_tick_index = start_tick_index(&tick_arrays[i + 1]);
-            if next_start_tick_index - current_start_tick_index != required_tick_array_spacing
-                && next_start_tick_index != <i32>::MAX
+            if next_start_tick_index != <i32>::MAX
+                && next_start_tick_index - current_start_tick_index != required_tick_array_spacing
             {
                 return Err(TICK_ARRAY_NOT_EVENLY_SPACED);
             }
         }
 
+        let initialized_bitmaps = core::array::from_fn(|i| initialized_bitmap(&tick_arrays[i]));
+
         Ok(Self {
             tick_arrays,
             tick_spacing,
+            initialized_bitmaps,
         })
     }
 
@@ -68,14 +71,8 @@ impl<const SIZE: usize> TickArraySequence<SIZE> {
         if (tick_index % self.tick_spacing as i32) != 0 {
             return Err(INVALID_TICK_INDEX);
         }
-        let first_index = start_tick_index(&self.tick_arrays[0]);
-        let tick_array_index = ((tick_index - first_index)
-            / (TICK_ARRAY_SIZE as i32 * self.tick_spacing as i32))
-            as usize;
-        let tick_array_start_index = start_tick_index(&self.tick_arrays[tick_array_index]);
-        let tick_array_ticks = ticks(&self.tick_arrays[tick_array_index]);
-        let index_in_array = (tick_index - tick_array_start_index) / self.tick_spacing as i32;
-        Ok(&tick_array_ticks[index_in_array as usize])
+        let (tick_array_index, index_in_array) = self.tick_location(tick_index);
+        Ok(&ticks(&self.tick_arrays[tick_array_index])[index_in_array as usize])
     }
 
     pub fn next_initialized_tick(
@@ -83,18 +80,36 @@ impl<const SIZE: usize> TickArraySequence<SIZE> {
         tick_index: i32,
     ) -> Result<(Option<&TickFacade>, i32), ErrorCode> {
         let array_end_index = self.end_index();
-        let mut next_index = tick_index;
-        loop {
-            next_index = get_next_initializable_tick_index(next_index, self.tick_spacing);
-            // If at the end of the sequence, we don't have tick info but can still return the next tick index
-            if next_index > array_end_index {
-                return Ok((None, array_end_index));
+        let next_index = get_next_initializable_tick_index(tick_index, self.tick_spacing);
+        // If at the end of the sequence, we don't have tick info but can still return the next tick index
+        if next_index > array_end_index {
+            return Ok((None, array_end_index));
+        }
+        if next_index < self.start_index() {
+            return Err(TICK_INDEX_OUT_OF_BOUNDS);
+        }
+
+        let (first_array_index, first_offset) = self.tick_location(next_index);
+        for array_index in first_array_index..SIZE {
+            // Skip the bits below `next_index` in the first array
+            let offset = if array_index == first_array_index {
+                first_offset
+            } else {
+                0
+            };
+            let bitmap = self.initialized_bitmaps[array_index] >> offset;
+            if bitmap == 0 {
+                continue;
             }
-            let tick = self.tick(next_index)?;
-            if tick.initialized {
-                return Ok((Some(tick), next_index));
+            let index_in_array = offset + bitmap.trailing_zeros();
+            let next_index = self.tick_index_at(array_index, index_in_array);
+            if next_index > array_end_index {
+                break;
             }
+            let tick = &ticks(&self.tick_arrays[array_index])[index_in_array as usize];
+            return Ok((Some(tick), next_index));
         }
+        Ok((None, array_end_index))
     }
 
     pub fn prev_initialized_tick(
@@ -102,19 +117,51 @@ impl<const SIZE: usize> TickArraySequence<SIZE> {
         tick_index: i32,
     ) -> Result<(Option<&TickFacade>, i32), ErrorCode> {
         let array_start_index = self.start_index();
-        let mut prev_index =
-            get_initializable_tick_index(tick_index, self.tick_spacing, Some(false));
-        loop {
-            // If at the start of the sequence, we don't have tick info but can still return the previous tick index
-            if prev_index < array_start_index {
-                return Ok((None, array_start_index));
+        let prev_index = get_initializable_tick_index(tick_index, self.tick_spacing, Some(false));
+        // If at the start of the sequence, we don't have tick info but can still return the previous tick index
+        if prev_index < array_start_index {
+            return Ok((None, array_start_index));
+        }
+        if prev_index > self.end_index() {
+            return Err(TICK_INDEX_OUT_OF_BOUNDS);
+        }
+
+        let (last_array_index, last_offset) = self.tick_location(prev_index);
+        for array_index in (0..=last_array_index).rev() {
+            // Skip the bits above `prev_index` in the last array
+            let mask = if array_index == last_array_index {
+                u128::MAX >> (u128::BITS - 1 - last_offset)
+            } else {
+                u128::MAX
+            };
+            let bitmap = self.initialized_bitmaps[array_index] & mask;
+            if bitmap == 0 {
+                continue;
             }
-            let tick = self.tick(prev_index)?;
-            if tick.initialized {
-                return Ok((Some(tick), prev_index));
+            let index_in_array = u128::BITS - 1 - bitmap.leading_zeros();
+            let prev_index = self.tick_index_at(array_index, index_in_array);
+            if prev_index < array_start_index {
+                break;
             }
-            prev_index = get_prev_initializable_tick_index(prev_index, self.tick_spacing);
+            let tick = &ticks(&self.tick_arrays[array_index])[index_in_array as usize];
+            return Ok((Some(tick), prev_index));
         }
+        Ok((None, array_start_index))
+    }
+
+    // Returns the (tick array index, index in tick array) pair of a tick index in the sequence
+    fn tick_location(&self, tick_index: i32) -> (usize, u32) {
+        let ticks_per_array = TICK_ARRAY_SIZE as i32 * self.tick_spacing as i32;
+        let first_index = start_tick_index(&self.tick_arrays[0]);
+        let array_index = ((tick_index - first_index) / ticks_per_array) as usize;
+        let tick_array_start_index = start_tick_index(&self.tick_arrays[array_index]);
+        let index_in_array = (tick_index - tick_array_start_index) / self.tick_spacing as i32;
+        (array_index, index_in_array as u32)
+    }
+
+    fn tick_index_at(&self, array_index: usize, index_in_array: u32) -> i32 {
+        start_tick_index(&self.tick_arrays[array_index])
+            + index_in_array as i32 * self.tick_spacing as i32
     }
 }
 
@@ -128,6 +175,14 @@ fn start_tick_index(tick_array: &Option<TickArrayFacade>) -> i32 {
     }
 }
 
+fn initialized_bitmap(tick_array: &Option<TickArrayFacade>) -> u128 {
+    ticks(tick_array)
+        .iter()
+        .enumerate()
+        .filter(|(_, tick)| tick.initialized)
+        .fold(0, |bitmap, (i, _)| bitmap | (1 << i))
+}
+
 fn ticks(tick_array: &Option<TickArrayFacade>) -> &[TickFacade] {
     if let Some(tick_array) = tick_array {
         &tick_array.ticks
@@ -181,6 +236,17 @@ mod tests {
         assert_eq!(sequence.end_index(), 2815);
     }
 
+    #[test]
+    fn test_tick_array_negative_start_index_with_padding() {
+        let tick_array = TickArrayFacade {
+            start_tick_index: -1408,
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        };
+        let sequence = TickArraySequence::new([Some(tick_array), None], 16).unwrap();
+        assert_eq!(sequence.start_index(), -1408);
+        assert_eq!(sequence.end_index(), -1);
+    }
+
     #[test]
     fn test_get_tick() {
         let sequence = test_sequence(16);
@@ -302,4 +368,86 @@ mod tests {
         assert_eq!(pair.map(|x| x.1), Ok(-1408));
         assert_eq!(pair.map(|x| x.0), Ok(None));
     }
+
+    fn test_sparse_sequence(tick_spacing: u16) -> TickArraySequence<5> {
+        let empty_ticks = [TickFacade::default(); TICK_ARRAY_SIZE];
+        let mut first_ticks = empty_ticks;
+        first_ticks[3] = TickFacade {
+            initialized: true,
+            liquidity_net: 3,
+            ..TickFacade::default()
+        };
+        let mut last_ticks = empty_ticks;
+        last_ticks[80] = TickFacade {
+            initialized: true,
+            liquidity_net: 80,
+            ..TickFacade::default()
+        };
+        let tick_array_span = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
+        let tick_arrays = [-2, -1, 0, 1, 2].map(|i| TickArrayFacade {
+            start_tick_index: i * tick_array_span,
+            ticks: match i {
+                -2 => first_ticks,
+                2 => last_ticks,
+                _ => empty_ticks,
+            },
+        });
+        TickArraySequence::new(tick_arrays.map(Some), tick_spacing).unwrap()
+    }
+
+    #[test]
+    fn test_get_next_initialized_tick_skip_empty_arrays() {
+        let sequence = test_sparse_sequence(1);
+        let pair = sequence.next_initialized_tick(-173);
+        assert_eq!(pair.map(|x| x.1), Ok(256));
+        assert_eq!(pair.map(|x| x.0.map(|x| x.liquidity_net)), Ok(Some(80)));
+    }
+
+    #[test]
+    fn test_get_next_initialized_tick_none_left() {
+        let sequence = test_sparse_sequence(1);
+        let pair = sequence.next_initialized_tick(256);
+        assert_eq!(pair.map(|x| x.1), Ok(263));
+        assert_eq!(pair.map(|x| x.0), Ok(None));
+    }
+
+    #[test]
+    fn test_get_prev_initialized_tick_skip_empty_arrays() {
+        let sequence = test_sparse_sequence(1);
+        let pair = sequence.prev_initialized_tick(255);
+        assert_eq!(pair.map(|x| x.1), Ok(-173));
+        assert_eq!(pair.map(|x| x.0.map(|x| x.liquidity_net)), Ok(Some(3)));
+    }
+
+    #[test]
+    fn test_get_prev_initialized_tick_none_left() {
+        let sequence = test_sparse_sequence(1);
+        let pair = sequence.prev_initialized_tick(-174);
+        assert_eq!(pair.map(|x| x.1), Ok(-176));
+        assert_eq!(pair.map(|x| x.0), Ok(None));
+    }
+
+    #[test]
+    fn test_get_initialized_tick_matches_linear_scan() {
+        let sequence = test_sequence(4);
+        let start_index = sequence.start_index();
+        let end_index = sequence.end_index();
+        for tick_index in start_index..end_index {
+            let expected_next = (tick_index + 1..=end_index)
+                .find(|x| x % 4 == 0 && sequence.tick(*x).unwrap().initialized)
+                .unwrap_or(end_index);
+            let expected_prev = (start_index..=tick_index)
+                .rev()
+                .find(|x| x % 4 == 0 && sequence.tick(*x).unwrap().initialized)
+                .unwrap_or(start_index);
+            assert_eq!(
+                sequence.next_initialized_tick(tick_index).map(|x| x.1),
+                Ok(expected_next)
+            );
+            assert_eq!(
+                sequence.prev_initialized_tick(tick_index).map(|x| x.1),
+                Ok(expected_prev)
+            );
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
     ErrorCode, TransferFee, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, BPS_DENOMINATOR,
     FEE_RATE_DENOMINATOR, INVALID_SLIPPAGE_TOLERANCE, INVALID_TRANSFER_FEE, MAX_SQRT_PRICE,
-    MIN_SQRT_PRICE, SQRT_PRICE_OUT_OF_BOUNDS, U128,
+    MIN_SQRT_PRICE, MULTIPLICATION_SHIFT_RIGHT_OVERFLOW, SQRT_PRICE_OUT_OF_BOUNDS, U128,
 };
 
 use ethnum::U256;
@@ -28,11 +28,11 @@ pub fn try_get_amount_delta_a(
     let (sqrt_price_lower, sqrt_price_upper) =
         order_prices(sqrt_price_1.into(), sqrt_price_2.into());
     let sqrt_price_diff = sqrt_price_upper - sqrt_price_lower;
-    let numerator: U256 = <U256>::from(liquidity)
-        .checked_mul(sqrt_price_diff.into())
-        .ok_or(ARITHMETIC_OVERFLOW)?
-        .checked_shl(64)
-        .ok_or(ARITHMETIC_OVERFLOW)?;
+    let numerator: U256 = try_shift_left_64(
+        <U256>::from(liquidity)
+            .checked_mul(sqrt_price_diff.into())
+            .ok_or(ARITHMETIC_OVERFLOW)?,
+    )?;
 
     let denominator: U256 = <U256>::from(sqrt_price_lower)
         .checked_mul(sqrt_price_upper.into())
@@ -71,16 +71,19 @@ pub fn try_get_amount_delta_b(
         order_prices(sqrt_price_1.into(), sqrt_price_2.into());
     let sqrt_price_diff = sqrt_price_upper - sqrt_price_lower;
 
-    let product: U256 = <U256>::from(liquidity)
-        .checked_mul(sqrt_price_diff.into())
-        .ok_or(ARITHMETIC_OVERFLOW)?;
-    let quotient: U256 = product >> 64;
-
-    let should_round = round_up && product & <U256>::from(u64::MAX) > 0;
+    let liquidity: u128 = liquidity.into();
+    let product = liquidity
+        .checked_mul(sqrt_price_diff)
+        .ok_or(MULTIPLICATION_SHIFT_RIGHT_OVERFLOW)?;
+    let quotient = (product >> 64) as u64;
 
-    let result = if should_round { quotient + 1 } else { quotient };
+    let should_round = round_up && product & u128::from(u64::MAX) > 0;
 
-    result.try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
+    if should_round {
+        quotient.checked_add(1).ok_or(ARITHMETIC_OVERFLOW)
+    } else {
+        Ok(quotient)
+    }
 }
 
 /// Calculate the next square root price
@@ -109,15 +112,17 @@ pub fn try_get_next_sqrt_price_from_a(
     let p = <U256>::from(current_sqrt_price)
         .checked_mul(amount.into())
         .ok_or(ARITHMETIC_OVERFLOW)?;
-    let numerator = <U256>::from(current_liquidity)
-        .checked_mul(current_sqrt_price.into())
-        .ok_or(ARITHMETIC_OVERFLOW)?
-        .checked_shl(64)
-        .ok_or(ARITHMETIC_OVERFLOW)?;
-
-    let current_liquidity_shifted = <U256>::from(current_liquidity)
-        .checked_shl(64)
-        .ok_or(ARITHMETIC_OVERFLOW)?;
+    let numerator = try_shift_left_64(
+        <U256>::from(current_liquidity)
+            .checked_mul(current_sqrt_price.into())
+            .ok_or(ARITHMETIC_OVERFLOW)?,
+    )?;
+
+    let current_liquidity_shifted = <U256>::from(current_liquidity) << 64;
+    // Removing all of the token A liquidity would move the price to infinity
+    if !specified_input && current_liquidity_shifted <= p {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
     let denominator = if specified_input {
         current_liquidity_shifted + p
     } else {
@@ -157,14 +162,17 @@ pub fn try_get_next_sqrt_price_from_b(
     amount: u64,
     specified_input: bool,
 ) -> Result<U128, ErrorCode> {
+    let current_sqrt_price: u128 = current_sqrt_price.into();
+    let current_liquidity: u128 = current_liquidity.into();
+    if current_liquidity == 0 {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
     if amount == 0 {
-        return Ok(current_sqrt_price);
+        return Ok(current_sqrt_price.into());
     }
     let current_sqrt_price = <U256>::from(current_sqrt_price);
     let current_liquidity = <U256>::from(current_liquidity);
-    let amount_shifted = <U256>::from(amount)
-        .checked_shl(64)
-        .ok_or(ARITHMETIC_OVERFLOW)?;
+    let amount_shifted = <U256>::from(amount) << 64;
 
     let quotient: U256 = amount_shifted / current_liquidity;
     let remainder: U256 = amount_shifted % current_liquidity;
@@ -178,7 +186,9 @@ pub fn try_get_next_sqrt_price_from_b(
     let result = if specified_input {
         current_sqrt_price + delta
     } else {
-        current_sqrt_price - delta
+        current_sqrt_price
+            .checked_sub(delta)
+            .ok_or(SQRT_PRICE_OUT_OF_BOUNDS)?
     };
 
     if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&result) {
@@ -367,6 +377,14 @@ fn try_mul_div(
     result.try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)
 }
 
+// Converts a product to Q64.64 without dropping any of its bits
+fn try_shift_left_64(value: U256) -> Result<U256, ErrorCode> {
+    if value.leading_zeros() < 64 {
+        return Err(ARITHMETIC_OVERFLOW);
+    }
+    Ok(value << 64)
+}
+
 fn order_prices(a: u128, b: u128) -> (u128, u128) {
     if a < b {
         (a, b)
@@ -386,6 +404,11 @@ mod tests {
 
         assert_eq!(try_get_amount_delta_a(4 << 64, 4 << 64, 4, true), Ok(0));
         assert_eq!(try_get_amount_delta_a(4 << 64, 4 << 64, 4, false), Ok(0));
+
+        assert_eq!(
+            try_get_amount_delta_a(1 << 64, u128::MAX, u128::MAX, true),
+            Err(ARITHMETIC_OVERFLOW)
+        );
     }
 
     #[test]
@@ -395,6 +418,16 @@ mod tests {
 
         assert_eq!(try_get_amount_delta_b(4 << 64, 4 << 64, 4, true), Ok(0));
         assert_eq!(try_get_amount_delta_b(4 << 64, 4 << 64, 4, false), Ok(0));
+
+        assert_eq!(
+            try_get_amount_delta_b(1 << 64, u128::MAX, u128::MAX, false),
+            Err(MULTIPLICATION_SHIFT_RIGHT_OVERFLOW)
+        );
+        assert_eq!(
+            try_get_amount_delta_b(0, u128::MAX, 1, true),
+            Err(ARITHMETIC_OVERFLOW)
+        );
+        assert_eq!(try_get_amount_delta_b(0, u128::MAX, 1, false), Ok(u64::MAX));
     }
 
     #[test]
@@ -416,6 +449,15 @@ mod tests {
             try_get_next_sqrt_price_from_a(4 << 64, 4, 0, false),
             Ok(4 << 64)
         );
+
+        assert_eq!(
+            try_get_next_sqrt_price_from_a(4 << 64, 4, 1, false),
+            Err(SQRT_PRICE_OUT_OF_BOUNDS)
+        );
+        assert_eq!(
+            try_get_next_sqrt_price_from_a(MAX_SQRT_PRICE, u128::MAX, 1, true),
+            Err(ARITHMETIC_OVERFLOW)
+        );
     }
 
     #[test]
@@ -437,6 +479,15 @@ mod tests {
             try_get_next_sqrt_price_from_b(4 << 64, 4, 0, false),
             Ok(4 << 64)
         );
+
+        assert_eq!(
+            try_get_next_sqrt_price_from_b(4 << 64, 0, 8, true),
+            Err(SQRT_PRICE_OUT_OF_BOUNDS)
+        );
+        assert_eq!(
+            try_get_next_sqrt_price_from_b(4 << 64, 1, 8, false),
+            Err(SQRT_PRICE_OUT_OF_BOUNDS)
+        );
     }
 
     #[test]
@@ -0,0 +1,225 @@
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+#[cfg(feature = "floats")]
+use libm::{log, sqrt};
+
+use crate::{
+    order_tick_indexes, tick_index_to_sqrt_price, ErrorCode, PriceHistory, PriceSnapshot,
+    INSUFFICIENT_PRICE_HISTORY, INVALID_TIMESTAMP, U128,
+};
+
+/// Calculate the time-weighted average tick index of a price history.
+///
+/// Every snapshot holds its tick index until the timestamp of the next snapshot. The last
+/// snapshot only marks the end of the window. The result is rounded towards negative infinity.
+///
+/// # Parameters
+/// - `history`: The price snapshots, sorted by timestamp
+///
+/// # Returns
+/// - `i32`: The time-weighted average tick index
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn time_weighted_average_tick_index(history: PriceHistory) -> Result<i32, ErrorCode> {
+    let duration = try_get_duration(&history.snapshots)?;
+    let tick_seconds: i128 = history
+        .snapshots
+        .windows(2)
+        .map(|window| {
+            window[0].tick_index as i128 * (window[1].timestamp - window[0].timestamp) as i128
+        })
+        .sum();
+    Ok(tick_seconds.div_euclid(duration as i128) as i32)
+}
+
+/// Calculate the time-weighted geometric mean price of a price history.
+///
+/// Tick indexes are logarithmic in price, so the sqrt price of the time-weighted average tick
+/// index is the geometric mean of the prices over the window.
+///
+/// # Parameters
+/// - `history`: The price snapshots, sorted by timestamp
+///
+/// # Returns
+/// - `u128`: The time-weighted average sqrt price
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn time_weighted_average_sqrt_price(history: PriceHistory) -> Result<U128, ErrorCode> {
+    let tick_index = time_weighted_average_tick_index(history)?;
+    Ok(tick_index_to_sqrt_price(tick_index))
+}
+
+/// Calculate how long the price of a price history was within a tick range.
+///
+/// The price is in range if the tick index is greater than or equal to the lower tick index and
+/// less than the upper tick index, which is when a position in that range earns fees.
+///
+/// # Parameters
+/// - `history`: The price snapshots, sorted by timestamp
+/// - `tick_index_1`: A tick index of the range
+/// - `tick_index_2`: The other tick index of the range
+///
+/// # Returns
+/// - `u64`: The number of seconds the price was in range
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn time_in_range(
+    history: PriceHistory,
+    tick_index_1: i32,
+    tick_index_2: i32,
+) -> Result<u64, ErrorCode> {
+    try_get_duration(&history.snapshots)?;
+    let tick_range = order_tick_indexes(tick_index_1, tick_index_2);
+    Ok(history
+        .snapshots
+        .windows(2)
+        .filter(|window| {
+            window[0].tick_index >= tick_range.tick_lower_index
+                && window[0].tick_index < tick_range.tick_upper_index
+        })
+        .map(|window| window[1].timestamp - window[0].timestamp)
+        .sum())
+}
+
+/// Calculate the realised volatility of a price history.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+/// Make sure to do these operations last and not to use the result for further calculations.
+///
+/// The variance per second is the sum of the squared log returns between consecutive snapshots
+/// divided by the duration of the window. It is scaled to the given period, so a period of
+/// 31536000 seconds results in an annualised volatility.
+///
+/// # Parameters
+/// - `history`: The price snapshots, sorted by timestamp
+/// - `period`: The period in seconds to scale the volatility to
+///
+/// # Returns
+/// - `f64`: The volatility of the log price over the period
+#[cfg(feature = "floats")]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn realized_volatility(history: PriceHistory, period: u64) -> Result<f64, ErrorCode> {
+    let duration = try_get_duration(&history.snapshots)?;
+    let log_tick_base = log(1.0001);
+    let squared_returns: f64 = history
+        .snapshots
+        .windows(2)
+        .map(|window| {
+            let log_return =
+                (window[1].tick_index as f64 - window[0].tick_index as f64) * log_tick_base;
+            log_return * log_return
+        })
+        .sum();
+    Ok(sqrt(squared_returns / duration as f64 * period as f64))
+}
+
+// Private functions
+
+pub(crate) fn try_get_duration(snapshots: &[PriceSnapshot]) -> Result<u64, ErrorCode> {
+    if snapshots.len() < 2 {
+        return Err(INSUFFICIENT_PRICE_HISTORY);
+    }
+    if snapshots
+        .windows(2)
+        .any(|window| window[1].timestamp < window[0].timestamp)
+    {
+        return Err(INVALID_TIMESTAMP);
+    }
+    let duration = snapshots[snapshots.len() - 1].timestamp - snapshots[0].timestamp;
+    if duration == 0 {
+        return Err(INSUFFICIENT_PRICE_HISTORY);
+    }
+    Ok(duration)
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::WhirlpoolFacade;
+
+    fn test_history(snapshots: &[(u64, i32)]) -> PriceHistory {
+        PriceHistory {
+            snapshots: snapshots
+                .iter()
+                .map(|&(timestamp, tick_index)| PriceSnapshot {
+                    timestamp,
+                    tick_index,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_time_weighted_average_tick_index() {
+        let history = test_history(&[(100, 10), (110, 20), (140, -20), (150, 0)]);
+        assert_eq!(time_weighted_average_tick_index(history), Ok(10));
+
+        let history = test_history(&[(0, 10), (10, 20), (40, 50)]);
+        assert_eq!(time_weighted_average_tick_index(history), Ok(17));
+
+        let history = test_history(&[(0, -10), (10, -20), (40, 50)]);
+        assert_eq!(time_weighted_average_tick_index(history), Ok(-18));
+
+        let history = test_history(&[(0, 10), (0, 1000), (10, 20)]);
+        assert_eq!(time_weighted_average_tick_index(history), Ok(1000));
+    }
+
+    #[test]
+    fn test_time_weighted_average_sqrt_price() {
+        let history = test_history(&[(0, 100), (10, -100), (20, 0)]);
+        assert_eq!(
+            time_weighted_average_sqrt_price(history),
+            Ok(tick_index_to_sqrt_price(0))
+        );
+    }
+
+    #[test]
+    fn test_from_whirlpool() {
+        let whirlpool = WhirlpoolFacade {
+            tick_current_index: 123,
+            ..WhirlpoolFacade::default()
+        };
+        let snapshot = PriceSnapshot::from_whirlpool(456, whirlpool);
+        assert_eq!(snapshot.timestamp, 456);
+        assert_eq!(snapshot.tick_index, 123);
+    }
+
+    #[test]
+    fn test_time_in_range() {
+        let history = test_history(&[(0, -20), (10, 0), (30, 10), (35, 20), (50, 0)]);
+        assert_eq!(time_in_range(history.clone(), 0, 20), Ok(25));
+        assert_eq!(time_in_range(history.clone(), 20, 0), Ok(25));
+        assert_eq!(time_in_range(history.clone(), -20, 21), Ok(50));
+        assert_eq!(time_in_range(history, 100, 200), Ok(0));
+    }
+
+    #[test]
+    #[cfg(feature = "floats")]
+    fn test_realized_volatility() {
+        let history = test_history(&[(0, 0), (10, 0), (20, 0)]);
+        assert_eq!(realized_volatility(history, 1), Ok(0.0));
+
+        let history = test_history(&[(0, 0), (1, 100), (2, 0), (4, 100)]);
+        let log_return = 100.0 * log(1.0001);
+        let expected = sqrt(3.0 * log_return * log_return / 4.0 * 100.0);
+        let result = realized_volatility(history, 100).unwrap();
+        assert!((result - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_history() {
+        assert_eq!(
+            time_weighted_average_tick_index(test_history(&[])),
+            Err(INSUFFICIENT_PRICE_HISTORY)
+        );
+        assert_eq!(
+            time_weighted_average_tick_index(test_history(&[(0, 10)])),
+            Err(INSUFFICIENT_PRICE_HISTORY)
+        );
+        assert_eq!(
+            time_weighted_average_tick_index(test_history(&[(5, 10), (5, 20)])),
+            Err(INSUFFICIENT_PRICE_HISTORY)
+        );
+        assert_eq!(
+            time_in_range(test_history(&[(5, 10), (4, 20)]), 0, 10),
+            Err(INVALID_TIMESTAMP)
+        );
+    }
+}
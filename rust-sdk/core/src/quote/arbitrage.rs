@@ -0,0 +1,339 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{
+    prepare_quoter, ArbitrageHop, ArbitrageOpportunities, ArbitrageOpportunity, ErrorCode,
+    RouteGraph, RoutePool, SwapQuoter, ZERO_TRADABLE_AMOUNT,
+};
+
+/// Finds profitable swap cycles between the whirlpools of a graph.
+///
+/// A cycle starts and ends with the same token and goes through two or three whirlpools, for
+/// example the same pair at different tick spacings (A/B, A/B) or a triangle (A/B, B/C, C/A).
+/// Every hop is simulated as a separate exact in swap, including the swap fee and the transfer
+/// fees of both tokens. For every cycle the input amount with the highest profit between one and
+/// `max_token_in` is searched for.
+///
+/// # Arguments
+/// - `max_token_in`: The maximum input amount of a cycle.
+/// - `graph`: The whirlpools to look for cycles in.
+///
+/// # Returns
+/// The profitable cycles, sorted by profit in descending order.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn find_arbitrage_opportunities(
+    max_token_in: u64,
+    graph: RouteGraph,
+) -> Result<ArbitrageOpportunities, ErrorCode> {
+    if max_token_in == 0 {
+        return Err(ZERO_TRADABLE_AMOUNT);
+    }
+
+    let quoters = graph
+        .pools
+        .iter()
+        .map(prepare_quoter)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut opportunities = Vec::new();
+    for cycle in find_cycles(&graph.pools) {
+        let Some((token_in, token_out)) = optimize_cycle(&quoters, &cycle, max_token_in) else {
+            continue;
+        };
+        if token_out <= token_in {
+            continue;
+        }
+
+        let mut hops = Vec::with_capacity(cycle.len());
+        let mut token_est_in = token_in;
+        for &(pool, a_to_b) in &cycle {
+            let quote = quoters[pool].quote_by_input_token(token_est_in, a_to_b, 0)?;
+            hops.push(ArbitrageHop {
+                whirlpool: graph.pools[pool].address,
+                a_to_b,
+                token_est_in: quote.token_in,
+                token_est_out: quote.token_est_out,
+            });
+            token_est_in = quote.token_est_out;
+        }
+
+        let (first_pool, first_a_to_b) = cycle[0];
+        let token_mint = if first_a_to_b {
+            graph.pools[first_pool].token_mint_a
+        } else {
+            graph.pools[first_pool].token_mint_b
+        };
+        opportunities.push(ArbitrageOpportunity {
+            token_mint,
+            hops,
+            token_in,
+            token_out,
+            profit: token_out - token_in,
+        });
+    }
+
+    opportunities.sort_by_key(|opportunity| Reverse(opportunity.profit));
+    Ok(ArbitrageOpportunities { opportunities })
+}
+
+// Private functions
+
+// A cycle is a list of (pool index, a_to_b) hops that ends with the token it starts with.
+type Cycle = Vec<(usize, bool)>;
+
+// Every cycle is only returned once per direction by requiring the first pool to have the
+// lowest index of the cycle.
+fn find_cycles(pools: &[RoutePool]) -> Vec<Cycle> {
+    let mut pools_by_mint: BTreeMap<&[u8], Vec<usize>> = BTreeMap::new();
+    for (i, pool) in pools.iter().enumerate() {
+        pools_by_mint.entry(&pool.token_mint_a).or_default().push(i);
+        pools_by_mint.entry(&pool.token_mint_b).or_default().push(i);
+    }
+
+    // Returns the direction of the hop through `pool` that starts at `mint` and the mint it ends at
+    let hop_from = |pool: usize, mint: &[u8]| -> (bool, &[u8]) {
+        if pools[pool].token_mint_a == mint {
+            (true, &pools[pool].token_mint_b)
+        } else {
+            (false, &pools[pool].token_mint_a)
+        }
+    };
+
+    let mut cycles = Vec::new();
+    for (first, pool) in pools.iter().enumerate() {
+        for a_to_b_one in [true, false] {
+            let (token_mint_start, token_mint_one): (&[u8], &[u8]) = if a_to_b_one {
+                (&pool.token_mint_a, &pool.token_mint_b)
+            } else {
+                (&pool.token_mint_b, &pool.token_mint_a)
+            };
+            if token_mint_start == token_mint_one {
+                continue;
+            }
+            for &second in pools_by_mint.get(token_mint_one).into_iter().flatten() {
+                if second <= first {
+                    continue;
+                }
+                let (a_to_b_two, token_mint_two) = hop_from(second, token_mint_one);
+                if token_mint_two == token_mint_start {
+                    cycles.push(vec![(first, a_to_b_one), (second, a_to_b_two)]);
+                    continue;
+                }
+                for &third in pools_by_mint.get(token_mint_two).into_iter().flatten() {
+                    if third <= first || third == second {
+                        continue;
+                    }
+                    let (a_to_b_three, token_mint_three) = hop_from(third, token_mint_two);
+                    if token_mint_three == token_mint_start {
+                        cycles.push(vec![
+                            (first, a_to_b_one),
+                            (second, a_to_b_two),
+                            (third, a_to_b_three),
+                        ]);
+                    }
+                }
+            }
+        }
+    }
+    cycles
+}
+
+fn quote_cycle(quoters: &[SwapQuoter], cycle: &Cycle, token_in: u64) -> Option<u64> {
+    cycle
+        .iter()
+        .try_fold(token_in, |token_est_in, &(pool, a_to_b)| {
+            quoters[pool]
+                .quote_by_input_token(token_est_in, a_to_b, 0)
+                .ok()
+                .map(|quote| quote.token_est_out)
+        })
+}
+
+// The profit of a cycle first increases with the input amount and then decreases once the price
+// impact outweighs the price difference, so the optimal input amount is found with a ternary
+// search. Input amounts that can not be swapped (e.g. not enough tick arrays) count as a loss.
+fn optimize_cycle(quoters: &[SwapQuoter], cycle: &Cycle, max_token_in: u64) -> Option<(u64, u64)> {
+    let profit = |token_in: u64| {
+        quote_cycle(quoters, cycle, token_in)
+            .map_or(i128::MIN, |token_out| token_out as i128 - token_in as i128)
+    };
+
+    let mut low = 1u64;
+    let mut high = max_token_in;
+    while high - low > 2 {
+        let third = (high - low) / 3;
+        let mid_low = low + third;
+        let mid_high = high - third;
+        if profit(mid_low) < profit(mid_high) {
+            low = mid_low + 1;
+        } else {
+            high = mid_high;
+        }
+    }
+
+    (low..=high)
+        .filter_map(|token_in| {
+            quote_cycle(quoters, cycle, token_in).map(|token_out| (token_in, token_out))
+        })
+        .max_by_key(|&(token_in, token_out)| token_out as i128 - token_in as i128)
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{
+        sqrt_price_to_tick_index, TickArrayFacade, TickFacade, TransferFee, WhirlpoolFacade,
+        TICK_ARRAY_SIZE,
+    };
+
+    const MINT_A: [u8; 32] = [1; 32];
+    const MINT_B: [u8; 32] = [2; 32];
+    const MINT_C: [u8; 32] = [3; 32];
+
+    fn test_tick_array(start_tick_index: i32) -> TickArrayFacade {
+        TickArrayFacade {
+            start_tick_index,
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        }
+    }
+
+    fn test_pool(
+        address: u8,
+        token_mint_a: [u8; 32],
+        token_mint_b: [u8; 32],
+        sqrt_price: u128,
+    ) -> RoutePool {
+        RoutePool {
+            address: [address; 32],
+            token_mint_a,
+            token_mint_b,
+            whirlpool: WhirlpoolFacade {
+                tick_current_index: sqrt_price_to_tick_index(sqrt_price),
+                fee_rate: 3000,
+                liquidity: 100000000,
+                sqrt_price,
+                tick_spacing: 2,
+                ..WhirlpoolFacade::default()
+            },
+            tick_arrays: [-352, -176, 0, 176, 352]
+                .into_iter()
+                .map(test_tick_array)
+                .collect(),
+            transfer_fee_a: None,
+            transfer_fee_b: None,
+        }
+    }
+
+    // sqrt(1.02) * 2^64
+    const SQRT_PRICE_1_02: u128 = 18630298286913101824;
+
+    #[test]
+    fn test_same_pair_price_difference() {
+        let graph = RouteGraph {
+            pools: vec![
+                test_pool(10, MINT_A, MINT_B, 1 << 64),
+                test_pool(11, MINT_A, MINT_B, SQRT_PRICE_1_02),
+            ],
+        };
+        let opportunities = find_arbitrage_opportunities(1000000, graph.clone())
+            .unwrap()
+            .opportunities;
+        assert_eq!(opportunities.len(), 1);
+
+        let opportunity = &opportunities[0];
+        assert_eq!(opportunity.token_mint, MINT_B);
+        assert_eq!(opportunity.hops.len(), 2);
+        assert_eq!(opportunity.hops[0].whirlpool, [10; 32]);
+        assert!(!opportunity.hops[0].a_to_b);
+        assert_eq!(opportunity.hops[1].whirlpool, [11; 32]);
+        assert!(opportunity.hops[1].a_to_b);
+        assert_eq!(opportunity.hops[0].token_est_in, opportunity.token_in);
+        assert_eq!(opportunity.hops[1].token_est_out, opportunity.token_out);
+        assert_eq!(
+            opportunity.profit,
+            opportunity.token_out - opportunity.token_in
+        );
+        assert!(opportunity.profit > 0);
+
+        // Swapping a different amount through the same cycle is not more profitable
+        let quoters = graph
+            .pools
+            .iter()
+            .map(prepare_quoter)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let cycle = vec![(0, false), (1, true)];
+        for token_in in [
+            opportunity.token_in / 2,
+            opportunity.token_in - 1,
+            opportunity.token_in + 1,
+            opportunity.token_in * 2,
+        ] {
+            let token_out = quote_cycle(&quoters, &cycle, token_in).unwrap();
+            assert!(token_out as i128 - token_in as i128 <= opportunity.profit as i128);
+        }
+    }
+
+    #[test]
+    fn test_same_price_is_not_profitable() {
+        let graph = RouteGraph {
+            pools: vec![
+                test_pool(10, MINT_A, MINT_B, 1 << 64),
+                test_pool(11, MINT_A, MINT_B, 1 << 64),
+            ],
+        };
+        let opportunities = find_arbitrage_opportunities(1000000, graph)
+            .unwrap()
+            .opportunities;
+        assert!(opportunities.is_empty());
+    }
+
+    #[test]
+    fn test_transfer_fee_removes_profit() {
+        let mut pool = test_pool(11, MINT_A, MINT_B, SQRT_PRICE_1_02);
+        pool.transfer_fee_a = Some(TransferFee::new(200));
+        let graph = RouteGraph {
+            pools: vec![test_pool(10, MINT_A, MINT_B, 1 << 64), pool],
+        };
+        let opportunities = find_arbitrage_opportunities(1000000, graph)
+            .unwrap()
+            .opportunities;
+        assert!(opportunities.is_empty());
+    }
+
+    #[test]
+    fn test_triangle() {
+        let graph = RouteGraph {
+            pools: vec![
+                test_pool(10, MINT_A, MINT_B, 1 << 64),
+                test_pool(11, MINT_B, MINT_C, 1 << 64),
+                test_pool(12, MINT_C, MINT_A, SQRT_PRICE_1_02),
+            ],
+        };
+        let opportunities = find_arbitrage_opportunities(1000000, graph)
+            .unwrap()
+            .opportunities;
+        assert_eq!(opportunities.len(), 1);
+
+        let opportunity = &opportunities[0];
+        assert_eq!(opportunity.token_mint, MINT_A);
+        assert_eq!(
+            opportunity
+                .hops
+                .iter()
+                .map(|hop| (hop.whirlpool, hop.a_to_b))
+                .collect::<Vec<_>>(),
+            vec![([10; 32], true), ([11; 32], true), ([12; 32], true)]
+        );
+        assert!(opportunity.profit > 0);
+    }
+
+    #[test]
+    fn test_zero_max_token_in() {
+        let result = find_arbitrage_opportunities(0, RouteGraph::default());
+        assert_eq!(result, Err(ZERO_TRADABLE_AMOUNT));
+    }
+}
@@ -4,8 +4,10 @@ use ethnum::U256;
 use orca_whirlpools_macros::wasm_expose;
 
 use crate::{
-    try_apply_transfer_fee, CollectFeesQuote, ErrorCode, PositionFacade, TickFacade, TransferFee,
-    WhirlpoolFacade, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW,
+    try_apply_swap, try_apply_swap_transfer_fee, try_apply_transfer_fee, CollectFeesQuote,
+    CollectFeesSimulation, CollectFeesSimulationQuote, ErrorCode, PositionFacade, TickArrayFacade,
+    TickArrays, TickFacade, TransferFee, WhirlpoolFacade, AMOUNT_EXCEEDS_MAX_U64,
+    ARITHMETIC_OVERFLOW,
 };
 
 /// Calculate fees owed for a position
@@ -96,9 +98,74 @@ pub fn collect_fees_quote(
     })
 }
 
+/// Calculate fees owed for positions after a list of swaps that have not happened yet
+///
+/// The swaps are applied in order to the whirlpool and tick arrays the same way the program
+/// does, including the protocol fee and the updates of the ticks that are crossed. The ticks of
+/// the positions are updated when they are crossed as well, so they don't need to be part of the
+/// tick arrays. The swap amounts are the amounts the user transfers, so the transfer fee on the
+/// input (or output) token is taken out of (or added to) them before the swap.
+///
+/// # Paramters
+/// - `whirlpool`: The whirlpool state
+/// - `tick_arrays`: The tick arrays needed for the swaps
+/// - `simulation`: The swaps to apply and the positions to quote
+/// - `timestamp`: The timestamp at which the swaps happen
+/// - `transfer_fee_a`: The transfer fee for token A
+/// - `transfer_fee_b`: The transfer fee for token B
+///
+/// # Returns
+/// - `CollectFeesSimulationQuote`: The whirlpool state after the swaps and the fees owed for
+///   every position
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn collect_fees_quote_after_swaps(
+    whirlpool: WhirlpoolFacade,
+    tick_arrays: TickArrays,
+    simulation: CollectFeesSimulation,
+    timestamp: u64,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<CollectFeesSimulationQuote, ErrorCode> {
+    let mut whirlpool = whirlpool;
+    let mut tick_arrays: [Option<TickArrayFacade>; 6] = tick_arrays.into();
+    let mut positions = simulation.positions;
+
+    for swap in simulation.swaps {
+        let swap = try_apply_swap_transfer_fee(swap, transfer_fee_a, transfer_fee_b)?;
+        let swap_result = try_apply_swap(swap, &mut whirlpool, &mut tick_arrays, timestamp)?;
+        for crossing in &swap_result.crossed_ticks {
+            for position in &mut positions {
+                if position.position.tick_lower_index == crossing.tick_index {
+                    crossing.apply(&mut position.tick_lower, &whirlpool.reward_infos);
+                }
+                if position.position.tick_upper_index == crossing.tick_index {
+                    crossing.apply(&mut position.tick_upper, &whirlpool.reward_infos);
+                }
+            }
+        }
+    }
+
+    let fees = positions
+        .into_iter()
+        .map(|position| {
+            collect_fees_quote(
+                whirlpool,
+                position.position,
+                position.tick_lower,
+                position.tick_upper,
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CollectFeesSimulationQuote { whirlpool, fees })
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use super::*;
+    use crate::{CollectFeesPosition, PendingSwap, INVALID_TIMESTAMP, TICK_ARRAY_SIZE};
 
     fn test_whirlpool(tick_index: i32) -> WhirlpoolFacade {
         WhirlpoolFacade {
@@ -219,4 +286,240 @@ mod tests {
         assert_eq!(result.fee_owed_a, 623);
         assert_eq!(result.fee_owed_b, 560);
     }
+
+    const LIQUIDITY: u128 = 1000000000000;
+
+    fn test_pool() -> (WhirlpoolFacade, [TickArrayFacade; 5]) {
+        let whirlpool = WhirlpoolFacade {
+            tick_spacing: 2,
+            fee_rate: 3000,
+            protocol_fee_rate: 300,
+            liquidity: 2 * LIQUIDITY,
+            sqrt_price: 1 << 64,
+            ..WhirlpoolFacade::default()
+        };
+        let mut tick_arrays = [-352, -176, 0, 176, 352].map(|start_tick_index| TickArrayFacade {
+            start_tick_index,
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        });
+        tick_arrays[1].ticks[85] = TickFacade {
+            initialized: true,
+            liquidity_net: LIQUIDITY as i128,
+            ..TickFacade::default()
+        };
+        tick_arrays[2].ticks[5] = TickFacade {
+            initialized: true,
+            liquidity_net: -(LIQUIDITY as i128),
+            ..TickFacade::default()
+        };
+        (whirlpool, tick_arrays)
+    }
+
+    fn test_simulation(swaps: Vec<PendingSwap>) -> CollectFeesSimulation {
+        CollectFeesSimulation {
+            swaps,
+            positions: vec![CollectFeesPosition {
+                position: PositionFacade {
+                    liquidity: LIQUIDITY,
+                    tick_lower_index: -6,
+                    tick_upper_index: 10,
+                    ..PositionFacade::default()
+                },
+                tick_lower: TickFacade {
+                    initialized: true,
+                    liquidity_net: LIQUIDITY as i128,
+                    ..TickFacade::default()
+                },
+                tick_upper: TickFacade {
+                    initialized: true,
+                    liquidity_net: -(LIQUIDITY as i128),
+                    ..TickFacade::default()
+                },
+            }],
+        }
+    }
+
+    fn test_swap(amount: u64, a_to_b: bool) -> PendingSwap {
+        PendingSwap {
+            amount,
+            amount_specified_is_input: true,
+            a_to_b,
+            sqrt_price_limit: 0,
+        }
+    }
+
+    #[test]
+    fn test_after_no_swaps() {
+        let (whirlpool, tick_arrays) = test_pool();
+        let simulation = test_simulation(vec![]);
+        let position = simulation.positions[0];
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.whirlpool, whirlpool);
+        assert_eq!(
+            result.fees,
+            vec![collect_fees_quote(
+                whirlpool,
+                position.position,
+                position.tick_lower,
+                position.tick_upper,
+                None,
+                None
+            )
+            .unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_after_swap_in_range() {
+        let (whirlpool, tick_arrays) = test_pool();
+        let simulation = test_simulation(vec![test_swap(100000000, true)]);
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        // 0.3% swap fee, 3% of it goes to the protocol and the position owns half the liquidity
+        assert_eq!(result.fees[0].fee_owed_a, 145499);
+        assert_eq!(result.fees[0].fee_owed_b, 0);
+        assert!(result.whirlpool.sqrt_price < whirlpool.sqrt_price);
+        assert_eq!(result.whirlpool.liquidity, whirlpool.liquidity);
+        assert_eq!(result.whirlpool.fee_growth_global_b, 0);
+    }
+
+    #[test]
+    fn test_after_swap_transfer_fee() {
+        let (whirlpool, tick_arrays) = test_pool();
+        let transfer_fee = TransferFee::new(100);
+
+        // 1% of the input amount is withheld by the input token
+        let simulation = test_simulation(vec![test_swap(100000000, true)]);
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            Some(transfer_fee),
+            None,
+        )
+        .unwrap();
+        let simulation = test_simulation(vec![test_swap(99000000, true)]);
+        let expected = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.whirlpool, expected.whirlpool);
+        assert_eq!(
+            result.fees[0].fee_owed_a,
+            try_apply_transfer_fee(expected.fees[0].fee_owed_a, transfer_fee).unwrap()
+        );
+
+        // The output amount is increased by the transfer fee of the output token
+        let mut swap = test_swap(99000000, true);
+        swap.amount_specified_is_input = false;
+        let simulation = test_simulation(vec![swap]);
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            Some(transfer_fee),
+            Some(transfer_fee),
+        )
+        .unwrap();
+        swap.amount = 100000000;
+        let simulation = test_simulation(vec![swap]);
+        let expected = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.whirlpool, expected.whirlpool);
+    }
+
+    #[test]
+    fn test_after_swaps_out_of_range() {
+        let (whirlpool, tick_arrays) = test_pool();
+        let simulation = test_simulation(vec![test_swap(2000000000, false)]);
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation.clone(),
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(result.whirlpool.tick_current_index >= 10);
+        assert_eq!(result.whirlpool.liquidity, LIQUIDITY);
+        let fee_owed_b = result.fees[0].fee_owed_b;
+        assert!(fee_owed_b > 0);
+        assert_eq!(result.fees[0].fee_owed_a, 0);
+
+        // A swap that stays above the range doesn't earn the position any fees
+        let mut simulation = simulation;
+        simulation.swaps.push(test_swap(100000, true));
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(result.whirlpool.tick_current_index >= 10);
+        assert!(result.whirlpool.fee_growth_global_a > 0);
+        assert_eq!(result.fees[0].fee_owed_a, 0);
+        assert_eq!(result.fees[0].fee_owed_b, fee_owed_b);
+    }
+
+    #[test]
+    fn test_after_swaps_rewards() {
+        let (mut whirlpool, tick_arrays) = test_pool();
+        whirlpool.reward_last_updated_timestamp = 100;
+        whirlpool.reward_infos[0].emissions_per_second_x64 = 2 * LIQUIDITY;
+        let simulation = test_simulation(vec![test_swap(1000, true)]);
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation.clone(),
+            110,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.whirlpool.reward_last_updated_timestamp, 110);
+        assert_eq!(result.whirlpool.reward_infos[0].growth_global_x64, 10);
+
+        let result = collect_fees_quote_after_swaps(
+            whirlpool,
+            tick_arrays.into(),
+            simulation,
+            90,
+            None,
+            None,
+        );
+        assert_eq!(result, Err(INVALID_TIMESTAMP));
+    }
 }
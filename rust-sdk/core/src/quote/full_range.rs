@@ -0,0 +1,425 @@
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use ethnum::U256;
+
+use crate::{
+    decrease_liquidity_quote, get_full_range_tick_indexes, increase_liquidity_quote_a,
+    increase_liquidity_quote_b, tick_index_to_sqrt_price, try_get_amount_delta_a,
+    try_get_amount_delta_b, DecreaseLiquidityQuote, ErrorCode, IncreaseLiquidityQuote,
+    InitializePoolQuote, PoolReserves, TransferFee, MAX_SQRT_PRICE, MIN_SQRT_PRICE,
+    SQRT_PRICE_OUT_OF_BOUNDS, TOKEN_MAX_EXCEEDED, U128, ZERO_TRADABLE_AMOUNT,
+};
+
+/// Calculate the quote for increasing liquidity of a full range position given a token a amount
+///
+/// # Parameters
+/// - `token_amount_a` - The amount of token a to increase
+/// - `slippage_tolerance` - The slippage tolerance in bps
+/// - `current_sqrt_price` - The current sqrt price of the pool
+/// - `tick_spacing` - The tick spacing of the pool
+/// - `transfer_fee_a` - The transfer fee for token A in bps
+/// - `transfer_fee_b` - The transfer fee for token B in bps
+///
+/// # Returns
+/// - An IncreaseLiquidityQuote struct containing the estimated token amounts
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn increase_liquidity_quote_full_range_a(
+    token_amount_a: u64,
+    slippage_tolerance_bps: u16,
+    current_sqrt_price: U128,
+    tick_spacing: u16,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<IncreaseLiquidityQuote, ErrorCode> {
+    let tick_range = get_full_range_tick_indexes(tick_spacing);
+    increase_liquidity_quote_a(
+        token_amount_a,
+        slippage_tolerance_bps,
+        current_sqrt_price,
+        tick_range.tick_lower_index,
+        tick_range.tick_upper_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Calculate the quote for increasing liquidity of a full range position given a token b amount
+///
+/// # Parameters
+/// - `token_amount_b` - The amount of token b to increase
+/// - `slippage_tolerance` - The slippage tolerance in bps
+/// - `current_sqrt_price` - The current sqrt price of the pool
+/// - `tick_spacing` - The tick spacing of the pool
+/// - `transfer_fee_a` - The transfer fee for token A in bps
+/// - `transfer_fee_b` - The transfer fee for token B in bps
+///
+/// # Returns
+/// - An IncreaseLiquidityQuote struct containing the estimated token amounts
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn increase_liquidity_quote_full_range_b(
+    token_amount_b: u64,
+    slippage_tolerance_bps: u16,
+    current_sqrt_price: U128,
+    tick_spacing: u16,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<IncreaseLiquidityQuote, ErrorCode> {
+    let tick_range = get_full_range_tick_indexes(tick_spacing);
+    increase_liquidity_quote_b(
+        token_amount_b,
+        slippage_tolerance_bps,
+        current_sqrt_price,
+        tick_range.tick_lower_index,
+        tick_range.tick_upper_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Calculate the quote for decreasing liquidity of a full range position
+///
+/// # Parameters
+/// - `liquidity_delta` - The amount of liquidity to decrease
+/// - `slippage_tolerance` - The slippage tolerance in bps
+/// - `current_sqrt_price` - The current sqrt price of the pool
+/// - `tick_spacing` - The tick spacing of the pool
+/// - `transfer_fee_a` - The transfer fee for token A in bps
+/// - `transfer_fee_b` - The transfer fee for token B in bps
+///
+/// # Returns
+/// - A DecreaseLiquidityQuote struct containing the estimated token amounts
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn decrease_liquidity_quote_full_range(
+    liquidity_delta: U128,
+    slippage_tolerance_bps: u16,
+    current_sqrt_price: U128,
+    tick_spacing: u16,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<DecreaseLiquidityQuote, ErrorCode> {
+    let tick_range = get_full_range_tick_indexes(tick_spacing);
+    decrease_liquidity_quote(
+        liquidity_delta,
+        slippage_tolerance_bps,
+        current_sqrt_price,
+        tick_range.tick_lower_index,
+        tick_range.tick_upper_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Calculate the sqrt price at which the ratio of two token amounts is the price,
+/// like the price of a constant product pool with these reserves.
+///
+/// # Parameters
+/// - `token_amount_a` - The amount of token a
+/// - `token_amount_b` - The amount of token b
+///
+/// # Returns
+/// - A u128 representing the sqrt price, rounded down
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn sqrt_price_from_token_amounts(
+    token_amount_a: u64,
+    token_amount_b: u64,
+) -> Result<U128, ErrorCode> {
+    if token_amount_a == 0 || token_amount_b == 0 {
+        return Err(ZERO_TRADABLE_AMOUNT);
+    }
+
+    let price_x128: U256 = (<U256>::from(token_amount_b) << 128) / <U256>::from(token_amount_a);
+    let sqrt_price: u128 = sqrt_u256(price_x128)
+        .try_into()
+        .map_err(|_| SQRT_PRICE_OUT_OF_BOUNDS)?;
+
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return Err(SQRT_PRICE_OUT_OF_BOUNDS);
+    }
+
+    Ok(sqrt_price.into())
+}
+
+/// Calculate the token amounts held by full range liquidity at a sqrt price.
+/// In a full range only pool this is the total amount of tokens in the pool,
+/// which behaves like the reserves of a constant product pool.
+///
+/// # Parameters
+/// - `liquidity` - The full range liquidity
+/// - `current_sqrt_price` - The current sqrt price of the pool
+/// - `tick_spacing` - The tick spacing of the pool
+///
+/// # Returns
+/// - A PoolReserves struct containing the token amounts, rounded down
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn full_range_pool_reserves(
+    liquidity: U128,
+    current_sqrt_price: U128,
+    tick_spacing: u16,
+) -> Result<PoolReserves, ErrorCode> {
+    let tick_range = get_full_range_tick_indexes(tick_spacing);
+    let liquidity: u128 = liquidity.into();
+    let sqrt_price_lower: u128 = tick_index_to_sqrt_price(tick_range.tick_lower_index).into();
+    let sqrt_price_upper: u128 = tick_index_to_sqrt_price(tick_range.tick_upper_index).into();
+    let current_sqrt_price: u128 = current_sqrt_price.into();
+    let current_sqrt_price = current_sqrt_price.clamp(sqrt_price_lower, sqrt_price_upper);
+
+    Ok(PoolReserves {
+        token_a: try_get_amount_delta_a(
+            current_sqrt_price.into(),
+            sqrt_price_upper.into(),
+            liquidity.into(),
+            false,
+        )?,
+        token_b: try_get_amount_delta_b(
+            sqrt_price_lower.into(),
+            current_sqrt_price.into(),
+            liquidity.into(),
+            false,
+        )?,
+    })
+}
+
+/// Calculate the initial price and full range deposit for a new pool from the token amounts
+/// that should be deposited. The initial price is the ratio of the token amounts. The deposit
+/// uses as much of both token amounts as possible without exceeding either of them.
+///
+/// # Parameters
+/// - `token_max_a` - The maximum amount of token a to deposit
+/// - `token_max_b` - The maximum amount of token b to deposit
+/// - `tick_spacing` - The tick spacing of the pool
+/// - `transfer_fee_a` - The transfer fee for token A in bps
+/// - `transfer_fee_b` - The transfer fee for token B in bps
+///
+/// # Returns
+/// - An InitializePoolQuote struct containing the initial sqrt price and the deposit
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn initialize_full_range_pool_quote(
+    token_max_a: u64,
+    token_max_b: u64,
+    tick_spacing: u16,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<InitializePoolQuote, ErrorCode> {
+    let initial_sqrt_price: u128 = sqrt_price_from_token_amounts(token_max_a, token_max_b)?.into();
+
+    let mut quote = increase_liquidity_quote_full_range_a(
+        token_max_a,
+        0,
+        initial_sqrt_price.into(),
+        tick_spacing,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+    let exceeds_max = |quote: &IncreaseLiquidityQuote| {
+        quote.token_max_a > token_max_a || quote.token_max_b > token_max_b
+    };
+    if exceeds_max(&quote) {
+        quote = increase_liquidity_quote_full_range_b(
+            token_max_b,
+            0,
+            initial_sqrt_price.into(),
+            tick_spacing,
+            transfer_fee_a,
+            transfer_fee_b,
+        )?;
+    }
+    // The deposit for the token B amount has to fit within the token A amount as well
+    if exceeds_max(&quote) {
+        return Err(TOKEN_MAX_EXCEEDED);
+    }
+
+    Ok(InitializePoolQuote {
+        initial_sqrt_price,
+        liquidity_delta: quote.liquidity_delta,
+        token_est_a: quote.token_est_a,
+        token_est_b: quote.token_est_b,
+        token_max_a: quote.token_max_a,
+        token_max_b: quote.token_max_b,
+    })
+}
+
+// Private functions
+
+fn sqrt_u256(value: U256) -> U256 {
+    if value < 2 {
+        return value;
+    }
+    let mut result = U256::ONE << (256 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (result + value / result) >> 1;
+        if next >= result {
+            return result;
+        }
+        result = next;
+    }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::FULL_RANGE_ONLY_TICK_SPACING_THRESHOLD;
+
+    const SPLASH_TICK_SPACING: u16 = FULL_RANGE_ONLY_TICK_SPACING_THRESHOLD;
+
+    #[test]
+    fn test_increase_liquidity_quote_full_range() {
+        let tick_range = get_full_range_tick_indexes(SPLASH_TICK_SPACING);
+
+        let result = increase_liquidity_quote_full_range_a(
+            1000000,
+            100,
+            1 << 64,
+            SPLASH_TICK_SPACING,
+            None,
+            None,
+        );
+        let expected = increase_liquidity_quote_a(
+            1000000,
+            100,
+            1 << 64,
+            tick_range.tick_lower_index,
+            tick_range.tick_upper_index,
+            None,
+            None,
+        );
+        assert_eq!(result, expected);
+        assert!(result.unwrap().liquidity_delta > 0);
+
+        let result = increase_liquidity_quote_full_range_b(
+            1000000,
+            100,
+            1 << 64,
+            SPLASH_TICK_SPACING,
+            None,
+            None,
+        );
+        let expected = increase_liquidity_quote_b(
+            1000000,
+            100,
+            1 << 64,
+            tick_range.tick_lower_index,
+            tick_range.tick_upper_index,
+            None,
+            None,
+        );
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_decrease_liquidity_quote_full_range() {
+        let tick_range = get_full_range_tick_indexes(SPLASH_TICK_SPACING);
+        let result = decrease_liquidity_quote_full_range(
+            1000000,
+            100,
+            1 << 64,
+            SPLASH_TICK_SPACING,
+            None,
+            None,
+        );
+        let expected = decrease_liquidity_quote(
+            1000000,
+            100,
+            1 << 64,
+            tick_range.tick_lower_index,
+            tick_range.tick_upper_index,
+            None,
+            None,
+        );
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_sqrt_price_from_token_amounts() {
+        assert_eq!(sqrt_price_from_token_amounts(1000, 1000), Ok(1 << 64));
+        assert_eq!(sqrt_price_from_token_amounts(1000, 4000), Ok(2 << 64));
+        assert_eq!(sqrt_price_from_token_amounts(4000, 1000), Ok(1 << 63));
+        assert_eq!(
+            sqrt_price_from_token_amounts(1, 2),
+            Ok(26087635650665564424)
+        );
+        assert_eq!(
+            sqrt_price_from_token_amounts(0, 1000),
+            Err(ZERO_TRADABLE_AMOUNT)
+        );
+        assert_eq!(
+            sqrt_price_from_token_amounts(u64::MAX, 1),
+            Err(SQRT_PRICE_OUT_OF_BOUNDS)
+        );
+    }
+
+    #[test]
+    fn test_full_range_pool_reserves() {
+        let reserves = full_range_pool_reserves(1000000, 1 << 64, SPLASH_TICK_SPACING).unwrap();
+        assert_eq!(reserves.token_a, 999999);
+        assert_eq!(reserves.token_b, 999999);
+
+        let reserves = full_range_pool_reserves(1000000, 2 << 64, SPLASH_TICK_SPACING).unwrap();
+        assert_eq!(reserves.token_a, 499999);
+        assert_eq!(reserves.token_b, 1999999);
+    }
+
+    #[test]
+    fn test_initialize_full_range_pool_quote() {
+        let quote =
+            initialize_full_range_pool_quote(1000000, 4000000, SPLASH_TICK_SPACING, None, None)
+                .unwrap();
+        assert_eq!(quote.initial_sqrt_price, 2 << 64);
+        assert!(quote.liquidity_delta > 0);
+        assert!(quote.token_max_a <= 1000000);
+        assert!(quote.token_max_b <= 4000000);
+        assert!(quote.token_max_a >= 999990);
+        assert!(quote.token_max_b >= 3999960);
+
+        let quote = initialize_full_range_pool_quote(
+            1000000,
+            4000000,
+            SPLASH_TICK_SPACING,
+            Some(TransferFee::new(100)),
+            Some(TransferFee::new(200)),
+        )
+        .unwrap();
+        assert!(quote.token_max_a <= 1000000);
+        assert!(quote.token_max_b <= 4000000);
+        assert!(quote.token_max_a >= 989000 || quote.token_max_b >= 3959000);
+
+        assert_eq!(
+            initialize_full_range_pool_quote(0, 4000000, SPLASH_TICK_SPACING, None, None),
+            Err(ZERO_TRADABLE_AMOUNT)
+        );
+    }
+
+    #[test]
+    fn test_initialize_full_range_pool_quote_within_max() {
+        // Amount ratios in both directions, so that both the token A and the token B quote are used
+        let amounts = [1, 7, 1000, 12345, 1 << 20, 1 << 40, u64::MAX / 3];
+        let transfer_fees = [
+            None,
+            Some(TransferFee::new(100)),
+            Some(TransferFee::new(3333)),
+            Some(TransferFee::new_with_max(500, 3)),
+        ];
+        for token_max_a in amounts {
+            for token_max_b in amounts {
+                for transfer_fee_a in transfer_fees {
+                    for transfer_fee_b in transfer_fees {
+                        let result = initialize_full_range_pool_quote(
+                            token_max_a,
+                            token_max_b,
+                            SPLASH_TICK_SPACING,
+                            transfer_fee_a,
+                            transfer_fee_b,
+                        );
+                        match result {
+                            Ok(quote) => {
+                                assert!(quote.token_max_a <= token_max_a);
+                                assert!(quote.token_max_b <= token_max_b);
+                            }
+                            Err(error) => assert_ne!(error, TOKEN_MAX_EXCEEDED),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
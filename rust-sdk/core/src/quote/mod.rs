@@ -1,9 +1,20 @@
+mod arbitrage;
 mod fees;
+mod full_range;
 mod liquidity;
+mod position;
 mod rewards;
+mod route;
 mod swap;
 
+#[cfg(all(test, not(feature = "wasm")))]
+mod swap_parity;
+
+pub use arbitrage::*;
 pub use fees::*;
+pub use full_range::*;
 pub use liquidity::*;
+pub use position::*;
 pub use rewards::*;
+pub use route::*;
 pub use swap::*;
@@ -0,0 +1,347 @@
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{
+    collect_fees_quote, collect_rewards_quote, decrease_liquidity_quote,
+    get_tick_array_start_tick_index, order_tick_indexes, ClosePositionQuote, ErrorCode,
+    OpenPositionCost, PositionFacade, PositionTokenType, TickFacade, TransferFee, WhirlpoolFacade,
+    ACCOUNT_STORAGE_OVERHEAD, ARITHMETIC_OVERFLOW, METADATA_ACCOUNT_LEN, MINT_ACCOUNT_LEN,
+    POSITION_ACCOUNT_LEN, RENT_EXEMPT_LAMPORTS_PER_BYTE, TICK_ARRAY_ACCOUNT_LEN,
+    TOKEN_2022_POSITION_MINT_LEN, TOKEN_2022_POSITION_MINT_WITH_METADATA_LEN,
+    TOKEN_2022_TOKEN_ACCOUNT_LEN, TOKEN_ACCOUNT_LEN,
+};
+
+/// Calculate the minimum lamports for an account to be rent exempt
+///
+/// # Parameters
+/// - `data_len` - The size of the account data in bytes
+///
+/// # Returns
+/// - A u64 representing the rent exempt lamports
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn get_rent_exempt_lamports(data_len: usize) -> u64 {
+    (data_len as u64 + ACCOUNT_STORAGE_OVERHEAD) * RENT_EXEMPT_LAMPORTS_PER_BYTE
+}
+
+/// Calculate the accounts that need to be created and the rent that needs to be paid
+/// to open a position
+///
+/// # Parameters
+/// - `tick_index_1` - The first tick index of the position
+/// - `tick_index_2` - The second tick index of the position
+/// - `tick_spacing` - The tick spacing of the pool
+/// - `initialized_tick_array_start_indexes` - The start tick indexes of the tick arrays that already exist
+/// - `position_token_type` - The token program and metadata used for the position mint
+///
+/// # Returns
+/// - An OpenPositionCost struct containing the tick arrays to initialize and the rent per account
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn open_position_cost_quote(
+    tick_index_1: i32,
+    tick_index_2: i32,
+    tick_spacing: u16,
+    initialized_tick_array_start_indexes: &[i32],
+    position_token_type: PositionTokenType,
+) -> OpenPositionCost {
+    let tick_range = order_tick_indexes(tick_index_1, tick_index_2);
+    let lower_start_index =
+        get_tick_array_start_tick_index(tick_range.tick_lower_index, tick_spacing);
+    let upper_start_index =
+        get_tick_array_start_tick_index(tick_range.tick_upper_index, tick_spacing);
+
+    let mut tick_array_start_indexes = vec![lower_start_index];
+    if upper_start_index != lower_start_index {
+        tick_array_start_indexes.push(upper_start_index);
+    }
+    tick_array_start_indexes
+        .retain(|start_index| !initialized_tick_array_start_indexes.contains(start_index));
+
+    let (mint_len, token_account_len, metadata_len) = match position_token_type {
+        PositionTokenType::Token => (MINT_ACCOUNT_LEN, TOKEN_ACCOUNT_LEN, None),
+        PositionTokenType::TokenWithMetadata => (
+            MINT_ACCOUNT_LEN,
+            TOKEN_ACCOUNT_LEN,
+            Some(METADATA_ACCOUNT_LEN),
+        ),
+        PositionTokenType::Token2022 => (
+            TOKEN_2022_POSITION_MINT_LEN,
+            TOKEN_2022_TOKEN_ACCOUNT_LEN,
+            None,
+        ),
+        PositionTokenType::Token2022WithMetadata => (
+            TOKEN_2022_POSITION_MINT_WITH_METADATA_LEN,
+            TOKEN_2022_TOKEN_ACCOUNT_LEN,
+            None,
+        ),
+    };
+
+    let tick_array_rent =
+        tick_array_start_indexes.len() as u64 * get_rent_exempt_lamports(TICK_ARRAY_ACCOUNT_LEN);
+    let position_rent = get_rent_exempt_lamports(POSITION_ACCOUNT_LEN);
+    let position_mint_rent = get_rent_exempt_lamports(mint_len);
+    let position_token_account_rent = get_rent_exempt_lamports(token_account_len);
+    let metadata_rent = metadata_len.map_or(0, get_rent_exempt_lamports);
+
+    OpenPositionCost {
+        tick_array_start_indexes,
+        tick_array_rent,
+        position_rent,
+        position_mint_rent,
+        position_token_account_rent,
+        metadata_rent,
+        total_rent: tick_array_rent
+            + position_rent
+            + position_mint_rent
+            + position_token_account_rent
+            + metadata_rent,
+    }
+}
+
+/// Calculate the quote for closing a position, which withdraws all liquidity
+/// and collects all fees and rewards owed
+///
+/// # Parameters
+/// - `whirlpool` - The whirlpool state
+/// - `position` - The position state
+/// - `tick_lower` - The lower tick state
+/// - `tick_upper` - The upper tick state
+/// - `current_timestamp` - The current timestamp
+/// - `slippage_tolerance_bps` - The slippage tolerance in bps
+/// - `transfer_fee_a` - The transfer fee for token A
+/// - `transfer_fee_b` - The transfer fee for token B
+/// - `transfer_fee_1` - The transfer fee for reward token 1
+/// - `transfer_fee_2` - The transfer fee for reward token 2
+/// - `transfer_fee_3` - The transfer fee for reward token 3
+///
+/// # Returns
+/// - A ClosePositionQuote struct containing the amounts received after transfer fees.
+///   The token A and B amounts include both the withdrawn liquidity and the fees owed.
+///   Only the withdrawn liquidity is subject to slippage, the fees and rewards are exact.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn close_position_quote(
+    whirlpool: WhirlpoolFacade,
+    position: PositionFacade,
+    tick_lower: TickFacade,
+    tick_upper: TickFacade,
+    current_timestamp: u64,
+    slippage_tolerance_bps: u16,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+    transfer_fee_1: Option<TransferFee>,
+    transfer_fee_2: Option<TransferFee>,
+    transfer_fee_3: Option<TransferFee>,
+) -> Result<ClosePositionQuote, ErrorCode> {
+    let decrease_quote = decrease_liquidity_quote(
+        position.liquidity.into(),
+        slippage_tolerance_bps,
+        whirlpool.sqrt_price.into(),
+        position.tick_lower_index,
+        position.tick_upper_index,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+    let fees_quote = collect_fees_quote(
+        whirlpool,
+        position,
+        tick_lower,
+        tick_upper,
+        transfer_fee_a,
+        transfer_fee_b,
+    )?;
+    let rewards_quote = collect_rewards_quote(
+        whirlpool,
+        position,
+        tick_lower,
+        tick_upper,
+        current_timestamp,
+        transfer_fee_1,
+        transfer_fee_2,
+        transfer_fee_3,
+    )?;
+
+    let add_fee_a = |amount: u64| amount.checked_add(fees_quote.fee_owed_a);
+    let add_fee_b = |amount: u64| amount.checked_add(fees_quote.fee_owed_b);
+
+    Ok(ClosePositionQuote {
+        liquidity_delta: decrease_quote.liquidity_delta,
+        token_est_a: add_fee_a(decrease_quote.token_est_a).ok_or(ARITHMETIC_OVERFLOW)?,
+        token_est_b: add_fee_b(decrease_quote.token_est_b).ok_or(ARITHMETIC_OVERFLOW)?,
+        token_min_a: add_fee_a(decrease_quote.token_min_a).ok_or(ARITHMETIC_OVERFLOW)?,
+        token_min_b: add_fee_b(decrease_quote.token_min_b).ok_or(ARITHMETIC_OVERFLOW)?,
+        rewards: rewards_quote.rewards,
+    })
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{PositionRewardInfoFacade, WhirlpoolRewardInfoFacade, NUM_REWARDS};
+
+    #[test]
+    fn test_get_rent_exempt_lamports() {
+        assert_eq!(get_rent_exempt_lamports(0), 890880);
+        assert_eq!(get_rent_exempt_lamports(TOKEN_ACCOUNT_LEN), 2039280);
+        assert_eq!(get_rent_exempt_lamports(MINT_ACCOUNT_LEN), 1461600);
+        assert_eq!(get_rent_exempt_lamports(TICK_ARRAY_ACCOUNT_LEN), 70407360);
+    }
+
+    #[test]
+    fn test_open_position_cost_quote_tick_arrays() {
+        let result = open_position_cost_quote(-100, 100, 1, &[], PositionTokenType::Token);
+        assert_eq!(result.tick_array_start_indexes, vec![-176, 88]);
+        assert_eq!(result.tick_array_rent, 140814720);
+
+        let result = open_position_cost_quote(100, -100, 1, &[88], PositionTokenType::Token);
+        assert_eq!(result.tick_array_start_indexes, vec![-176]);
+        assert_eq!(result.tick_array_rent, 70407360);
+
+        let result = open_position_cost_quote(0, 64, 64, &[], PositionTokenType::Token);
+        assert_eq!(result.tick_array_start_indexes, vec![0]);
+
+        let result = open_position_cost_quote(-100, 100, 1, &[-176, 88], PositionTokenType::Token);
+        assert!(result.tick_array_start_indexes.is_empty());
+        assert_eq!(result.tick_array_rent, 0);
+    }
+
+    #[test]
+    fn test_open_position_cost_quote_token_types() {
+        let result = open_position_cost_quote(0, 64, 64, &[0], PositionTokenType::Token);
+        assert_eq!(result.position_rent, 2394240);
+        assert_eq!(result.position_mint_rent, 1461600);
+        assert_eq!(result.position_token_account_rent, 2039280);
+        assert_eq!(result.metadata_rent, 0);
+        assert_eq!(result.total_rent, 5895120);
+
+        let result =
+            open_position_cost_quote(0, 64, 64, &[0], PositionTokenType::TokenWithMetadata);
+        assert_eq!(result.metadata_rent, 5616720);
+        assert_eq!(result.total_rent, 11511840);
+
+        let result = open_position_cost_quote(0, 64, 64, &[0], PositionTokenType::Token2022);
+        assert_eq!(result.position_mint_rent, 2296800);
+        assert_eq!(result.position_token_account_rent, 2074080);
+        assert_eq!(result.metadata_rent, 0);
+        assert_eq!(result.total_rent, 6765120);
+
+        let result =
+            open_position_cost_quote(0, 64, 64, &[0], PositionTokenType::Token2022WithMetadata);
+        assert_eq!(result.position_mint_rent, 4336080);
+        assert_eq!(result.metadata_rent, 0);
+        assert_eq!(result.total_rent, 8804400);
+    }
+
+    fn test_close_whirlpool() -> WhirlpoolFacade {
+        WhirlpoolFacade {
+            tick_current_index: 0,
+            sqrt_price: 1 << 64,
+            fee_growth_global_a: 800,
+            fee_growth_global_b: 1000,
+            reward_infos: [WhirlpoolRewardInfoFacade {
+                growth_global_x64: 500,
+                emissions_per_second_x64: 0,
+            }; NUM_REWARDS],
+            liquidity: 1000000,
+            ..WhirlpoolFacade::default()
+        }
+    }
+
+    fn test_close_position() -> PositionFacade {
+        PositionFacade {
+            liquidity: 1000000,
+            tick_lower_index: -10,
+            tick_upper_index: 10,
+            fee_owed_a: 400,
+            fee_owed_b: 600,
+            reward_infos: [PositionRewardInfoFacade {
+                growth_inside_checkpoint: 500,
+                amount_owed: 100,
+            }; NUM_REWARDS],
+            ..PositionFacade::default()
+        }
+    }
+
+    #[test]
+    fn test_close_position_quote() {
+        let whirlpool = test_close_whirlpool();
+        let position = test_close_position();
+        let tick = TickFacade::default();
+
+        let result = close_position_quote(
+            whirlpool, position, tick, tick, 0, 100, None, None, None, None, None,
+        )
+        .unwrap();
+        let decrease_quote =
+            decrease_liquidity_quote(1000000, 100, 1 << 64, -10, 10, None, None).unwrap();
+        let fees_quote = collect_fees_quote(whirlpool, position, tick, tick, None, None).unwrap();
+        assert_eq!(result.liquidity_delta, 1000000);
+        assert_eq!(
+            result.token_est_a,
+            decrease_quote.token_est_a + fees_quote.fee_owed_a
+        );
+        assert_eq!(
+            result.token_est_b,
+            decrease_quote.token_est_b + fees_quote.fee_owed_b
+        );
+        assert_eq!(
+            result.token_min_a,
+            decrease_quote.token_min_a + fees_quote.fee_owed_a
+        );
+        assert_eq!(
+            result.token_min_b,
+            decrease_quote.token_min_b + fees_quote.fee_owed_b
+        );
+        assert_eq!(result.token_est_a, 499 + 400);
+        assert_eq!(result.token_min_a, 494 + 400);
+        assert_eq!(result.rewards[0].rewards_owed, 100);
+    }
+
+    #[test]
+    fn test_close_position_quote_transfer_fees() {
+        let tick = TickFacade::default();
+        let result = close_position_quote(
+            test_close_whirlpool(),
+            test_close_position(),
+            tick,
+            tick,
+            0,
+            100,
+            Some(TransferFee::new(1000)),
+            Some(TransferFee::new(2000)),
+            Some(TransferFee::new(5000)),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.token_est_a, 449 + 360);
+        assert_eq!(result.token_est_b, 399 + 480);
+        assert_eq!(result.rewards[0].rewards_owed, 50);
+        assert_eq!(result.rewards[1].rewards_owed, 100);
+    }
+
+    #[test]
+    fn test_close_position_quote_empty_position() {
+        let tick = TickFacade::default();
+        let position = PositionFacade {
+            liquidity: 0,
+            ..test_close_position()
+        };
+        let result = close_position_quote(
+            test_close_whirlpool(),
+            position,
+            tick,
+            tick,
+            0,
+            100,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.liquidity_delta, 0);
+        assert_eq!(result.token_est_a, 400);
+        assert_eq!(result.token_min_b, 600);
+    }
+}
@@ -5,8 +5,8 @@ use orca_whirlpools_macros::wasm_expose;
 
 use crate::{
     try_apply_transfer_fee, CollectRewardQuote, CollectRewardsQuote, ErrorCode, PositionFacade,
-    TickFacade, TransferFee, WhirlpoolFacade, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW,
-    NUM_REWARDS,
+    TickFacade, TransferFee, WhirlpoolFacade, WhirlpoolRewardInfoFacade, AMOUNT_EXCEEDS_MAX_U64,
+    ARITHMETIC_OVERFLOW, INVALID_TIMESTAMP, NUM_REWARDS,
 };
 
 /// Calculate rewards owed for a position
@@ -42,13 +42,14 @@ pub fn collect_rewards_quote(
 
     for i in 0..NUM_REWARDS {
         let mut reward_growth: u128 = whirlpool.reward_infos[i].growth_global_x64;
-        if whirlpool.liquidity != 0 {
-            let reward_growth_delta = whirlpool.reward_infos[i]
-                .emissions_per_second_x64
-                .checked_mul(timestamp_delta as u128)
-                .ok_or(ARITHMETIC_OVERFLOW)?
-                / whirlpool.liquidity;
-            reward_growth += <u128>::try_from(reward_growth_delta).unwrap();
+        // Rewards only accrue while the pool has liquidity
+        if let Some(reward_growth_delta) = whirlpool.reward_infos[i]
+            .emissions_per_second_x64
+            .checked_mul(timestamp_delta as u128)
+            .ok_or(ARITHMETIC_OVERFLOW)?
+            .checked_div(whirlpool.liquidity)
+        {
+            reward_growth += reward_growth_delta;
         }
         let mut reward_growth_below = tick_lower.reward_growths_outside[i];
         let mut reward_growth_above = tick_upper.reward_growths_outside[i];
@@ -83,6 +84,31 @@ pub fn collect_rewards_quote(
     })
 }
 
+// Advances the global reward growths of a whirlpool to `timestamp` the same way the program does
+// before every swap and liquidity change.
+pub(crate) fn try_get_next_reward_infos(
+    whirlpool: WhirlpoolFacade,
+    timestamp: u64,
+) -> Result<[WhirlpoolRewardInfoFacade; NUM_REWARDS], ErrorCode> {
+    if timestamp < whirlpool.reward_last_updated_timestamp {
+        return Err(INVALID_TIMESTAMP);
+    }
+    let mut reward_infos = whirlpool.reward_infos;
+    if whirlpool.liquidity == 0 {
+        return Ok(reward_infos);
+    }
+    let timestamp_delta = (timestamp - whirlpool.reward_last_updated_timestamp) as u128;
+    for reward_info in &mut reward_infos {
+        let reward_growth_delta = timestamp_delta
+            .checked_mul(reward_info.emissions_per_second_x64)
+            .map_or(0, |product| product / whirlpool.liquidity);
+        reward_info.growth_global_x64 = reward_info
+            .growth_global_x64
+            .wrapping_add(reward_growth_delta);
+    }
+    Ok(reward_infos)
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use crate::{PositionRewardInfoFacade, WhirlpoolRewardInfoFacade};
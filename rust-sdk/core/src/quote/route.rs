@@ -0,0 +1,730 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{
+    try_apply_transfer_fee, try_get_max_amount_with_slippage_tolerance,
+    try_get_min_amount_with_slippage_tolerance, try_quote_by_input_token,
+    try_quote_by_output_token, try_reverse_apply_transfer_fee, ErrorCode, ExactInSwapQuote,
+    ExactOutSwapQuote, RouteGraph, RouteLeg, RoutePool, SwapQuoter, SwapRoute, TickArrayFacade,
+    TransferFee, ARITHMETIC_OVERFLOW, INTERMEDIATE_TOKEN_AMOUNT_MISMATCH, ROUTE_NOT_FOUND,
+    TOO_MANY_TICK_ARRAYS, ZERO_TRADABLE_AMOUNT,
+};
+
+/// Finds the best route for swapping an exact input amount between two tokens.
+///
+/// Routes go through one or two whirlpools of the graph. If `max_splits` is larger than one,
+/// the input amount can be split across multiple routes that do not share a whirlpool.
+/// Every leg of the resulting route maps onto a single `swap_v2` (one whirlpool) or
+/// `two_hop_swap_v2` (two whirlpools) instruction without a sqrt price limit.
+///
+/// # Arguments
+/// - `token_in`: The input token amount.
+/// - `token_mint_in`: The mint of the input token.
+/// - `token_mint_out`: The mint of the output token.
+/// - `max_splits`: The maximum number of parts the input amount can be split into.
+/// - `slippage_tolerance_bps`: The slippage tolerance in basis points.
+/// - `graph`: The whirlpools that can be used for routing.
+///
+/// # Returns
+/// The route with the highest estimated output amount.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_route_by_input_token(
+    token_in: u64,
+    token_mint_in: &[u8],
+    token_mint_out: &[u8],
+    max_splits: u8,
+    slippage_tolerance_bps: u16,
+    graph: RouteGraph,
+) -> Result<SwapRoute, ErrorCode> {
+    find_route(
+        token_in,
+        true,
+        token_mint_in,
+        token_mint_out,
+        max_splits,
+        slippage_tolerance_bps,
+        &graph,
+    )
+}
+
+/// Finds the best route for swapping into an exact output amount between two tokens.
+///
+/// Routes go through one or two whirlpools of the graph. If `max_splits` is larger than one,
+/// the output amount can be split across multiple routes that do not share a whirlpool.
+/// Every leg of the resulting route maps onto a single `swap_v2` (one whirlpool) or
+/// `two_hop_swap_v2` (two whirlpools) instruction without a sqrt price limit.
+///
+/// # Arguments
+/// - `token_out`: The output token amount.
+/// - `token_mint_in`: The mint of the input token.
+/// - `token_mint_out`: The mint of the output token.
+/// - `max_splits`: The maximum number of parts the output amount can be split into.
+/// - `slippage_tolerance_bps`: The slippage tolerance in basis points.
+/// - `graph`: The whirlpools that can be used for routing.
+///
+/// # Returns
+/// The route with the lowest estimated input amount.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_route_by_output_token(
+    token_out: u64,
+    token_mint_in: &[u8],
+    token_mint_out: &[u8],
+    max_splits: u8,
+    slippage_tolerance_bps: u16,
+    graph: RouteGraph,
+) -> Result<SwapRoute, ErrorCode> {
+    find_route(
+        token_out,
+        false,
+        token_mint_in,
+        token_mint_out,
+        max_splits,
+        slippage_tolerance_bps,
+        &graph,
+    )
+}
+
+// Private functions
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Hop {
+    pool: usize,
+    a_to_b: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Path {
+    one: Hop,
+    two: Option<Hop>,
+}
+
+impl Path {
+    fn uses_pool(&self, pool: usize) -> bool {
+        self.one.pool == pool || self.two.is_some_and(|two| two.pool == pool)
+    }
+
+    fn shares_pool(&self, other: &Path) -> bool {
+        other.uses_pool(self.one.pool) || self.two.is_some_and(|two| other.uses_pool(two.pool))
+    }
+}
+
+fn find_route(
+    amount: u64,
+    specified_input: bool,
+    token_mint_in: &[u8],
+    token_mint_out: &[u8],
+    max_splits: u8,
+    slippage_tolerance_bps: u16,
+    graph: &RouteGraph,
+) -> Result<SwapRoute, ErrorCode> {
+    if amount == 0 {
+        return Err(ZERO_TRADABLE_AMOUNT);
+    }
+
+    let quoters = graph
+        .pools
+        .iter()
+        .map(prepare_quoter)
+        .collect::<Result<Vec<_>, _>>()?;
+    let paths = find_paths(&graph.pools, token_mint_in, token_mint_out);
+
+    let best_single = paths
+        .iter()
+        .enumerate()
+        .filter_map(|(i, path)| {
+            quote_path(&quoters, path, amount, specified_input)
+                .ok()
+                .map(|quote| (i, quote))
+        })
+        .reduce(|best, next| {
+            if is_better(next.1, best.1, specified_input) {
+                next
+            } else {
+                best
+            }
+        });
+    let (best_single_index, best_single_quote) = best_single.ok_or(ROUTE_NOT_FOUND)?;
+
+    let mut allocation = vec![0u64; paths.len()];
+    allocation[best_single_index] = amount;
+    if max_splits > 1 {
+        if let Some((split_allocation, split_quote)) =
+            split_amount(&quoters, &paths, amount, specified_input, max_splits)
+        {
+            if is_better(split_quote, best_single_quote, specified_input) {
+                allocation = split_allocation;
+            }
+        }
+    }
+
+    let mut route = SwapRoute::default();
+    for (path, leg_amount) in paths.iter().zip(allocation) {
+        if leg_amount == 0 {
+            continue;
+        }
+        let (token_est_in, token_est_out) =
+            quote_path(&quoters, path, leg_amount, specified_input)?;
+        let other_amount_threshold = if specified_input {
+            try_get_min_amount_with_slippage_tolerance(token_est_out, slippage_tolerance_bps)?
+        } else {
+            try_get_max_amount_with_slippage_tolerance(token_est_in, slippage_tolerance_bps)?
+        };
+        route.legs.push(RouteLeg {
+            whirlpool_one: graph.pools[path.one.pool].address,
+            whirlpool_two: path.two.map(|two| graph.pools[two.pool].address),
+            a_to_b_one: path.one.a_to_b,
+            a_to_b_two: path.two.is_some_and(|two| two.a_to_b),
+            amount: leg_amount,
+            other_amount_threshold,
+            amount_specified_is_input: specified_input,
+            token_est_in,
+            token_est_out,
+        });
+        route.token_est_in = route
+            .token_est_in
+            .checked_add(token_est_in)
+            .ok_or(ARITHMETIC_OVERFLOW)?;
+        route.token_est_out = route
+            .token_est_out
+            .checked_add(token_est_out)
+            .ok_or(ARITHMETIC_OVERFLOW)?;
+    }
+
+    Ok(route)
+}
+
+pub(crate) fn prepare_quoter(pool: &RoutePool) -> Result<SwapQuoter, ErrorCode> {
+    let mut tick_arrays: [Option<TickArrayFacade>; 6] = [None; 6];
+    if pool.tick_arrays.len() > tick_arrays.len() {
+        return Err(TOO_MANY_TICK_ARRAYS);
+    }
+    for (i, tick_array) in pool.tick_arrays.iter().enumerate() {
+        tick_arrays[i] = Some(*tick_array);
+    }
+    SwapQuoter::new(
+        pool.whirlpool,
+        tick_arrays,
+        pool.transfer_fee_a,
+        pool.transfer_fee_b,
+    )
+}
+
+fn find_paths(pools: &[RoutePool], token_mint_in: &[u8], token_mint_out: &[u8]) -> Vec<Path> {
+    let mut pools_by_mint: BTreeMap<&[u8], Vec<usize>> = BTreeMap::new();
+    for (i, pool) in pools.iter().enumerate() {
+        pools_by_mint.entry(&pool.token_mint_a).or_default().push(i);
+        pools_by_mint.entry(&pool.token_mint_b).or_default().push(i);
+    }
+
+    // Returns the hop through `pool` that starts at `mint` together with the mint it ends at
+    let hop_from = |pool: usize, mint: &[u8]| -> (Hop, &[u8]) {
+        let a_to_b = pools[pool].token_mint_a == mint;
+        let mint_out: &[u8] = if a_to_b {
+            &pools[pool].token_mint_b
+        } else {
+            &pools[pool].token_mint_a
+        };
+        (Hop { pool, a_to_b }, mint_out)
+    };
+
+    let mut paths = Vec::new();
+    if token_mint_in == token_mint_out {
+        return paths;
+    }
+    for &pool_one in pools_by_mint.get(token_mint_in).into_iter().flatten() {
+        let (one, token_mint_intermediate) = hop_from(pool_one, token_mint_in);
+        if token_mint_intermediate == token_mint_out {
+            paths.push(Path { one, two: None });
+            continue;
+        }
+        for &pool_two in pools_by_mint
+            .get(token_mint_intermediate)
+            .into_iter()
+            .flatten()
+        {
+            if pool_two == pool_one {
+                continue;
+            }
+            let (two, mint_out) = hop_from(pool_two, token_mint_intermediate);
+            if mint_out == token_mint_out {
+                paths.push(Path {
+                    one,
+                    two: Some(two),
+                });
+            }
+        }
+    }
+    paths
+}
+
+// Splits the amount into equal parts and greedily assigns each part to the path with the best
+// marginal quote. Paths that share a whirlpool with an already used path are skipped since the
+// quotes would not account for the price impact of the other path.
+fn split_amount(
+    quoters: &[SwapQuoter],
+    paths: &[Path],
+    amount: u64,
+    specified_input: bool,
+    max_splits: u8,
+) -> Option<(Vec<u64>, (u64, u64))> {
+    let parts = (max_splits as u64).min(amount);
+    let part_amount = amount / parts;
+    let mut allocation = vec![0u64; paths.len()];
+    let mut quotes = vec![(0u64, 0u64); paths.len()];
+
+    for part in 0..parts {
+        let part_amount = if part == parts - 1 {
+            amount - part_amount * (parts - 1)
+        } else {
+            part_amount
+        };
+
+        let mut best: Option<(usize, (u64, u64), u64)> = None;
+        for (i, path) in paths.iter().enumerate() {
+            let conflicts = allocation[i] == 0
+                && paths
+                    .iter()
+                    .zip(&allocation)
+                    .any(|(other, &other_amount)| other_amount > 0 && path.shares_pool(other));
+            if conflicts {
+                continue;
+            }
+            let Ok(quote) = quote_path(quoters, path, allocation[i] + part_amount, specified_input)
+            else {
+                continue;
+            };
+            let marginal = if specified_input {
+                quote.1.saturating_sub(quotes[i].1)
+            } else {
+                quote.0.saturating_sub(quotes[i].0)
+            };
+            let is_best = best.is_none_or(|(_, _, best_marginal)| {
+                if specified_input {
+                    marginal > best_marginal
+                } else {
+                    marginal < best_marginal
+                }
+            });
+            if is_best {
+                best = Some((i, quote, marginal));
+            }
+        }
+
+        let (i, quote, _) = best?;
+        allocation[i] += part_amount;
+        quotes[i] = quote;
+    }
+
+    let total = quotes.iter().try_fold((0u64, 0u64), |total, quote| {
+        Some((total.0.checked_add(quote.0)?, total.1.checked_add(quote.1)?))
+    })?;
+    Some((allocation, total))
+}
+
+// For exact in swaps a higher output is better, for exact out swaps a lower input is better.
+fn is_better(quote: (u64, u64), other: (u64, u64), specified_input: bool) -> bool {
+    if specified_input {
+        quote.1 > other.1
+    } else {
+        quote.0 < other.0
+    }
+}
+
+// Returns the estimated (input, output) amounts of swapping along a path.
+fn quote_path(
+    quoters: &[SwapQuoter],
+    path: &Path,
+    amount: u64,
+    specified_input: bool,
+) -> Result<(u64, u64), ErrorCode> {
+    let quoter_one = &quoters[path.one.pool];
+    match (path.two, specified_input) {
+        (None, true) => {
+            let quote = quote_hop_by_input(quoter_one, path.one, amount, true)?;
+            Ok((quote.token_in, quote.token_est_out))
+        }
+        (None, false) => {
+            let quote = quote_hop_by_output(quoter_one, path.one, amount)?;
+            Ok((quote.token_est_in, quote.token_out))
+        }
+        // The intermediate token is transferred from vault to vault, so its transfer fee is
+        // only charged once (on the input of the second swap). This matches `two_hop_swap_v2`.
+        (Some(two), true) => {
+            let quote_one = quote_hop_by_input(quoter_one, path.one, amount, false)?;
+            let quote_two =
+                quote_hop_by_input(&quoters[two.pool], two, quote_one.token_est_out, true)?;
+            Ok((quote_one.token_in, quote_two.token_est_out))
+        }
+        (Some(two), false) => {
+            let quoter_two = &quoters[two.pool];
+            let quote_two = quote_hop_by_output(quoter_two, two, amount)?;
+            let token_out_one = try_apply_transfer_fee(
+                quote_two.token_est_in,
+                intermediate_transfer_fee(quoter_two, !two.a_to_b),
+            )?;
+            // The first swap outputs the fee included amount, which has to be exactly the input
+            // of the second swap. Otherwise `two_hop_swap_v2` fails with
+            // `IntermediateTokenAmountMismatch`.
+            let token_out_one_fee_included = try_reverse_apply_transfer_fee(
+                token_out_one,
+                intermediate_transfer_fee(quoter_one, path.one.a_to_b),
+            )?;
+            if token_out_one_fee_included != quote_two.token_est_in {
+                return Err(INTERMEDIATE_TOKEN_AMOUNT_MISMATCH);
+            }
+            let quote_one = quote_hop_by_output(quoter_one, path.one, token_out_one)?;
+            Ok((quote_one.token_est_in, quote_two.token_out))
+        }
+    }
+}
+
+// Returns the transfer fee of token B if `is_token_b` is true, otherwise of token A.
+fn intermediate_transfer_fee(quoter: &SwapQuoter, is_token_b: bool) -> TransferFee {
+    if is_token_b {
+        quoter.transfer_fee_b
+    } else {
+        quoter.transfer_fee_a
+    }
+    .unwrap_or_default()
+}
+
+fn quote_hop_by_input(
+    quoter: &SwapQuoter,
+    hop: Hop,
+    token_in: u64,
+    include_transfer_fee_out: bool,
+) -> Result<ExactInSwapQuote, ErrorCode> {
+    let mut transfer_fee_a = quoter.transfer_fee_a;
+    let mut transfer_fee_b = quoter.transfer_fee_b;
+    if !include_transfer_fee_out {
+        if hop.a_to_b {
+            transfer_fee_b = None;
+        } else {
+            transfer_fee_a = None;
+        }
+    }
+    try_quote_by_input_token(
+        token_in,
+        hop.a_to_b,
+        0,
+        quoter.whirlpool,
+        &quoter.tick_sequence,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+fn quote_hop_by_output(
+    quoter: &SwapQuoter,
+    hop: Hop,
+    token_out: u64,
+) -> Result<ExactOutSwapQuote, ErrorCode> {
+    try_quote_by_output_token(
+        token_out,
+        !hop.a_to_b,
+        0,
+        quoter.whirlpool,
+        &quoter.tick_sequence,
+        quoter.transfer_fee_a,
+        quoter.transfer_fee_b,
+    )
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{
+        sqrt_price_to_tick_index, swap_quote_by_input_token, swap_quote_by_output_token,
+        TickArrays, TickFacade, WhirlpoolFacade, TICK_ARRAY_SIZE,
+    };
+
+    const MINT_A: [u8; 32] = [1; 32];
+    const MINT_B: [u8; 32] = [2; 32];
+    const MINT_C: [u8; 32] = [3; 32];
+
+    fn test_whirlpool(liquidity: u128) -> WhirlpoolFacade {
+        let sqrt_price = 1 << 64;
+        WhirlpoolFacade {
+            tick_current_index: sqrt_price_to_tick_index(sqrt_price),
+            fee_rate: 3000,
+            liquidity,
+            sqrt_price,
+            tick_spacing: 2,
+            ..WhirlpoolFacade::default()
+        }
+    }
+
+    fn test_tick_array(start_tick_index: i32) -> TickArrayFacade {
+        let liquidity_net = if start_tick_index < 0 { 1000 } else { -1000 };
+        TickArrayFacade {
+            start_tick_index,
+            ticks: [TickFacade {
+                initialized: true,
+                liquidity_net,
+                ..TickFacade::default()
+            }; TICK_ARRAY_SIZE],
+        }
+    }
+
+    fn test_tick_arrays() -> Vec<TickArrayFacade> {
+        [0, 176, 352, -176, -352]
+            .into_iter()
+            .map(test_tick_array)
+            .collect()
+    }
+
+    fn test_swap_tick_arrays() -> TickArrays {
+        [0, 176, 352, -176, -352].map(test_tick_array).into()
+    }
+
+    fn test_pool(
+        address: u8,
+        token_mint_a: [u8; 32],
+        token_mint_b: [u8; 32],
+        liquidity: u128,
+    ) -> RoutePool {
+        RoutePool {
+            address: [address; 32],
+            token_mint_a,
+            token_mint_b,
+            whirlpool: test_whirlpool(liquidity),
+            tick_arrays: test_tick_arrays(),
+            transfer_fee_a: None,
+            transfer_fee_b: None,
+        }
+    }
+
+    #[test]
+    fn test_single_pool_matches_swap_quote() {
+        let pool = test_pool(10, MINT_A, MINT_B, 100000000);
+        let graph = RouteGraph {
+            pools: vec![pool.clone()],
+        };
+
+        let route =
+            swap_route_by_input_token(1000, &MINT_A, &MINT_B, 1, 100, graph.clone()).unwrap();
+        let quote = swap_quote_by_input_token(
+            1000,
+            true,
+            100,
+            pool.whirlpool,
+            test_swap_tick_arrays(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(route.legs.len(), 1);
+        assert_eq!(route.legs[0].whirlpool_one, [10; 32]);
+        assert_eq!(route.legs[0].whirlpool_two, None);
+        assert!(route.legs[0].a_to_b_one);
+        assert!(route.legs[0].amount_specified_is_input);
+        assert_eq!(route.legs[0].amount, 1000);
+        assert_eq!(route.legs[0].other_amount_threshold, quote.token_min_out);
+        assert_eq!(route.token_est_in, quote.token_in);
+        assert_eq!(route.token_est_out, quote.token_est_out);
+
+        let route = swap_route_by_output_token(1000, &MINT_B, &MINT_A, 1, 100, graph).unwrap();
+        let quote = swap_quote_by_output_token(
+            1000,
+            true,
+            100,
+            pool.whirlpool,
+            test_swap_tick_arrays(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(route.legs.len(), 1);
+        assert!(!route.legs[0].a_to_b_one);
+        assert!(!route.legs[0].amount_specified_is_input);
+        assert_eq!(route.legs[0].other_amount_threshold, quote.token_max_in);
+        assert_eq!(route.token_est_in, quote.token_est_in);
+        assert_eq!(route.token_est_out, quote.token_out);
+    }
+
+    #[test]
+    fn test_picks_deepest_pool() {
+        let graph = RouteGraph {
+            pools: vec![
+                test_pool(10, MINT_A, MINT_B, 265000),
+                test_pool(11, MINT_A, MINT_B, 100000000),
+            ],
+        };
+        let route = swap_route_by_input_token(1000, &MINT_A, &MINT_B, 1, 100, graph).unwrap();
+        assert_eq!(route.legs.len(), 1);
+        assert_eq!(route.legs[0].whirlpool_one, [11; 32]);
+        assert_eq!(route.token_est_out, 996);
+    }
+
+    #[test]
+    fn test_split_across_parallel_pools() {
+        let graph = RouteGraph {
+            pools: vec![
+                test_pool(10, MINT_A, MINT_B, 265000),
+                test_pool(11, MINT_A, MINT_B, 265000),
+            ],
+        };
+        let single =
+            swap_route_by_input_token(2000, &MINT_A, &MINT_B, 1, 100, graph.clone()).unwrap();
+        let split = swap_route_by_input_token(2000, &MINT_A, &MINT_B, 4, 100, graph).unwrap();
+        assert_eq!(single.legs.len(), 1);
+        assert_eq!(split.legs.len(), 2);
+        assert_eq!(split.legs[0].amount + split.legs[1].amount, 2000);
+        assert_eq!(split.token_est_in, 2000);
+        assert!(split.token_est_out > single.token_est_out);
+    }
+
+    #[test]
+    fn test_two_hop_route() {
+        let graph = RouteGraph {
+            pools: vec![
+                test_pool(10, MINT_A, MINT_C, 100000000),
+                test_pool(11, MINT_B, MINT_C, 100000000),
+            ],
+        };
+        let route =
+            swap_route_by_input_token(1000, &MINT_A, &MINT_B, 1, 100, graph.clone()).unwrap();
+        assert_eq!(route.legs.len(), 1);
+        assert_eq!(route.legs[0].whirlpool_one, [10; 32]);
+        assert_eq!(route.legs[0].whirlpool_two, Some([11; 32]));
+        assert!(route.legs[0].a_to_b_one);
+        assert!(!route.legs[0].a_to_b_two);
+        assert_eq!(route.token_est_in, 1000);
+        assert_eq!(route.token_est_out, 992);
+
+        let route = swap_route_by_output_token(992, &MINT_A, &MINT_B, 1, 100, graph).unwrap();
+        assert_eq!(route.legs.len(), 1);
+        assert_eq!(route.token_est_in, 1000);
+        assert_eq!(route.token_est_out, 992);
+    }
+
+    #[test]
+    fn test_two_hop_intermediate_transfer_fee() {
+        let mut pool_one = test_pool(10, MINT_A, MINT_C, 100000000);
+        pool_one.transfer_fee_b = Some(TransferFee::new(100));
+        let mut pool_two = test_pool(11, MINT_C, MINT_B, 100000000);
+        pool_two.transfer_fee_a = Some(TransferFee::new(100));
+        let graph = RouteGraph {
+            pools: vec![pool_one, pool_two],
+        };
+        let route = swap_route_by_input_token(1000, &MINT_A, &MINT_B, 1, 100, graph).unwrap();
+
+        // The intermediate transfer fee is only charged once
+        let quote_one = swap_quote_by_input_token(
+            1000,
+            true,
+            0,
+            test_whirlpool(100000000),
+            test_swap_tick_arrays(),
+            None,
+            None,
+        )
+        .unwrap();
+        let quote_two = swap_quote_by_input_token(
+            quote_one.token_est_out,
+            true,
+            0,
+            test_whirlpool(100000000),
+            test_swap_tick_arrays(),
+            Some(TransferFee::new(100)),
+            None,
+        )
+        .unwrap();
+        assert_eq!(route.token_est_out, quote_two.token_est_out);
+    }
+
+    fn test_intermediate_fee_graph(fee_bps: u16) -> RouteGraph {
+        let mut pool_one = test_pool(10, MINT_A, MINT_C, 100000000);
+        pool_one.transfer_fee_b = Some(TransferFee::new(fee_bps));
+        let mut pool_two = test_pool(11, MINT_C, MINT_B, 100000000);
+        pool_two.transfer_fee_a = Some(TransferFee::new(fee_bps));
+        RouteGraph {
+            pools: vec![pool_one, pool_two],
+        }
+    }
+
+    #[test]
+    fn test_two_hop_intermediate_transfer_fee_exact_out() {
+        let graph = test_intermediate_fee_graph(100);
+        let route = swap_route_by_output_token(1000, &MINT_A, &MINT_B, 1, 100, graph).unwrap();
+
+        // The first hop outputs the fee excluded input of the second hop, with the fee added back
+        let quote_two = swap_quote_by_output_token(
+            1000,
+            false,
+            0,
+            test_whirlpool(100000000),
+            test_swap_tick_arrays(),
+            Some(TransferFee::new(100)),
+            None,
+        )
+        .unwrap();
+        let token_out_one =
+            try_apply_transfer_fee(quote_two.token_est_in, TransferFee::new(100)).unwrap();
+        let quote_one = swap_quote_by_output_token(
+            token_out_one,
+            false,
+            0,
+            test_whirlpool(100000000),
+            test_swap_tick_arrays(),
+            None,
+            Some(TransferFee::new(100)),
+        )
+        .unwrap();
+        assert_eq!(route.token_est_in, quote_one.token_est_in);
+        assert_eq!(route.token_est_out, 1000);
+    }
+
+    #[test]
+    fn test_two_hop_intermediate_amount_mismatch() {
+        let graph = test_intermediate_fee_graph(100);
+        let mut quoters = graph
+            .pools
+            .iter()
+            .map(prepare_quoter)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let path = Path {
+            one: Hop {
+                pool: 0,
+                a_to_b: true,
+            },
+            two: Some(Hop {
+                pool: 1,
+                a_to_b: true,
+            }),
+        };
+        assert!(quote_path(&quoters, &path, 1000, false).is_ok());
+
+        // The pools were fetched while the intermediate transfer fee changed
+        quoters[0].transfer_fee_b = None;
+        let result = quote_path(&quoters, &path, 1000, false);
+        assert_eq!(result, Err(INTERMEDIATE_TOKEN_AMOUNT_MISMATCH));
+    }
+
+    #[test]
+    fn test_route_not_found() {
+        let graph = RouteGraph {
+            pools: vec![test_pool(10, MINT_A, MINT_C, 100000000)],
+        };
+        let result = swap_route_by_input_token(1000, &MINT_A, &MINT_B, 1, 100, graph.clone());
+        assert_eq!(result, Err(ROUTE_NOT_FOUND));
+
+        let result = swap_route_by_input_token(1000, &MINT_A, &MINT_A, 1, 100, graph);
+        assert_eq!(result, Err(ROUTE_NOT_FOUND));
+    }
+
+    #[test]
+    fn test_too_many_tick_arrays() {
+        let mut pool = test_pool(10, MINT_A, MINT_B, 100000000);
+        pool.tick_arrays = [-528, -352, -176, 0, 176, 352, 528]
+            .into_iter()
+            .map(test_tick_array)
+            .collect();
+        let graph = RouteGraph { pools: vec![pool] };
+        let result = swap_route_by_input_token(1000, &MINT_A, &MINT_B, 1, 100, graph);
+        assert_eq!(result, Err(TOO_MANY_TICK_ARRAYS));
+    }
+}
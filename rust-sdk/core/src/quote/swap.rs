@@ -1,12 +1,16 @@
 use crate::{
-    sqrt_price_to_tick_index, tick_index_to_sqrt_price, try_apply_swap_fee, try_apply_transfer_fee,
-    try_get_amount_delta_a, try_get_amount_delta_b, try_get_max_amount_with_slippage_tolerance,
-    try_get_min_amount_with_slippage_tolerance, try_get_next_sqrt_price_from_a,
-    try_get_next_sqrt_price_from_b, try_reverse_apply_swap_fee, try_reverse_apply_transfer_fee,
-    ErrorCode, ExactInSwapQuote, ExactOutSwapQuote, TickArraySequence, TickArrays, TickFacade,
-    TransferFee, WhirlpoolFacade, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW,
+    get_tick_index_in_array, sqrt_price_to_tick_index, tick_index_to_sqrt_price,
+    try_apply_swap_fee, try_apply_transfer_fee, try_get_amount_delta_a, try_get_amount_delta_b,
+    try_get_max_amount_with_slippage_tolerance, try_get_min_amount_with_slippage_tolerance,
+    try_get_next_reward_infos, try_get_next_sqrt_price_from_a, try_get_next_sqrt_price_from_b,
+    try_reverse_apply_transfer_fee, ErrorCode, ExactInSwapQuote, ExactOutSwapQuote, PendingSwap,
+    TickArrayFacade, TickArraySequence, TickArrays, TickFacade, TransferFee, WhirlpoolFacade,
+    WhirlpoolRewardInfoFacade, AMOUNT_CALC_OVERFLOW, AMOUNT_EXCEEDS_MAX_U64,
+    AMOUNT_REMAINING_OVERFLOW, ARITHMETIC_OVERFLOW, FEE_RATE_DENOMINATOR,
     INVALID_SQRT_PRICE_LIMIT_DIRECTION, MAX_SQRT_PRICE, MIN_SQRT_PRICE,
-    SQRT_PRICE_LIMIT_OUT_OF_BOUNDS, ZERO_TRADABLE_AMOUNT,
+    MULTIPLICATION_SHIFT_RIGHT_OVERFLOW, NUM_REWARDS, PROTOCOL_FEE_RATE_DENOMINATOR,
+    SQRT_PRICE_LIMIT_OUT_OF_BOUNDS, TICK_ARRAY_SIZE, TICK_INDEX_OUT_OF_BOUNDS,
+    ZERO_TRADABLE_AMOUNT,
 };
 
 #[cfg(feature = "wasm")]
@@ -34,6 +38,147 @@ pub fn swap_quote_by_input_token(
     tick_arrays: TickArrays,
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
+) -> Result<ExactInSwapQuote, ErrorCode> {
+    let tick_sequence = TickArraySequence::new(tick_arrays.into(), whirlpool.tick_spacing)?;
+    try_quote_by_input_token(
+        token_in,
+        specified_token_a,
+        slippage_tolerance_bps,
+        whirlpool,
+        &tick_sequence,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// Computes the exact input or output amount for a swap transaction.
+///
+/// # Arguments
+/// - `token_out`: The output token amount.
+/// - `specified_token_a`: If `true`, the output token is token A. Otherwise, it is token B.
+/// - `slippage_tolerance`: The slippage tolerance in basis points.
+/// - `whirlpool`: The whirlpool state.
+/// - `tick_arrays`: The tick arrays needed for the swap.
+/// - `transfer_fee_a`: The transfer fee for token A.
+/// - `transfer_fee_b`: The transfer fee for token B.
+///
+/// # Returns
+/// The exact input or output amount for the swap transaction.
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn swap_quote_by_output_token(
+    token_out: u64,
+    specified_token_a: bool,
+    slippage_tolerance_bps: u16,
+    whirlpool: WhirlpoolFacade,
+    tick_arrays: TickArrays,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<ExactOutSwapQuote, ErrorCode> {
+    let tick_sequence = TickArraySequence::new(tick_arrays.into(), whirlpool.tick_spacing)?;
+    try_quote_by_output_token(
+        token_out,
+        specified_token_a,
+        slippage_tolerance_bps,
+        whirlpool,
+        &tick_sequence,
+        transfer_fee_a,
+        transfer_fee_b,
+    )
+}
+
+/// A whirlpool prepared for quoting many swaps against the same pool state.
+///
+/// The tick arrays are sorted and validated once when the quoter is created, so each quote only
+/// runs the swap simulation. The quotes are identical to the ones returned by
+/// `swap_quote_by_input_token` and `swap_quote_by_output_token` for the same pool state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapQuoter {
+    pub(crate) whirlpool: WhirlpoolFacade,
+    pub(crate) tick_sequence: TickArraySequence<6>,
+    pub(crate) transfer_fee_a: Option<TransferFee>,
+    pub(crate) transfer_fee_b: Option<TransferFee>,
+}
+
+impl SwapQuoter {
+    /// Prepares a whirlpool for quoting.
+    ///
+    /// # Arguments
+    /// - `whirlpool`: The whirlpool state.
+    /// - `tick_arrays`: The tick arrays needed for the swaps.
+    /// - `transfer_fee_a`: The transfer fee for token A.
+    /// - `transfer_fee_b`: The transfer fee for token B.
+    ///
+    /// # Returns
+    /// A quoter for the whirlpool or an error if the tick arrays do not form a valid sequence.
+    pub fn new(
+        whirlpool: WhirlpoolFacade,
+        tick_arrays: impl Into<[Option<TickArrayFacade>; 6]>,
+        transfer_fee_a: Option<TransferFee>,
+        transfer_fee_b: Option<TransferFee>,
+    ) -> Result<Self, ErrorCode> {
+        let tick_sequence = TickArraySequence::new(tick_arrays.into(), whirlpool.tick_spacing)?;
+        Ok(Self {
+            whirlpool,
+            tick_sequence,
+            transfer_fee_a,
+            transfer_fee_b,
+        })
+    }
+
+    /// Returns the whirlpool state the quoter was prepared with.
+    pub fn whirlpool(&self) -> WhirlpoolFacade {
+        self.whirlpool
+    }
+
+    /// Computes the quote for a swap with an exact input amount.
+    /// See `swap_quote_by_input_token` for details.
+    pub fn quote_by_input_token(
+        &self,
+        token_in: u64,
+        specified_token_a: bool,
+        slippage_tolerance_bps: u16,
+    ) -> Result<ExactInSwapQuote, ErrorCode> {
+        try_quote_by_input_token(
+            token_in,
+            specified_token_a,
+            slippage_tolerance_bps,
+            self.whirlpool,
+            &self.tick_sequence,
+            self.transfer_fee_a,
+            self.transfer_fee_b,
+        )
+    }
+
+    /// Computes the quote for a swap with an exact output amount.
+    /// See `swap_quote_by_output_token` for details.
+    pub fn quote_by_output_token(
+        &self,
+        token_out: u64,
+        specified_token_a: bool,
+        slippage_tolerance_bps: u16,
+    ) -> Result<ExactOutSwapQuote, ErrorCode> {
+        try_quote_by_output_token(
+            token_out,
+            specified_token_a,
+            slippage_tolerance_bps,
+            self.whirlpool,
+            &self.tick_sequence,
+            self.transfer_fee_a,
+            self.transfer_fee_b,
+        )
+    }
+}
+
+// Private functions
+
+pub(crate) fn try_quote_by_input_token<const SIZE: usize>(
+    token_in: u64,
+    specified_token_a: bool,
+    slippage_tolerance_bps: u16,
+    whirlpool: WhirlpoolFacade,
+    tick_sequence: &TickArraySequence<SIZE>,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
 ) -> Result<ExactInSwapQuote, ErrorCode> {
     let (transfer_fee_in, transfer_fee_out) = if specified_token_a {
         (transfer_fee_a, transfer_fee_b)
@@ -43,8 +188,6 @@ pub fn swap_quote_by_input_token(
     let token_in_after_fee =
         try_apply_transfer_fee(token_in.into(), transfer_fee_in.unwrap_or_default())?;
 
-    let tick_sequence = TickArraySequence::new(tick_arrays.into(), whirlpool.tick_spacing)?;
-
     let swap_result = compute_swap(
         token_in_after_fee.into(),
         0,
@@ -80,26 +223,12 @@ pub fn swap_quote_by_input_token(
     })
 }
 
-/// Computes the exact input or output amount for a swap transaction.
-///
-/// # Arguments
-/// - `token_out`: The output token amount.
-/// - `specified_token_a`: If `true`, the output token is token A. Otherwise, it is token B.
-/// - `slippage_tolerance`: The slippage tolerance in basis points.
-/// - `whirlpool`: The whirlpool state.
-/// - `tick_arrays`: The tick arrays needed for the swap.
-/// - `transfer_fee_a`: The transfer fee for token A.
-/// - `transfer_fee_b`: The transfer fee for token B.
-///
-/// # Returns
-/// The exact input or output amount for the swap transaction.
-#[cfg_attr(feature = "wasm", wasm_expose)]
-pub fn swap_quote_by_output_token(
+pub(crate) fn try_quote_by_output_token<const SIZE: usize>(
     token_out: u64,
     specified_token_a: bool,
     slippage_tolerance_bps: u16,
     whirlpool: WhirlpoolFacade,
-    tick_arrays: TickArrays,
+    tick_sequence: &TickArraySequence<SIZE>,
     transfer_fee_a: Option<TransferFee>,
     transfer_fee_b: Option<TransferFee>,
 ) -> Result<ExactOutSwapQuote, ErrorCode> {
@@ -111,8 +240,6 @@ pub fn swap_quote_by_output_token(
     let token_out_before_fee =
         try_reverse_apply_transfer_fee(token_out, transfer_fee_out.unwrap_or_default())?;
 
-    let tick_sequence = TickArraySequence::new(tick_arrays.into(), whirlpool.tick_spacing)?;
-
     let swap_result = compute_swap(
         token_out_before_fee.into(),
         0,
@@ -148,19 +275,122 @@ pub fn swap_quote_by_output_token(
     })
 }
 
-// Private functions
+// Returns the swap with the amount the program swaps for it: `swap_v2` swaps the transfer fee
+// excluded input for exact input swaps and the transfer fee included output for exact output swaps.
+pub(crate) fn try_apply_swap_transfer_fee(
+    swap: PendingSwap,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<PendingSwap, ErrorCode> {
+    let (transfer_fee_in, transfer_fee_out) = if swap.a_to_b {
+        (transfer_fee_a, transfer_fee_b)
+    } else {
+        (transfer_fee_b, transfer_fee_a)
+    };
+    let amount = if swap.amount_specified_is_input {
+        try_apply_transfer_fee(swap.amount, transfer_fee_in.unwrap_or_default())?
+    } else {
+        try_reverse_apply_transfer_fee(swap.amount, transfer_fee_out.unwrap_or_default())?
+    };
+    Ok(PendingSwap { amount, ..swap })
+}
 
-struct SwapResult {
-    token_a: u64,
-    token_b: u64,
-    trade_fee: u64,
+// Applies a swap to the whirlpool and tick array states the same way the program does. Ticks
+// that are crossed by the swap are updated in place, reward growths are advanced to `timestamp`.
+pub(crate) fn try_apply_swap<const SIZE: usize>(
+    swap: PendingSwap,
+    whirlpool: &mut WhirlpoolFacade,
+    tick_arrays: &mut [Option<TickArrayFacade>; SIZE],
+    timestamp: u64,
+) -> Result<SwapResult, ErrorCode> {
+    let reward_infos = try_get_next_reward_infos(*whirlpool, timestamp)?;
+    let tick_sequence = TickArraySequence::new(*tick_arrays, whirlpool.tick_spacing)?;
+    let swap_result = compute_swap(
+        swap.amount,
+        swap.sqrt_price_limit,
+        *whirlpool,
+        &tick_sequence,
+        swap.a_to_b,
+        swap.amount_specified_is_input,
+        timestamp,
+    )?;
+
+    let ticks_per_array = TICK_ARRAY_SIZE as i32 * whirlpool.tick_spacing as i32;
+    for crossing in &swap_result.crossed_ticks {
+        let tick_array = tick_arrays.iter_mut().flatten().find(|tick_array| {
+            (0..ticks_per_array).contains(&(crossing.tick_index - tick_array.start_tick_index))
+        });
+        if let Some(tick_array) = tick_array {
+            let index_in_array = get_tick_index_in_array(
+                crossing.tick_index,
+                tick_array.start_tick_index,
+                whirlpool.tick_spacing,
+            );
+            crossing.apply(
+                &mut tick_array.ticks[index_in_array as usize],
+                &reward_infos,
+            );
+        }
+    }
+
+    whirlpool.sqrt_price = swap_result.sqrt_price;
+    whirlpool.tick_current_index = swap_result.tick_current_index;
+    whirlpool.liquidity = swap_result.liquidity;
+    if swap.a_to_b {
+        whirlpool.fee_growth_global_a = swap_result.fee_growth_global_input;
+    } else {
+        whirlpool.fee_growth_global_b = swap_result.fee_growth_global_input;
+    }
+    whirlpool.reward_infos = reward_infos;
+    whirlpool.reward_last_updated_timestamp = timestamp;
+
+    Ok(swap_result)
+}
+
+pub(crate) struct SwapResult {
+    pub(crate) token_a: u64,
+    pub(crate) token_b: u64,
+    pub(crate) trade_fee: u64,
+    pub(crate) sqrt_price: u128,
+    pub(crate) tick_current_index: i32,
+    pub(crate) liquidity: u128,
+    pub(crate) fee_growth_global_input: u128,
+    pub(crate) crossed_ticks: Vec<TickCrossing>,
+}
+
+// An initialized tick crossed by a swap, with the global fee growths at the time of the crossing
+pub(crate) struct TickCrossing {
+    pub(crate) tick_index: i32,
+    pub(crate) fee_growth_global_a: u128,
+    pub(crate) fee_growth_global_b: u128,
+}
+
+impl TickCrossing {
+    // Flips the growths outside of the tick to the other side of the current price
+    pub(crate) fn apply(
+        &self,
+        tick: &mut TickFacade,
+        reward_infos: &[WhirlpoolRewardInfoFacade; NUM_REWARDS],
+    ) {
+        tick.fee_growth_outside_a = self
+            .fee_growth_global_a
+            .wrapping_sub(tick.fee_growth_outside_a);
+        tick.fee_growth_outside_b = self
+            .fee_growth_global_b
+            .wrapping_sub(tick.fee_growth_outside_b);
+        for (growth_outside, reward_info) in
+            tick.reward_growths_outside.iter_mut().zip(reward_infos)
+        {
+            *growth_outside = reward_info.growth_global_x64.wrapping_sub(*growth_outside);
+        }
+    }
 }
 
-fn compute_swap<const SIZE: usize>(
+pub(crate) fn compute_swap<const SIZE: usize>(
     token_amount: u64,
     sqrt_price_limit: u128,
     whirlpool: WhirlpoolFacade,
-    tick_sequence: TickArraySequence<SIZE>,
+    tick_sequence: &TickArraySequence<SIZE>,
     a_to_b: bool,
     specified_input: bool,
     _timestamp: u64, // currently ignored but needed for full swap logic
@@ -195,6 +425,12 @@ fn compute_swap<const SIZE: usize>(
     let mut current_tick_index = whirlpool.tick_current_index;
     let mut current_liquidity = whirlpool.liquidity;
     let mut trade_fee = 0u64;
+    let mut fee_growth_global_input = if a_to_b {
+        whirlpool.fee_growth_global_a
+    } else {
+        whirlpool.fee_growth_global_b
+    };
+    let mut crossed_ticks = Vec::new();
 
     while amount_remaining > 0 && sqrt_price_limit != current_sqrt_price {
         let (next_tick, next_tick_index) = if a_to_b {
@@ -203,6 +439,10 @@ fn compute_swap<const SIZE: usize>(
             tick_sequence.next_initialized_tick(current_tick_index)?
         };
         let next_tick_sqrt_price: u128 = tick_index_to_sqrt_price(next_tick_index.into()).into();
+        // The swap reached the end of the tick array sequence without being filled
+        if next_tick.is_none() && next_tick_sqrt_price == current_sqrt_price {
+            return Err(TICK_INDEX_OUT_OF_BOUNDS);
+        }
         let target_sqrt_price = if a_to_b {
             next_tick_sqrt_price.max(sqrt_price_limit)
         } else {
@@ -220,28 +460,48 @@ fn compute_swap<const SIZE: usize>(
         )?;
 
         trade_fee += step_quote.fee_amount;
+        fee_growth_global_input = fee_growth_global_input.wrapping_add(get_fee_growth_delta(
+            step_quote.fee_amount,
+            whirlpool.protocol_fee_rate,
+            current_liquidity,
+        ));
 
         if specified_input {
             amount_remaining = amount_remaining
                 .checked_sub(step_quote.amount_in)
-                .ok_or(ARITHMETIC_OVERFLOW)?
+                .ok_or(AMOUNT_REMAINING_OVERFLOW)?
                 .checked_sub(step_quote.fee_amount)
-                .ok_or(ARITHMETIC_OVERFLOW)?;
+                .ok_or(AMOUNT_REMAINING_OVERFLOW)?;
             amount_calculated = amount_calculated
                 .checked_add(step_quote.amount_out)
-                .ok_or(ARITHMETIC_OVERFLOW)?;
+                .ok_or(AMOUNT_CALC_OVERFLOW)?;
         } else {
             amount_remaining = amount_remaining
                 .checked_sub(step_quote.amount_out)
-                .ok_or(ARITHMETIC_OVERFLOW)?;
+                .ok_or(AMOUNT_REMAINING_OVERFLOW)?;
             amount_calculated = amount_calculated
                 .checked_add(step_quote.amount_in)
-                .ok_or(ARITHMETIC_OVERFLOW)?
+                .ok_or(AMOUNT_CALC_OVERFLOW)?
                 .checked_add(step_quote.fee_amount)
-                .ok_or(ARITHMETIC_OVERFLOW)?;
+                .ok_or(AMOUNT_CALC_OVERFLOW)?;
         }
 
         if step_quote.next_sqrt_price == next_tick_sqrt_price {
+            if next_tick.is_some() {
+                crossed_ticks.push(TickCrossing {
+                    tick_index: next_tick_index,
+                    fee_growth_global_a: if a_to_b {
+                        fee_growth_global_input
+                    } else {
+                        whirlpool.fee_growth_global_a
+                    },
+                    fee_growth_global_b: if a_to_b {
+                        whirlpool.fee_growth_global_b
+                    } else {
+                        fee_growth_global_input
+                    },
+                });
+            }
             current_liquidity = get_next_liquidity(current_liquidity, next_tick, a_to_b);
             current_tick_index = if a_to_b {
                 next_tick_index - 1
@@ -272,9 +532,24 @@ fn compute_swap<const SIZE: usize>(
         token_a,
         token_b,
         trade_fee,
+        sqrt_price: current_sqrt_price,
+        tick_current_index: current_tick_index,
+        liquidity: current_liquidity,
+        fee_growth_global_input,
+        crossed_ticks,
     })
 }
 
+// The part of the swap fee that is not collected by the protocol, per unit of liquidity
+fn get_fee_growth_delta(fee_amount: u64, protocol_fee_rate: u16, liquidity: u128) -> u128 {
+    if liquidity == 0 {
+        return 0;
+    }
+    let protocol_fee =
+        fee_amount as u128 * protocol_fee_rate as u128 / PROTOCOL_FEE_RATE_DENOMINATOR as u128;
+    ((fee_amount as u128 - protocol_fee) << 64) / liquidity
+}
+
 fn get_next_liquidity(
     current_liquidity: u128,
     next_tick: Option<&TickFacade>,
@@ -311,7 +586,7 @@ fn compute_swap_step(
     a_to_b: bool,
     specified_input: bool,
 ) -> Result<SwapStepQuote, ErrorCode> {
-    // Any error that is not AMOUNT_EXCEEDS_MAX_U64 is not recoverable
+    // Only an amount that does not fit in a u64 is recoverable, any other error is not
     let initial_amount_fixed_delta = try_get_amount_fixed_delta(
         current_sqrt_price,
         target_sqrt_price,
@@ -319,8 +594,12 @@ fn compute_swap_step(
         a_to_b,
         specified_input,
     );
-    let is_initial_amount_fixed_overflow =
-        initial_amount_fixed_delta == Err(AMOUNT_EXCEEDS_MAX_U64);
+    let is_initial_amount_fixed_overflow = match initial_amount_fixed_delta {
+        Err(AMOUNT_EXCEEDS_MAX_U64) | Err(MULTIPLICATION_SHIFT_RIGHT_OVERFLOW) => true,
+        // The amount B delta only overflows when rounding up u64::MAX
+        Err(ARITHMETIC_OVERFLOW) => a_to_b != specified_input,
+        _ => false,
+    };
 
     let amount_calculated = if specified_input {
         try_apply_swap_fee(amount_remaining.into(), fee_rate)?
@@ -375,11 +654,17 @@ fn compute_swap_step(
         amount_out = amount_remaining;
     }
 
+    // The fee is computed on its own, so that an amount in close to u64::MAX does not overflow
     let fee_amount = if specified_input && !is_max_swap {
         amount_remaining - amount_in
     } else {
-        let pre_fee_amount = try_reverse_apply_swap_fee(amount_in.into(), fee_rate)?;
-        pre_fee_amount - amount_in
+        let fee_rate = u128::from(fee_rate);
+        let numerator = u128::from(amount_in) * fee_rate;
+        let denominator = u128::from(FEE_RATE_DENOMINATOR) - fee_rate;
+        numerator
+            .div_ceil(denominator)
+            .try_into()
+            .map_err(|_| AMOUNT_EXCEEDS_MAX_U64)?
     };
 
     Ok(SwapStepQuote {
@@ -466,7 +751,7 @@ fn try_get_next_sqrt_price(
 
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
-    use crate::{TickArrayFacade, TICK_ARRAY_SIZE};
+    use crate::{TICK_ARRAY_NOT_EVENLY_SPACED, TICK_ARRAY_SIZE};
 
     use super::*;
 
@@ -658,4 +943,107 @@ mod tests {
     // TODO: add more complex tests that
     // * only fill partially
     // * transfer fee
+
+    #[test]
+    fn test_swap_quoter_matches_single_quotes() {
+        let quoter = SwapQuoter::new(
+            test_whirlpool(1 << 64, false),
+            test_tick_arrays(),
+            Some(TransferFee::new(100)),
+            Some(TransferFee::new_with_max(200, 5)),
+        )
+        .unwrap();
+        for amount in [1, 10, 100, 1000, 10000] {
+            for specified_token_a in [true, false] {
+                let expected = swap_quote_by_input_token(
+                    amount,
+                    specified_token_a,
+                    100,
+                    test_whirlpool(1 << 64, false),
+                    test_tick_arrays(),
+                    Some(TransferFee::new(100)),
+                    Some(TransferFee::new_with_max(200, 5)),
+                );
+                let result = quoter.quote_by_input_token(amount, specified_token_a, 100);
+                assert_eq!(result, expected);
+
+                let expected = swap_quote_by_output_token(
+                    amount,
+                    specified_token_a,
+                    100,
+                    test_whirlpool(1 << 64, false),
+                    test_tick_arrays(),
+                    Some(TransferFee::new(100)),
+                    Some(TransferFee::new_with_max(200, 5)),
+                );
+                let result = quoter.quote_by_output_token(amount, specified_token_a, 100);
+                assert_eq!(result, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_quoter_invalid_tick_arrays() {
+        let result = SwapQuoter::new(
+            test_whirlpool(1 << 64, true),
+            TickArrays::from([test_tick_array(0), test_tick_array(352)]),
+            None,
+            None,
+        );
+        assert_eq!(result, Err(TICK_ARRAY_NOT_EVENLY_SPACED));
+    }
+
+    #[test]
+    fn test_exact_in_exceeds_tick_arrays() {
+        let result = swap_quote_by_input_token(
+            1000000,
+            true,
+            1000,
+            test_whirlpool(1 << 64, false),
+            test_tick_arrays(),
+            None,
+            None,
+        );
+        assert_eq!(result, Err(TICK_INDEX_OUT_OF_BOUNDS));
+    }
+
+    #[test]
+    fn test_swap_at_end_of_tick_arrays() {
+        // The price sits on the boundary of the tick arrays, so no swap step can move it
+        let result = swap_quote_by_input_token(
+            1000,
+            true,
+            1000,
+            test_whirlpool(tick_index_to_sqrt_price(-352).into(), true),
+            test_tick_arrays(),
+            None,
+            None,
+        );
+        assert_eq!(result, Err(TICK_INDEX_OUT_OF_BOUNDS));
+
+        let result = swap_quote_by_input_token(
+            1000,
+            false,
+            1000,
+            test_whirlpool(tick_index_to_sqrt_price(527).into(), true),
+            test_tick_arrays(),
+            None,
+            None,
+        );
+        assert_eq!(result, Err(TICK_INDEX_OUT_OF_BOUNDS));
+    }
+
+    #[test]
+    fn test_exact_out_exceeds_tick_arrays() {
+        let result = swap_quote_by_output_token(
+            1000000,
+            true,
+            1000,
+            test_whirlpool(1 << 64, false),
+            test_tick_arrays(),
+            None,
+            None,
+        );
+        assert_eq!(result, Err(TICK_INDEX_OUT_OF_BOUNDS));
+    }
 }
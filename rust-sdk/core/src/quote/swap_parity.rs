@@ -0,0 +1,182 @@
+//! Runs the swap test cases of the program through `compute_swap`, so that any divergence
+//! between the swap of this crate and `swap_manager::swap` of the program is caught mechanically.
+//!
+//! The test cases are generated by `programs/whirlpool/src/tests/generate_swap_testcase_json.ts`
+//! and are shared with `swap_integration_tests.rs` of the program, which sets up the swaps the
+//! same way.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::{
+    compute_swap, get_tick_array_start_tick_index, tick_index_to_sqrt_price, ErrorCode,
+    TickArrayFacade, TickArraySequence, TickFacade, WhirlpoolFacade, AMOUNT_CALC_OVERFLOW,
+    AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW, MAX_TICK_INDEX, MIN_TICK_INDEX,
+    MULTIPLICATION_SHIFT_RIGHT_OVERFLOW, TICK_ARRAY_SIZE, ZERO_TRADABLE_AMOUNT,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestCase {
+    test_id: u32,
+    description: String,
+    tick_spacing: u16,
+    fee_rate: u16,
+    protocol_fee_rate: u16,
+    liquidity: String,
+    curr_tick_index: i32,
+    trade_amount: String,
+    amount_is_input: bool,
+    a_to_b: bool,
+    expectation: Expectation,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Expectation {
+    exception: String,
+    amount_a: String,
+    amount_b: String,
+    next_liquidity: String,
+    next_tick_index: i32,
+    next_sqrt_price: String,
+    next_fee_growth_global: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct SwapOutcome {
+    amount_a: u64,
+    amount_b: u64,
+    next_liquidity: u128,
+    next_tick_index: i32,
+    next_sqrt_price: u128,
+    next_fee_growth_global: u128,
+}
+
+// The errors of the program and the errors of this crate for the same failure
+const EXPECTED_ERRORS: [(&str, ErrorCode); 5] = [
+    ("ZeroTradableAmount", ZERO_TRADABLE_AMOUNT),
+    ("MultiplicationOverflow", ARITHMETIC_OVERFLOW),
+    (
+        "MultiplicationShiftRightOverflow",
+        MULTIPLICATION_SHIFT_RIGHT_OVERFLOW,
+    ),
+    ("TokenMaxExceeded", AMOUNT_EXCEEDS_MAX_U64),
+    ("AmountCalcOverflow", AMOUNT_CALC_OVERFLOW),
+];
+
+#[test]
+fn test_concentrated_pool_swap_parity() {
+    run_swap_parity_tests("swap_test_cases.json");
+}
+
+#[test]
+fn test_splash_pool_swap_parity() {
+    run_swap_parity_tests("swap_test_cases_splash_pool.json");
+}
+
+fn run_swap_parity_tests(file_name: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../programs/whirlpool/src/tests")
+        .join(file_name);
+    let contents = fs::read_to_string(&path).expect("Failure to read the file.");
+    let test_cases: Vec<TestCase> =
+        serde_json::from_str(&contents).expect("JSON was not well-formatted");
+
+    let failures: Vec<String> = test_cases
+        .iter()
+        .filter_map(|test_case| {
+            let expected = expected_outcome(test_case);
+            let received = swap(test_case);
+            (expected != received).then(|| {
+                format!(
+                    "Test case {} - {}: expected {:?}, received {:?}",
+                    test_case.test_id, test_case.description, expected, received
+                )
+            })
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} test cases failed:\n{}",
+        failures.len(),
+        test_cases.len(),
+        failures.join("\n")
+    );
+}
+
+// Swaps through three empty tick arrays in the direction of the swap, up to the last tick of the
+// last tick array
+fn swap(test_case: &TestCase) -> Result<SwapOutcome, ErrorCode> {
+    let whirlpool = WhirlpoolFacade {
+        tick_spacing: test_case.tick_spacing,
+        fee_rate: test_case.fee_rate,
+        protocol_fee_rate: test_case.protocol_fee_rate,
+        liquidity: test_case.liquidity.parse().unwrap(),
+        sqrt_price: tick_index_to_sqrt_price(test_case.curr_tick_index),
+        tick_current_index: test_case.curr_tick_index,
+        ..WhirlpoolFacade::default()
+    };
+
+    let start_tick_index =
+        get_tick_array_start_tick_index(test_case.curr_tick_index, test_case.tick_spacing);
+    let ticks_per_array = TICK_ARRAY_SIZE as i32 * test_case.tick_spacing as i32;
+    let direction = if test_case.a_to_b { -1 } else { 1 };
+    let tick_arrays: [Option<TickArrayFacade>; 3] = core::array::from_fn(|i| {
+        Some(TickArrayFacade {
+            start_tick_index: start_tick_index + i as i32 * ticks_per_array * direction,
+            ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+        })
+    });
+    let tick_sequence = TickArraySequence::new(tick_arrays, test_case.tick_spacing)?;
+
+    let last_tick_index = if test_case.a_to_b {
+        start_tick_index - 2 * ticks_per_array
+    } else {
+        start_tick_index + 3 * ticks_per_array - 1
+    };
+    let sqrt_price_limit =
+        tick_index_to_sqrt_price(last_tick_index.clamp(MIN_TICK_INDEX, MAX_TICK_INDEX));
+
+    let result = compute_swap(
+        test_case.trade_amount.parse().unwrap(),
+        sqrt_price_limit,
+        whirlpool,
+        &tick_sequence,
+        test_case.a_to_b,
+        test_case.amount_is_input,
+        0,
+    )?;
+
+    Ok(SwapOutcome {
+        amount_a: result.token_a,
+        amount_b: result.token_b,
+        next_liquidity: result.liquidity,
+        next_tick_index: result.tick_current_index,
+        next_sqrt_price: result.sqrt_price,
+        next_fee_growth_global: result.fee_growth_global_input,
+    })
+}
+
+fn expected_outcome(test_case: &TestCase) -> Result<SwapOutcome, ErrorCode> {
+    let expectation = &test_case.expectation;
+    if !expectation.exception.is_empty() {
+        let error = EXPECTED_ERRORS
+            .iter()
+            .find(|(exception, _)| *exception == expectation.exception)
+            .unwrap_or_else(|| panic!("Unregistered exception - {}", expectation.exception));
+        return Err(error.1);
+    }
+
+    Ok(SwapOutcome {
+        amount_a: expectation.amount_a.parse().unwrap(),
+        amount_b: expectation.amount_b.parse().unwrap(),
+        next_liquidity: expectation.next_liquidity.parse().unwrap(),
+        next_tick_index: expectation.next_tick_index,
+        next_sqrt_price: expectation.next_sqrt_price.parse().unwrap(),
+        next_fee_growth_global: expectation.next_fee_growth_global.parse().unwrap(),
+    })
+}
@@ -0,0 +1,7 @@
+mod replay;
+mod simulator;
+mod strategy;
+
+pub use replay::*;
+pub use simulator::*;
+pub use strategy::*;
@@ -0,0 +1,226 @@
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{
+    try_apply_swap_transfer_fee, ErrorCode, PendingSwap, SwapDivergence, SwapReplay,
+    SwapReplayReport, WhirlpoolSimulator, ARITHMETIC_OVERFLOW,
+};
+
+/// Replay historical swaps against a snapshot of a whirlpool and compare the resulting prices
+/// with the observed prices.
+///
+/// The swaps are applied in order with the same state transitions as the program. A swap
+/// diverges if the sqrt price or the tick current index after the swap differs from the observed
+/// values, or if the swap fails. A failed swap is skipped. The replay continues from the
+/// simulated state after a divergence, so a snapshot that is missing initialized ticks usually
+/// diverges from the first swap that crosses one of them.
+///
+/// # Parameters
+/// - `replay`: The whirlpool and its tick arrays at the start of the replay, the swaps to apply,
+///   sorted by slot, and the transfer fees of the tokens. Tick arrays that are not provided have
+///   no initialized ticks. The transfer fees are applied to the swap amounts like `swap_v2` does.
+///
+/// # Returns
+/// - `SwapReplayReport`: The whirlpool after the last swap and every swap that diverged
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn replay_swaps(replay: SwapReplay) -> Result<SwapReplayReport, ErrorCode> {
+    let mut simulator = WhirlpoolSimulator::new(replay.whirlpool, &replay.tick_arrays);
+    let mut divergences = Vec::new();
+
+    for (index, swap) in replay.swaps.iter().enumerate() {
+        let pending_swap = PendingSwap {
+            amount: swap.amount,
+            amount_specified_is_input: swap.amount_specified_is_input,
+            a_to_b: swap.a_to_b,
+            sqrt_price_limit: swap.sqrt_price_limit,
+        };
+        // A failed swap transaction does not change the whirlpool, so the replay skips it
+        let snapshot = simulator.clone();
+        let error =
+            try_apply_swap_transfer_fee(pending_swap, replay.transfer_fee_a, replay.transfer_fee_b)
+                .and_then(|pending_swap| simulator.swap(pending_swap, swap.timestamp))
+                .err();
+        if error.is_some() {
+            simulator = snapshot;
+        }
+
+        let whirlpool = simulator.whirlpool();
+        if error.is_some()
+            || whirlpool.sqrt_price != swap.observed_sqrt_price
+            || whirlpool.tick_current_index != swap.observed_tick_current_index
+        {
+            divergences.push(SwapDivergence {
+                index: index.try_into().map_err(|_| ARITHMETIC_OVERFLOW)?,
+                slot: swap.slot,
+                sqrt_price: whirlpool.sqrt_price,
+                tick_current_index: whirlpool.tick_current_index,
+                observed_sqrt_price: swap.observed_sqrt_price,
+                observed_tick_current_index: swap.observed_tick_current_index,
+                error,
+            });
+        }
+    }
+
+    Ok(SwapReplayReport {
+        whirlpool: simulator.whirlpool(),
+        divergences,
+    })
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{
+        try_reverse_apply_transfer_fee, HistoricalSwap, TickArrayFacade, TickFacade, TransferFee,
+        WhirlpoolFacade, INVALID_TIMESTAMP, TICK_ARRAY_SIZE,
+    };
+
+    const LIQUIDITY: u128 = 1000000000000;
+
+    fn test_whirlpool() -> WhirlpoolFacade {
+        WhirlpoolFacade {
+            tick_spacing: 2,
+            fee_rate: 3000,
+            protocol_fee_rate: 300,
+            liquidity: LIQUIDITY,
+            sqrt_price: 1 << 64,
+            ..WhirlpoolFacade::default()
+        }
+    }
+
+    // Half of the liquidity ends at tick 20
+    fn test_tick_array() -> TickArrayFacade {
+        let mut ticks = [TickFacade::default(); TICK_ARRAY_SIZE];
+        ticks[10] = TickFacade {
+            initialized: true,
+            liquidity_net: -(LIQUIDITY as i128 / 2),
+            liquidity_gross: LIQUIDITY / 2,
+            ..TickFacade::default()
+        };
+        TickArrayFacade {
+            start_tick_index: 0,
+            ticks,
+        }
+    }
+
+    // The observed swaps are the result of swapping against the complete snapshot
+    fn test_swaps() -> Vec<HistoricalSwap> {
+        let mut simulator = WhirlpoolSimulator::new(test_whirlpool(), &[test_tick_array()]);
+        [(1000000, true), (2000000000, false), (5000000, true)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (amount, a_to_b))| {
+                let swap = PendingSwap {
+                    amount,
+                    amount_specified_is_input: true,
+                    a_to_b,
+                    sqrt_price_limit: 0,
+                };
+                simulator.swap(swap, 100 + i as u64).unwrap();
+                HistoricalSwap {
+                    slot: 1000 + i as u64,
+                    timestamp: 100 + i as u64,
+                    amount,
+                    amount_specified_is_input: true,
+                    a_to_b,
+                    sqrt_price_limit: 0,
+                    observed_sqrt_price: simulator.whirlpool().sqrt_price,
+                    observed_tick_current_index: simulator.whirlpool().tick_current_index,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_complete_snapshot() {
+        let swaps = test_swaps();
+        let report = replay_swaps(SwapReplay {
+            whirlpool: test_whirlpool(),
+            tick_arrays: vec![test_tick_array()],
+            swaps: swaps.clone(),
+            ..SwapReplay::default()
+        })
+        .unwrap();
+        assert_eq!(report.divergences, vec![]);
+        assert_eq!(report.whirlpool.sqrt_price, swaps[2].observed_sqrt_price);
+        assert!(report.whirlpool.tick_current_index > 20);
+        assert_eq!(report.whirlpool.liquidity, LIQUIDITY / 2);
+    }
+
+    #[test]
+    fn test_replay_missing_tick_array() {
+        let swaps = test_swaps();
+        let report = replay_swaps(SwapReplay {
+            whirlpool: test_whirlpool(),
+            tick_arrays: vec![],
+            swaps: swaps.clone(),
+            ..SwapReplay::default()
+        })
+        .unwrap();
+        assert_eq!(report.divergences.len(), 2);
+        let divergence = report.divergences[0];
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.slot, 1001);
+        assert_eq!(divergence.observed_sqrt_price, swaps[1].observed_sqrt_price);
+        assert!(divergence.sqrt_price < divergence.observed_sqrt_price);
+        assert_eq!(report.whirlpool.liquidity, LIQUIDITY);
+    }
+
+    #[test]
+    fn test_replay_transfer_fee() {
+        // The observed swaps swapped the amounts without the input transfer fee
+        let transfer_fee = TransferFee::new(100);
+        let swaps: Vec<HistoricalSwap> = test_swaps()
+            .into_iter()
+            .map(|swap| HistoricalSwap {
+                amount: try_reverse_apply_transfer_fee(swap.amount, transfer_fee).unwrap(),
+                ..swap
+            })
+            .collect();
+        let report = replay_swaps(SwapReplay {
+            whirlpool: test_whirlpool(),
+            tick_arrays: vec![test_tick_array()],
+            swaps: swaps.clone(),
+            transfer_fee_a: Some(transfer_fee),
+            transfer_fee_b: Some(transfer_fee),
+        })
+        .unwrap();
+        assert_eq!(report.divergences, vec![]);
+
+        let report = replay_swaps(SwapReplay {
+            whirlpool: test_whirlpool(),
+            tick_arrays: vec![test_tick_array()],
+            swaps,
+            ..SwapReplay::default()
+        })
+        .unwrap();
+        assert_eq!(report.divergences.len(), 3);
+    }
+
+    #[test]
+    fn test_replay_failed_swap() {
+        let mut swaps = test_swaps();
+        swaps[1].timestamp = 0;
+        let report = replay_swaps(SwapReplay {
+            whirlpool: test_whirlpool(),
+            tick_arrays: vec![test_tick_array()],
+            swaps: swaps.clone(),
+            ..SwapReplay::default()
+        })
+        .unwrap();
+
+        // The failed swap is skipped, so the swap after it diverges as well
+        assert_eq!(report.divergences.len(), 2);
+        let divergence = report.divergences[0];
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.slot, 1001);
+        assert_eq!(divergence.error, Some(INVALID_TIMESTAMP));
+        assert_eq!(divergence.sqrt_price, swaps[0].observed_sqrt_price);
+        assert_eq!(
+            divergence.tick_current_index,
+            swaps[0].observed_tick_current_index
+        );
+        assert_eq!(report.divergences[1].index, 2);
+        assert_eq!(report.divergences[1].error, None);
+    }
+}
@@ -0,0 +1,798 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    get_full_range_tick_indexes, get_tick_array_start_tick_index, get_tick_index_in_array,
+    is_full_range_only, is_tick_index_in_bounds, is_tick_initializable, tick_index_to_sqrt_price,
+    try_apply_swap, try_get_amount_delta_a, try_get_amount_delta_b, try_get_next_reward_infos,
+    ErrorCode, PendingSwap, PositionFacade, SimulatedSwap, TickArrayFacade, TickFacade,
+    WhirlpoolFacade, ARITHMETIC_OVERFLOW, INVALID_REWARD_INDEX, INVALID_SQRT_PRICE_LIMIT_DIRECTION,
+    INVALID_TICK_INDEX, MAX_SQRT_PRICE, MAX_TICK_INDEX, MIN_SQRT_PRICE, MIN_TICK_INDEX,
+    NUM_REWARDS, POSITION_NOT_EMPTY, POSITION_NOT_FOUND, SQRT_PRICE_LIMIT_OUT_OF_BOUNDS,
+    TICK_ARRAY_SIZE, ZERO_LIQUIDITY, ZERO_TRADABLE_AMOUNT,
+};
+
+// The number of tick arrays a single simulated swap can traverse
+const SWAP_TICK_ARRAY_COUNT: usize = 6;
+
+/// An in-memory whirlpool with its tick arrays and positions.
+///
+/// Every operation changes the state the same way the corresponding instruction of the program
+/// does, including the rounding of token amounts and the fee and reward growth updates. Tick
+/// arrays that are not part of the state have no initialized ticks, so a whirlpool without any
+/// tick arrays behaves like a pool with the same liquidity at every price. Timestamps must never
+/// decrease between operations.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct WhirlpoolSimulator {
+    whirlpool: WhirlpoolFacade,
+    tick_arrays: BTreeMap<i32, TickArrayFacade>,
+    positions: BTreeMap<u32, PositionFacade>,
+    next_position_id: u32,
+}
+
+impl WhirlpoolSimulator {
+    /// Create a simulator from a whirlpool and its tick arrays.
+    pub fn new(whirlpool: WhirlpoolFacade, tick_arrays: &[TickArrayFacade]) -> Self {
+        Self {
+            whirlpool,
+            tick_arrays: tick_arrays
+                .iter()
+                .map(|tick_array| (tick_array.start_tick_index, *tick_array))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// The current whirlpool state.
+    pub fn whirlpool(&self) -> WhirlpoolFacade {
+        self.whirlpool
+    }
+
+    /// The current state of a tick.
+    pub fn tick(&self, tick_index: i32) -> TickFacade {
+        let tick_spacing = self.whirlpool.tick_spacing;
+        let start_tick_index = get_tick_array_start_tick_index(tick_index, tick_spacing);
+        self.tick_arrays
+            .get(&start_tick_index)
+            .map_or(TickFacade::default(), |tick_array| {
+                let index_in_array =
+                    get_tick_index_in_array(tick_index, start_tick_index, tick_spacing);
+                tick_array.ticks[index_in_array as usize]
+            })
+    }
+
+    /// The current state of a position, if it is open.
+    pub fn position(&self, position_id: u32) -> Option<PositionFacade> {
+        self.positions.get(&position_id).copied()
+    }
+
+    /// Open an empty position and return its id.
+    pub fn open_position(
+        &mut self,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+    ) -> Result<u32, ErrorCode> {
+        let tick_spacing = self.whirlpool.tick_spacing;
+        let is_valid_tick_index = |tick_index| {
+            is_tick_index_in_bounds(tick_index) && is_tick_initializable(tick_index, tick_spacing)
+        };
+        if !is_valid_tick_index(tick_lower_index)
+            || !is_valid_tick_index(tick_upper_index)
+            || tick_lower_index >= tick_upper_index
+        {
+            return Err(INVALID_TICK_INDEX);
+        }
+        let full_range = get_full_range_tick_indexes(tick_spacing);
+        if is_full_range_only(tick_spacing)
+            && (tick_lower_index != full_range.tick_lower_index
+                || tick_upper_index != full_range.tick_upper_index)
+        {
+            return Err(INVALID_TICK_INDEX);
+        }
+
+        let position_id = self.next_position_id;
+        self.next_position_id += 1;
+        self.positions.insert(
+            position_id,
+            PositionFacade {
+                tick_lower_index,
+                tick_upper_index,
+                ..PositionFacade::default()
+            },
+        );
+        Ok(position_id)
+    }
+
+    /// Close a position. The position must not have any liquidity, fees or rewards left.
+    pub fn close_position(&mut self, position_id: u32) -> Result<(), ErrorCode> {
+        let position = self.try_get_position(position_id)?;
+        let is_empty = position.liquidity == 0
+            && position.fee_owed_a == 0
+            && position.fee_owed_b == 0
+            && position
+                .reward_infos
+                .iter()
+                .all(|reward_info| reward_info.amount_owed == 0);
+        if !is_empty {
+            return Err(POSITION_NOT_EMPTY);
+        }
+        self.positions.remove(&position_id);
+        Ok(())
+    }
+
+    /// Add liquidity to a position and return the token amounts that are deposited, rounded up.
+    pub fn increase_liquidity(
+        &mut self,
+        position_id: u32,
+        liquidity_delta: u128,
+        timestamp: u64,
+    ) -> Result<(u64, u64), ErrorCode> {
+        if liquidity_delta == 0 {
+            return Err(ZERO_LIQUIDITY);
+        }
+        let liquidity_delta = i128::try_from(liquidity_delta).map_err(|_| ARITHMETIC_OVERFLOW)?;
+        self.try_modify_liquidity(position_id, liquidity_delta, timestamp)
+    }
+
+    /// Remove liquidity from a position and return the token amounts that are withdrawn, rounded
+    /// down. Fees and rewards earned so far are added to the amounts owed of the position.
+    pub fn decrease_liquidity(
+        &mut self,
+        position_id: u32,
+        liquidity_delta: u128,
+        timestamp: u64,
+    ) -> Result<(u64, u64), ErrorCode> {
+        if liquidity_delta == 0 {
+            return Err(ZERO_LIQUIDITY);
+        }
+        let liquidity_delta = i128::try_from(liquidity_delta).map_err(|_| ARITHMETIC_OVERFLOW)?;
+        self.try_modify_liquidity(position_id, -liquidity_delta, timestamp)
+    }
+
+    /// Add the fees and rewards a position earned so far to its amounts owed.
+    pub fn update_fees_and_rewards(
+        &mut self,
+        position_id: u32,
+        timestamp: u64,
+    ) -> Result<(), ErrorCode> {
+        self.try_modify_liquidity(position_id, 0, timestamp)?;
+        Ok(())
+    }
+
+    /// Collect the fees owed to a position and return the token amounts.
+    pub fn collect_fees(&mut self, position_id: u32) -> Result<(u64, u64), ErrorCode> {
+        let position = self.try_get_position_mut(position_id)?;
+        let fees = (position.fee_owed_a, position.fee_owed_b);
+        position.fee_owed_a = 0;
+        position.fee_owed_b = 0;
+        Ok(fees)
+    }
+
+    /// Collect the rewards owed to a position for one of the rewards of the whirlpool.
+    pub fn collect_reward(
+        &mut self,
+        position_id: u32,
+        reward_index: usize,
+    ) -> Result<u64, ErrorCode> {
+        if reward_index >= NUM_REWARDS {
+            return Err(INVALID_REWARD_INDEX);
+        }
+        let position = self.try_get_position_mut(position_id)?;
+        let reward_owed = position.reward_infos[reward_index].amount_owed;
+        position.reward_infos[reward_index].amount_owed = 0;
+        Ok(reward_owed)
+    }
+
+    /// Advance the global reward growths to a timestamp and change the emissions of a reward.
+    pub fn set_reward_emissions(
+        &mut self,
+        reward_index: usize,
+        emissions_per_second_x64: u128,
+        timestamp: u64,
+    ) -> Result<(), ErrorCode> {
+        if reward_index >= NUM_REWARDS {
+            return Err(INVALID_REWARD_INDEX);
+        }
+        self.advance_time(timestamp)?;
+        self.whirlpool.reward_infos[reward_index].emissions_per_second_x64 =
+            emissions_per_second_x64;
+        Ok(())
+    }
+
+    /// Advance the global reward growths of the whirlpool to a timestamp.
+    pub fn advance_time(&mut self, timestamp: u64) -> Result<(), ErrorCode> {
+        self.whirlpool.reward_infos = try_get_next_reward_infos(self.whirlpool, timestamp)?;
+        self.whirlpool.reward_last_updated_timestamp = timestamp;
+        Ok(())
+    }
+
+    /// Swap against the whirlpool. Unlike the program, the swap can traverse any number of tick
+    /// arrays. If the sqrt price limit is zero, the swap continues until the specified amount is
+    /// filled or the price reaches the minimum or maximum sqrt price.
+    pub fn swap(&mut self, swap: PendingSwap, timestamp: u64) -> Result<SimulatedSwap, ErrorCode> {
+        let sqrt_price_limit = match (swap.sqrt_price_limit, swap.a_to_b) {
+            (0, true) => MIN_SQRT_PRICE,
+            (0, false) => MAX_SQRT_PRICE,
+            (sqrt_price_limit, _) => sqrt_price_limit,
+        };
+        if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price_limit) {
+            return Err(SQRT_PRICE_LIMIT_OUT_OF_BOUNDS);
+        }
+        if swap.a_to_b && sqrt_price_limit > self.whirlpool.sqrt_price
+            || !swap.a_to_b && sqrt_price_limit < self.whirlpool.sqrt_price
+        {
+            return Err(INVALID_SQRT_PRICE_LIMIT_DIRECTION);
+        }
+        if swap.amount == 0 {
+            return Err(ZERO_TRADABLE_AMOUNT);
+        }
+
+        let mut amount_remaining = swap.amount;
+        let mut amount_calculated = 0u64;
+        let mut trade_fee = 0u64;
+        while amount_remaining > 0 && self.whirlpool.sqrt_price != sqrt_price_limit {
+            let (mut tick_arrays, window_sqrt_price_limit) = self.swap_tick_arrays(swap.a_to_b);
+            let window_swap = PendingSwap {
+                amount: amount_remaining,
+                sqrt_price_limit: if swap.a_to_b {
+                    sqrt_price_limit.max(window_sqrt_price_limit)
+                } else {
+                    sqrt_price_limit.min(window_sqrt_price_limit)
+                },
+                ..swap
+            };
+            let swap_result = try_apply_swap(
+                window_swap,
+                &mut self.whirlpool,
+                &mut tick_arrays,
+                timestamp,
+            )?;
+
+            // Only tick arrays with initialized ticks are changed by a swap
+            for tick_array in tick_arrays.into_iter().flatten() {
+                if let Some(state) = self.tick_arrays.get_mut(&tick_array.start_tick_index) {
+                    *state = tick_array;
+                }
+            }
+
+            let (amount_specified, amount_other) = if swap.a_to_b == swap.amount_specified_is_input
+            {
+                (swap_result.token_a, swap_result.token_b)
+            } else {
+                (swap_result.token_b, swap_result.token_a)
+            };
+            amount_remaining -= amount_specified;
+            amount_calculated = amount_calculated
+                .checked_add(amount_other)
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+            trade_fee += swap_result.trade_fee;
+        }
+
+        let amount_swapped = swap.amount - amount_remaining;
+        let (token_in, token_out) = if swap.amount_specified_is_input {
+            (amount_swapped, amount_calculated)
+        } else {
+            (amount_calculated, amount_swapped)
+        };
+        Ok(SimulatedSwap {
+            token_in,
+            token_out,
+            trade_fee,
+        })
+    }
+
+    /// Swap until the whirlpool reaches a sqrt price, however much that costs.
+    pub fn swap_to_sqrt_price(
+        &mut self,
+        sqrt_price: u128,
+        timestamp: u64,
+    ) -> Result<(), ErrorCode> {
+        let sqrt_price = sqrt_price.clamp(MIN_SQRT_PRICE, MAX_SQRT_PRICE);
+        while self.whirlpool.sqrt_price != sqrt_price {
+            let swap = PendingSwap {
+                amount: u64::MAX,
+                amount_specified_is_input: true,
+                a_to_b: sqrt_price < self.whirlpool.sqrt_price,
+                sqrt_price_limit: sqrt_price,
+            };
+            self.swap(swap, timestamp)?;
+        }
+        Ok(())
+    }
+
+    // Private functions
+
+    fn try_get_position(&self, position_id: u32) -> Result<PositionFacade, ErrorCode> {
+        self.position(position_id).ok_or(POSITION_NOT_FOUND)
+    }
+
+    fn try_get_position_mut(&mut self, position_id: u32) -> Result<&mut PositionFacade, ErrorCode> {
+        self.positions
+            .get_mut(&position_id)
+            .ok_or(POSITION_NOT_FOUND)
+    }
+
+    fn tick_mut(&mut self, tick_index: i32) -> &mut TickFacade {
+        let tick_spacing = self.whirlpool.tick_spacing;
+        let start_tick_index = get_tick_array_start_tick_index(tick_index, tick_spacing);
+        let index_in_array = get_tick_index_in_array(tick_index, start_tick_index, tick_spacing);
+        let tick_array = self
+            .tick_arrays
+            .entry(start_tick_index)
+            .or_insert_with(|| empty_tick_array(start_tick_index));
+        &mut tick_array.ticks[index_in_array as usize]
+    }
+
+    // The tick arrays a single swap can traverse starting at the current tick index, and the
+    // sqrt price at the end of them
+    fn swap_tick_arrays(
+        &self,
+        a_to_b: bool,
+    ) -> ([Option<TickArrayFacade>; SWAP_TICK_ARRAY_COUNT], u128) {
+        let tick_spacing = self.whirlpool.tick_spacing;
+        let ticks_per_array = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
+        let first_start_tick_index =
+            get_tick_array_start_tick_index(self.whirlpool.tick_current_index, tick_spacing);
+        let start_tick_indexes: [i32; SWAP_TICK_ARRAY_COUNT] = core::array::from_fn(|i| {
+            if a_to_b {
+                first_start_tick_index - i as i32 * ticks_per_array
+            } else {
+                first_start_tick_index + i as i32 * ticks_per_array
+            }
+        });
+
+        let last_start_tick_index = start_tick_indexes[SWAP_TICK_ARRAY_COUNT - 1];
+        let end_tick_index = if a_to_b {
+            last_start_tick_index.max(MIN_TICK_INDEX)
+        } else {
+            (last_start_tick_index + ticks_per_array - 1).min(MAX_TICK_INDEX)
+        };
+
+        let tick_arrays = start_tick_indexes.map(|start_tick_index| {
+            Some(
+                self.tick_arrays
+                    .get(&start_tick_index)
+                    .copied()
+                    .unwrap_or_else(|| empty_tick_array(start_tick_index)),
+            )
+        });
+        (tick_arrays, tick_index_to_sqrt_price(end_tick_index).into())
+    }
+
+    // Changes the liquidity of a position the same way the program does. The fees and rewards
+    // the position earned so far are added to its amounts owed. A liquidity delta of zero only
+    // updates the fees and rewards.
+    fn try_modify_liquidity(
+        &mut self,
+        position_id: u32,
+        liquidity_delta: i128,
+        timestamp: u64,
+    ) -> Result<(u64, u64), ErrorCode> {
+        let position = self.try_get_position(position_id)?;
+        if liquidity_delta == 0 && position.liquidity == 0 {
+            return Err(ZERO_LIQUIDITY);
+        }
+        let whirlpool = self.whirlpool;
+        let reward_infos = try_get_next_reward_infos(whirlpool, timestamp)?;
+        let tick_lower_index = position.tick_lower_index;
+        let tick_upper_index = position.tick_upper_index;
+        let tick_lower = self.tick(tick_lower_index);
+        let tick_upper = self.tick(tick_upper_index);
+
+        // By convention, all growth before a tick was initialized happened below the tick
+        let growth_inside = |global: u128, lower_outside: u128, upper_outside: u128| {
+            let below = if !tick_lower.initialized {
+                global
+            } else if whirlpool.tick_current_index < tick_lower_index {
+                global.wrapping_sub(lower_outside)
+            } else {
+                lower_outside
+            };
+            let above = if !tick_upper.initialized {
+                0
+            } else if whirlpool.tick_current_index < tick_upper_index {
+                upper_outside
+            } else {
+                global.wrapping_sub(upper_outside)
+            };
+            global.wrapping_sub(below).wrapping_sub(above)
+        };
+        // Like `checked_mul_shift_right` in the program
+        let amount_owed_delta = |growth_inside: u128, checkpoint: u128| {
+            let product = position
+                .liquidity
+                .checked_mul(growth_inside.wrapping_sub(checkpoint))
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+            u64::try_from(product >> 64).map_err(|_| ARITHMETIC_OVERFLOW)
+        };
+
+        let mut next_position = position;
+        let fee_growth_inside_a = growth_inside(
+            whirlpool.fee_growth_global_a,
+            tick_lower.fee_growth_outside_a,
+            tick_upper.fee_growth_outside_a,
+        );
+        let fee_growth_inside_b = growth_inside(
+            whirlpool.fee_growth_global_b,
+            tick_lower.fee_growth_outside_b,
+            tick_upper.fee_growth_outside_b,
+        );
+        next_position.fee_owed_a = position.fee_owed_a.wrapping_add(amount_owed_delta(
+            fee_growth_inside_a,
+            position.fee_growth_checkpoint_a,
+        )?);
+        next_position.fee_owed_b = position.fee_owed_b.wrapping_add(amount_owed_delta(
+            fee_growth_inside_b,
+            position.fee_growth_checkpoint_b,
+        )?);
+        next_position.fee_growth_checkpoint_a = fee_growth_inside_a;
+        next_position.fee_growth_checkpoint_b = fee_growth_inside_b;
+        for (i, reward_info) in next_position.reward_infos.iter_mut().enumerate() {
+            let reward_growth_inside = growth_inside(
+                reward_infos[i].growth_global_x64,
+                tick_lower.reward_growths_outside[i],
+                tick_upper.reward_growths_outside[i],
+            );
+            reward_info.amount_owed = reward_info.amount_owed.wrapping_add(amount_owed_delta(
+                reward_growth_inside,
+                reward_info.growth_inside_checkpoint,
+            )?);
+            reward_info.growth_inside_checkpoint = reward_growth_inside;
+        }
+        next_position.liquidity = position
+            .liquidity
+            .checked_add_signed(liquidity_delta)
+            .ok_or(ARITHMETIC_OVERFLOW)?;
+
+        let mut next_whirlpool = whirlpool;
+        next_whirlpool.reward_infos = reward_infos;
+        next_whirlpool.reward_last_updated_timestamp = timestamp;
+        if (tick_lower_index..tick_upper_index).contains(&whirlpool.tick_current_index) {
+            next_whirlpool.liquidity = whirlpool
+                .liquidity
+                .checked_add_signed(liquidity_delta)
+                .ok_or(ARITHMETIC_OVERFLOW)?;
+        }
+
+        let token_deltas = if liquidity_delta == 0 {
+            (0, 0)
+        } else {
+            self.try_get_token_deltas(&position, liquidity_delta)?
+        };
+
+        if liquidity_delta != 0 {
+            let next_tick_lower = next_tick(
+                tick_lower,
+                tick_lower_index,
+                &next_whirlpool,
+                liquidity_delta,
+                false,
+            )?;
+            let next_tick_upper = next_tick(
+                tick_upper,
+                tick_upper_index,
+                &next_whirlpool,
+                liquidity_delta,
+                true,
+            )?;
+            *self.tick_mut(tick_lower_index) = next_tick_lower;
+            *self.tick_mut(tick_upper_index) = next_tick_upper;
+        }
+        self.positions.insert(position_id, next_position);
+        self.whirlpool = next_whirlpool;
+        Ok(token_deltas)
+    }
+
+    fn try_get_token_deltas(
+        &self,
+        position: &PositionFacade,
+        liquidity_delta: i128,
+    ) -> Result<(u64, u64), ErrorCode> {
+        let liquidity = liquidity_delta.unsigned_abs();
+        let round_up = liquidity_delta > 0;
+        let sqrt_price = self.whirlpool.sqrt_price;
+        let sqrt_price_lower: u128 = tick_index_to_sqrt_price(position.tick_lower_index).into();
+        let sqrt_price_upper: u128 = tick_index_to_sqrt_price(position.tick_upper_index).into();
+
+        if self.whirlpool.tick_current_index < position.tick_lower_index {
+            let token_a = try_get_amount_delta_a(
+                sqrt_price_lower.into(),
+                sqrt_price_upper.into(),
+                liquidity.into(),
+                round_up,
+            )?;
+            Ok((token_a, 0))
+        } else if self.whirlpool.tick_current_index < position.tick_upper_index {
+            let token_a = try_get_amount_delta_a(
+                sqrt_price.into(),
+                sqrt_price_upper.into(),
+                liquidity.into(),
+                round_up,
+            )?;
+            let token_b = try_get_amount_delta_b(
+                sqrt_price_lower.into(),
+                sqrt_price.into(),
+                liquidity.into(),
+                round_up,
+            )?;
+            Ok((token_a, token_b))
+        } else {
+            let token_b = try_get_amount_delta_b(
+                sqrt_price_lower.into(),
+                sqrt_price_upper.into(),
+                liquidity.into(),
+                round_up,
+            )?;
+            Ok((0, token_b))
+        }
+    }
+}
+
+// The state of a tick after the liquidity of a position that starts or ends at the tick changes.
+// Ticks that are initialized start with all growth outside of the tick if the current price is
+// above the tick, and are reset once they have no liquidity left.
+fn next_tick(
+    tick: TickFacade,
+    tick_index: i32,
+    whirlpool: &WhirlpoolFacade,
+    liquidity_delta: i128,
+    is_upper_tick: bool,
+) -> Result<TickFacade, ErrorCode> {
+    let liquidity_gross = tick
+        .liquidity_gross
+        .checked_add_signed(liquidity_delta)
+        .ok_or(ARITHMETIC_OVERFLOW)?;
+    if liquidity_gross == 0 {
+        return Ok(TickFacade::default());
+    }
+
+    let mut next_tick = tick;
+    if tick.liquidity_gross == 0 {
+        next_tick = TickFacade::default();
+        if whirlpool.tick_current_index >= tick_index {
+            next_tick.fee_growth_outside_a = whirlpool.fee_growth_global_a;
+            next_tick.fee_growth_outside_b = whirlpool.fee_growth_global_b;
+            next_tick.reward_growths_outside = whirlpool
+                .reward_infos
+                .map(|reward_info| reward_info.growth_global_x64);
+        }
+    }
+    next_tick.liquidity_net = if is_upper_tick {
+        tick.liquidity_net.checked_sub(liquidity_delta)
+    } else {
+        tick.liquidity_net.checked_add(liquidity_delta)
+    }
+    .ok_or(ARITHMETIC_OVERFLOW)?;
+    next_tick.liquidity_gross = liquidity_gross;
+    next_tick.initialized = true;
+    Ok(next_tick)
+}
+
+fn empty_tick_array(start_tick_index: i32) -> TickArrayFacade {
+    TickArrayFacade {
+        start_tick_index,
+        ticks: [TickFacade::default(); TICK_ARRAY_SIZE],
+    }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{collect_fees_quote, WhirlpoolRewardInfoFacade, INVALID_TIMESTAMP};
+
+    const LIQUIDITY: u128 = 1000000000000;
+
+    fn test_simulator() -> WhirlpoolSimulator {
+        let whirlpool = WhirlpoolFacade {
+            tick_spacing: 2,
+            fee_rate: 3000,
+            protocol_fee_rate: 300,
+            liquidity: LIQUIDITY,
+            sqrt_price: 1 << 64,
+            reward_last_updated_timestamp: 100,
+            reward_infos: [
+                WhirlpoolRewardInfoFacade {
+                    emissions_per_second_x64: 1 << 64,
+                    growth_global_x64: 0,
+                },
+                WhirlpoolRewardInfoFacade::default(),
+                WhirlpoolRewardInfoFacade::default(),
+            ],
+            ..WhirlpoolFacade::default()
+        };
+        WhirlpoolSimulator::new(whirlpool, &[])
+    }
+
+    fn test_swap(amount: u64, a_to_b: bool) -> PendingSwap {
+        PendingSwap {
+            amount,
+            amount_specified_is_input: true,
+            a_to_b,
+            sqrt_price_limit: 0,
+        }
+    }
+
+    #[test]
+    fn test_open_and_close_position() {
+        let mut simulator = test_simulator();
+        assert_eq!(simulator.open_position(-10, 10), Ok(0));
+        assert_eq!(simulator.open_position(-10, 10), Ok(1));
+        assert_eq!(simulator.open_position(10, -10), Err(INVALID_TICK_INDEX));
+        assert_eq!(simulator.open_position(-9, 10), Err(INVALID_TICK_INDEX));
+        assert_eq!(
+            simulator.open_position(MIN_TICK_INDEX - 2, 10),
+            Err(INVALID_TICK_INDEX)
+        );
+
+        simulator.increase_liquidity(0, 1000, 100).unwrap();
+        assert_eq!(simulator.close_position(0), Err(POSITION_NOT_EMPTY));
+        assert_eq!(simulator.close_position(1), Ok(()));
+        assert_eq!(simulator.close_position(1), Err(POSITION_NOT_FOUND));
+        assert_eq!(simulator.position(1), None);
+    }
+
+    #[test]
+    fn test_modify_liquidity() {
+        let mut simulator = test_simulator();
+        let position_id = simulator.open_position(-10, 10).unwrap();
+        let (token_a, token_b) = simulator
+            .increase_liquidity(position_id, LIQUIDITY, 100)
+            .unwrap();
+        assert_eq!(token_a, 499850035);
+        assert_eq!(token_b, 499850035);
+        assert_eq!(simulator.whirlpool().liquidity, 2 * LIQUIDITY);
+        assert_eq!(simulator.tick(-10).liquidity_net, LIQUIDITY as i128);
+        assert_eq!(simulator.tick(10).liquidity_net, -(LIQUIDITY as i128));
+
+        // Withdrawing rounds down
+        let (token_a, token_b) = simulator
+            .decrease_liquidity(position_id, LIQUIDITY, 100)
+            .unwrap();
+        assert_eq!(token_a, 499850034);
+        assert_eq!(token_b, 499850034);
+        assert_eq!(simulator.whirlpool().liquidity, LIQUIDITY);
+        assert_eq!(simulator.tick(-10), TickFacade::default());
+        assert_eq!(simulator.tick(10), TickFacade::default());
+
+        assert_eq!(
+            simulator.update_fees_and_rewards(position_id, 100),
+            Err(ZERO_LIQUIDITY)
+        );
+        assert_eq!(
+            simulator.increase_liquidity(position_id, LIQUIDITY, 99),
+            Err(INVALID_TIMESTAMP)
+        );
+        assert_eq!(
+            simulator.increase_liquidity(position_id, 0, 100),
+            Err(ZERO_LIQUIDITY)
+        );
+        assert_eq!(
+            simulator.decrease_liquidity(position_id, 0, 100),
+            Err(ZERO_LIQUIDITY)
+        );
+    }
+
+    #[test]
+    fn test_fees_owed_overflow() {
+        let mut simulator = test_simulator();
+        let position_id = simulator.open_position(-10, 10).unwrap();
+        simulator
+            .increase_liquidity(position_id, LIQUIDITY, 100)
+            .unwrap();
+
+        // The liquidity times the fee growth inside does not fit in a u128
+        simulator.whirlpool.fee_growth_global_a = u128::MAX / 2;
+        assert_eq!(
+            simulator.update_fees_and_rewards(position_id, 100),
+            Err(ARITHMETIC_OVERFLOW)
+        );
+    }
+
+    #[test]
+    fn test_swap_fees() {
+        let mut simulator = test_simulator();
+        let position_id = simulator.open_position(-10, 10).unwrap();
+        simulator
+            .increase_liquidity(position_id, LIQUIDITY, 100)
+            .unwrap();
+
+        let swap = simulator.swap(test_swap(100000000, true), 100).unwrap();
+        assert_eq!(swap.token_in, 100000000);
+        assert_eq!(swap.trade_fee, 300000);
+        assert!(swap.token_out > 0);
+
+        // The position owns half of the liquidity and the protocol takes 3% of the fees
+        simulator.update_fees_and_rewards(position_id, 100).unwrap();
+        assert_eq!(simulator.collect_fees(position_id), Ok((145499, 0)));
+        assert_eq!(simulator.collect_fees(position_id), Ok((0, 0)));
+
+        // The fees match the fees owed quote for the same state
+        simulator.swap(test_swap(100000000, false), 100).unwrap();
+        let position = simulator.position(position_id).unwrap();
+        let quote = collect_fees_quote(
+            simulator.whirlpool(),
+            position,
+            simulator.tick(-10),
+            simulator.tick(10),
+            None,
+            None,
+        )
+        .unwrap();
+        simulator.update_fees_and_rewards(position_id, 100).unwrap();
+        assert_eq!(
+            simulator.collect_fees(position_id),
+            Ok((quote.fee_owed_a, quote.fee_owed_b))
+        );
+    }
+
+    #[test]
+    fn test_swap_across_tick_arrays() {
+        let mut simulator = test_simulator();
+        let position_id = simulator.open_position(-10, 10).unwrap();
+        simulator
+            .increase_liquidity(position_id, LIQUIDITY, 100)
+            .unwrap();
+
+        // Moves the price across more tick arrays than a single swap can traverse
+        let sqrt_price: u128 = tick_index_to_sqrt_price(5000).into();
+        simulator.swap_to_sqrt_price(sqrt_price, 100).unwrap();
+        assert_eq!(simulator.whirlpool().sqrt_price, sqrt_price);
+        assert_eq!(simulator.whirlpool().tick_current_index, 5000);
+        assert_eq!(simulator.whirlpool().liquidity, LIQUIDITY);
+
+        let sqrt_price: u128 = tick_index_to_sqrt_price(-5000).into();
+        simulator.swap_to_sqrt_price(sqrt_price, 100).unwrap();
+        assert_eq!(simulator.whirlpool().tick_current_index, -5000);
+        assert_eq!(simulator.whirlpool().liquidity, LIQUIDITY);
+
+        // Out of range, so the fees only come from the swaps across the range
+        simulator.update_fees_and_rewards(position_id, 100).unwrap();
+        let (fee_a, fee_b) = simulator.collect_fees(position_id).unwrap();
+        assert!(fee_a > 0);
+        assert!(fee_b > 0);
+        simulator.swap(test_swap(1000000, true), 100).unwrap();
+        simulator.update_fees_and_rewards(position_id, 100).unwrap();
+        assert_eq!(simulator.collect_fees(position_id), Ok((0, 0)));
+    }
+
+    #[test]
+    fn test_swap_invalid() {
+        let mut simulator = test_simulator();
+        assert_eq!(
+            simulator.swap(test_swap(0, true), 100),
+            Err(ZERO_TRADABLE_AMOUNT)
+        );
+        let swap = PendingSwap {
+            sqrt_price_limit: MAX_SQRT_PRICE,
+            ..test_swap(1000, true)
+        };
+        assert_eq!(
+            simulator.swap(swap, 100),
+            Err(INVALID_SQRT_PRICE_LIMIT_DIRECTION)
+        );
+    }
+
+    #[test]
+    fn test_rewards() {
+        let mut simulator = test_simulator();
+        let position_id = simulator.open_position(-10, 10).unwrap();
+        simulator
+            .increase_liquidity(position_id, LIQUIDITY, 100)
+            .unwrap();
+
+        // One token per second, shared with the other half of the liquidity
+        simulator.advance_time(110).unwrap();
+        simulator.update_fees_and_rewards(position_id, 120).unwrap();
+        assert_eq!(simulator.whirlpool().reward_last_updated_timestamp, 120);
+        assert_eq!(simulator.collect_reward(position_id, 0), Ok(9));
+        assert_eq!(simulator.collect_reward(position_id, 0), Ok(0));
+        assert_eq!(simulator.collect_reward(position_id, 1), Ok(0));
+        assert_eq!(
+            simulator.collect_reward(position_id, 3),
+            Err(INVALID_REWARD_INDEX)
+        );
+
+        simulator.set_reward_emissions(0, 0, 130).unwrap();
+        simulator.update_fees_and_rewards(position_id, 200).unwrap();
+        assert_eq!(simulator.collect_reward(position_id, 0), Ok(4));
+    }
+}
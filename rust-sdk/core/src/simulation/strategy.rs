@@ -0,0 +1,570 @@
+use ethnum::U256;
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+#[cfg(feature = "floats")]
+use libm::{cos, log, round, sqrt};
+
+use crate::{
+    get_full_range_tick_indexes, get_initializable_tick_index, increase_liquidity_quote_a,
+    increase_liquidity_quote_b, order_tick_indexes, tick_index_to_sqrt_price, try_get_duration,
+    ErrorCode, IncreaseLiquidityQuote, PriceHistory, RebalancePolicy, StrategyCandidate,
+    StrategyPool, StrategyResult, WhirlpoolSimulator, AMOUNT_EXCEEDS_MAX_U64, ARITHMETIC_OVERFLOW,
+};
+
+#[cfg(feature = "floats")]
+use crate::{
+    PricePathParams, PriceSnapshot, StrategyResults, INVALID_TIMESTAMP, MAX_TICK_INDEX,
+    MIN_TICK_INDEX,
+};
+
+/// Simulate a liquidity providing strategy along a price path.
+///
+/// The pool is swapped to the price of every snapshot with the same swap math as the program, so
+/// the position earns the exact fees of the swaps that move the price. The position is opened at
+/// the first snapshot with as much of the token amounts as possible, the rest is held. All values
+/// are denominated in token B at the price of the pool.
+///
+/// # Parameters
+/// - `pool`: The whirlpool and its tick arrays. Tick arrays that are not provided have no
+///   initialized ticks.
+/// - `path`: The price snapshots, sorted by timestamp
+/// - `candidate`: The tick range relative to the current tick index and the rebalance policy
+/// - `token_max_a`: The amount of token A to provide
+/// - `token_max_b`: The amount of token B to provide
+///
+/// # Returns
+/// - `StrategyResult`: The final holdings, fees earned, PnL, impermanent loss and time in range
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn simulate_strategy(
+    pool: StrategyPool,
+    path: PriceHistory,
+    candidate: StrategyCandidate,
+    token_max_a: u64,
+    token_max_b: u64,
+) -> Result<StrategyResult, ErrorCode> {
+    try_get_duration(&path.snapshots)?;
+    let mut simulator = WhirlpoolSimulator::new(pool.whirlpool, &pool.tick_arrays);
+    let mut result = StrategyResult {
+        token_a: token_max_a,
+        token_b: token_max_b,
+        ..StrategyResult::default()
+    };
+
+    let first = path.snapshots[0];
+    simulator.swap_to_sqrt_price(
+        tick_index_to_sqrt_price(first.tick_index).into(),
+        first.timestamp,
+    )?;
+    let initial_sqrt_price = simulator.whirlpool().sqrt_price;
+    let mut position_id =
+        try_open_position(&mut simulator, candidate, &mut result, first.timestamp)?;
+
+    for window in path.snapshots.windows(2) {
+        if is_in_range(&simulator, &result) {
+            result.time_in_range += window[1].timestamp - window[0].timestamp;
+        }
+        simulator.swap_to_sqrt_price(
+            tick_index_to_sqrt_price(window[1].tick_index).into(),
+            window[1].timestamp,
+        )?;
+        if candidate.rebalance_policy == RebalancePolicy::WhenOutOfRange
+            && !is_in_range(&simulator, &result)
+        {
+            try_close_position(
+                &mut simulator,
+                position_id,
+                &mut result,
+                window[1].timestamp,
+            )?;
+            position_id =
+                try_open_position(&mut simulator, candidate, &mut result, window[1].timestamp)?;
+            result.rebalance_count += 1;
+        }
+    }
+
+    let last = path.snapshots[path.snapshots.len() - 1];
+    try_close_position(&mut simulator, position_id, &mut result, last.timestamp)?;
+
+    let sqrt_price = simulator.whirlpool().sqrt_price;
+    result.initial_value = try_get_value(token_max_a, token_max_b, initial_sqrt_price)?;
+    result.hold_value = try_get_value(token_max_a, token_max_b, sqrt_price)?;
+    result.final_value = try_get_value(result.token_a, result.token_b, sqrt_price)?;
+    result.fee_value = try_get_value(result.fee_a, result.fee_b, sqrt_price)?;
+    result.pnl = try_get_difference(result.final_value.into(), result.initial_value.into())?;
+    result.impermanent_loss = try_get_difference(
+        result.hold_value.into(),
+        i128::from(result.final_value) - i128::from(result.fee_value),
+    )?;
+    Ok(result)
+}
+
+/// Generate a price path that follows a geometric brownian motion.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+///
+/// The same seed always results in the same path.
+///
+/// # Parameters
+/// - `start_tick_index`: The tick index of the first snapshot
+/// - `params`: The number and interval of the snapshots and the drift and volatility of the log
+///   price over `params.period` seconds
+/// - `seed`: The seed of the random number generator
+///
+/// # Returns
+/// - `PriceHistory`: The generated price snapshots
+#[cfg(feature = "floats")]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn generate_price_path(
+    start_tick_index: i32,
+    params: PricePathParams,
+    seed: u64,
+) -> Result<PriceHistory, ErrorCode> {
+    try_generate_price_path(start_tick_index, params, &mut SplitMix64(seed))
+}
+
+/// Simulate a liquidity providing strategy along many generated price paths.
+/// IMPORTANT: floating point operations can reduce the precision of the result.
+///
+/// Every path starts at the current tick index of the pool. The same seed always results in the
+/// same paths, so candidates can be compared by simulating them with the same seed.
+///
+/// # Parameters
+/// - `pool`: The whirlpool and its tick arrays
+/// - `candidate`: The tick range relative to the current tick index and the rebalance policy
+/// - `params`: The parameters of the generated price paths
+/// - `token_max_a`: The amount of token A to provide
+/// - `token_max_b`: The amount of token B to provide
+/// - `path_count`: The number of price paths to simulate
+/// - `seed`: The seed of the random number generator
+///
+/// # Returns
+/// - `StrategyResults`: The result of every price path
+#[cfg(feature = "floats")]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub fn monte_carlo_strategy(
+    pool: StrategyPool,
+    candidate: StrategyCandidate,
+    params: PricePathParams,
+    token_max_a: u64,
+    token_max_b: u64,
+    path_count: u32,
+    seed: u64,
+) -> Result<StrategyResults, ErrorCode> {
+    let mut rng = SplitMix64(seed);
+    let results = (0..path_count)
+        .map(|_| {
+            let path =
+                try_generate_price_path(pool.whirlpool.tick_current_index, params, &mut rng)?;
+            simulate_strategy(pool.clone(), path, candidate, token_max_a, token_max_b)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(StrategyResults { results })
+}
+
+// Private functions
+
+fn is_in_range(simulator: &WhirlpoolSimulator, result: &StrategyResult) -> bool {
+    (result.tick_lower_index..result.tick_upper_index)
+        .contains(&simulator.whirlpool().tick_current_index)
+}
+
+// Opens a position around the current tick index with as much of the held tokens as possible
+fn try_open_position(
+    simulator: &mut WhirlpoolSimulator,
+    candidate: StrategyCandidate,
+    result: &mut StrategyResult,
+    timestamp: u64,
+) -> Result<u32, ErrorCode> {
+    let whirlpool = simulator.whirlpool();
+    let full_range = get_full_range_tick_indexes(whirlpool.tick_spacing);
+    let tick_index = |offset: i32| {
+        get_initializable_tick_index(
+            whirlpool.tick_current_index + offset,
+            whirlpool.tick_spacing,
+            None,
+        )
+        .clamp(full_range.tick_lower_index, full_range.tick_upper_index)
+    };
+    let tick_range = order_tick_indexes(
+        tick_index(candidate.tick_lower_offset),
+        tick_index(candidate.tick_upper_offset),
+    );
+    let position_id =
+        simulator.open_position(tick_range.tick_lower_index, tick_range.tick_upper_index)?;
+
+    let quote = try_get_deposit_quote(
+        whirlpool.sqrt_price,
+        tick_range.tick_lower_index,
+        tick_range.tick_upper_index,
+        result.token_a,
+        result.token_b,
+    )?;
+    if quote.liquidity_delta > 0 {
+        let (token_a, token_b) =
+            simulator.increase_liquidity(position_id, quote.liquidity_delta, timestamp)?;
+        let checked_sub = |a: u64, b: u64| a.checked_sub(b).ok_or(ARITHMETIC_OVERFLOW);
+        result.token_a = checked_sub(result.token_a, token_a)?;
+        result.token_b = checked_sub(result.token_b, token_b)?;
+    }
+    result.tick_lower_index = tick_range.tick_lower_index;
+    result.tick_upper_index = tick_range.tick_upper_index;
+    Ok(position_id)
+}
+
+// Withdraws the liquidity, collects the fees and closes a position
+fn try_close_position(
+    simulator: &mut WhirlpoolSimulator,
+    position_id: u32,
+    result: &mut StrategyResult,
+    timestamp: u64,
+) -> Result<(), ErrorCode> {
+    let liquidity = simulator
+        .position(position_id)
+        .map_or(0, |position| position.liquidity);
+    let (token_a, token_b) = if liquidity > 0 {
+        simulator.decrease_liquidity(position_id, liquidity, timestamp)?
+    } else {
+        (0, 0)
+    };
+    let (fee_a, fee_b) = simulator.collect_fees(position_id)?;
+    simulator.close_position(position_id)?;
+
+    let checked_add = |a: u64, b: u64| a.checked_add(b).ok_or(ARITHMETIC_OVERFLOW);
+    result.fee_a = checked_add(result.fee_a, fee_a)?;
+    result.fee_b = checked_add(result.fee_b, fee_b)?;
+    result.token_a = checked_add(result.token_a, checked_add(token_a, fee_a)?)?;
+    result.token_b = checked_add(result.token_b, checked_add(token_b, fee_b)?)?;
+    Ok(())
+}
+
+// The deposit with the most liquidity that doesn't exceed either of the token amounts
+fn try_get_deposit_quote(
+    sqrt_price: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    token_a: u64,
+    token_b: u64,
+) -> Result<IncreaseLiquidityQuote, ErrorCode> {
+    let quote_a = increase_liquidity_quote_a(
+        token_a,
+        0,
+        sqrt_price.into(),
+        tick_lower_index,
+        tick_upper_index,
+        None,
+        None,
+    )?;
+    let quote_b = increase_liquidity_quote_b(
+        token_b,
+        0,
+        sqrt_price.into(),
+        tick_lower_index,
+        tick_upper_index,
+        None,
+        None,
+    )?;
+    Ok([quote_a, quote_b]
+        .into_iter()
+        .filter(|quote| quote.token_est_a <= token_a && quote.token_est_b <= token_b)
+        .max_by_key(|quote| quote.liquidity_delta)
+        .unwrap_or_default())
+}
+
+// The value of token amounts in token B, rounded down
+fn try_get_value(token_a: u64, token_b: u64, sqrt_price: u128) -> Result<u64, ErrorCode> {
+    let value_a: U256 = <U256>::from(token_a)
+        .checked_mul(<U256>::from(sqrt_price))
+        .ok_or(AMOUNT_EXCEEDS_MAX_U64)?
+        >> 64;
+    let value_a: U256 = value_a
+        .checked_mul(<U256>::from(sqrt_price))
+        .ok_or(AMOUNT_EXCEEDS_MAX_U64)?
+        >> 64;
+    let value_a: u64 = value_a.try_into().map_err(|_| AMOUNT_EXCEEDS_MAX_U64)?;
+    value_a.checked_add(token_b).ok_or(AMOUNT_EXCEEDS_MAX_U64)
+}
+
+fn try_get_difference(value: i128, other_value: i128) -> Result<i64, ErrorCode> {
+    (value - other_value)
+        .try_into()
+        .map_err(|_| ARITHMETIC_OVERFLOW)
+}
+
+#[cfg(feature = "floats")]
+fn try_generate_price_path(
+    start_tick_index: i32,
+    params: PricePathParams,
+    rng: &mut SplitMix64,
+) -> Result<PriceHistory, ErrorCode> {
+    if params.interval == 0 || params.period == 0 {
+        return Err(INVALID_TIMESTAMP);
+    }
+    let log_tick_base = log(1.0001);
+    let dt = params.interval as f64 / params.period as f64;
+    let tick_drift =
+        (params.drift - params.volatility * params.volatility / 2.0) * dt / log_tick_base;
+    let tick_volatility = params.volatility * sqrt(dt) / log_tick_base;
+
+    let mut tick_index = start_tick_index as f64;
+    let mut timestamp = params.start_timestamp;
+    let mut snapshots = Vec::with_capacity(params.steps as usize + 1);
+    snapshots.push(PriceSnapshot {
+        timestamp,
+        tick_index: start_tick_index,
+    });
+    for _ in 0..params.steps {
+        tick_index += tick_drift + tick_volatility * rng.next_normal();
+        timestamp = timestamp
+            .checked_add(params.interval)
+            .ok_or(ARITHMETIC_OVERFLOW)?;
+        snapshots.push(PriceSnapshot {
+            timestamp,
+            tick_index: (round(tick_index) as i32).clamp(MIN_TICK_INDEX, MAX_TICK_INDEX),
+        });
+    }
+    Ok(PriceHistory { snapshots })
+}
+
+// A small deterministic random number generator, see https://prng.di.unimi.it/splitmix64.c
+#[cfg(feature = "floats")]
+struct SplitMix64(u64);
+
+#[cfg(feature = "floats")]
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    // A uniform sample in (0, 1]
+    fn next_uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    // A standard normal sample using the Box-Muller transform
+    fn next_normal(&mut self) -> f64 {
+        let u1 = self.next_uniform();
+        let u2 = self.next_uniform();
+        sqrt(-2.0 * log(u1)) * cos(2.0 * core::f64::consts::PI * u2)
+    }
+}
+
+#[cfg(all(test, not(feature = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{PriceSnapshot, WhirlpoolFacade, INVALID_TICK_INDEX};
+
+    const LIQUIDITY: u128 = 1000000000000;
+
+    fn test_pool() -> StrategyPool {
+        StrategyPool {
+            whirlpool: WhirlpoolFacade {
+                tick_spacing: 2,
+                fee_rate: 3000,
+                liquidity: LIQUIDITY,
+                sqrt_price: 1 << 64,
+                ..WhirlpoolFacade::default()
+            },
+            tick_arrays: vec![],
+        }
+    }
+
+    fn test_path(ticks: &[i32]) -> PriceHistory {
+        PriceHistory {
+            snapshots: ticks
+                .iter()
+                .enumerate()
+                .map(|(i, &tick_index)| PriceSnapshot {
+                    timestamp: i as u64 * 10,
+                    tick_index,
+                })
+                .collect(),
+        }
+    }
+
+    fn test_candidate(rebalance_policy: RebalancePolicy) -> StrategyCandidate {
+        StrategyCandidate {
+            tick_lower_offset: -100,
+            tick_upper_offset: 100,
+            rebalance_policy,
+        }
+    }
+
+    #[test]
+    fn test_constant_price() {
+        let result = simulate_strategy(
+            test_pool(),
+            test_path(&[0, 0, 0]),
+            test_candidate(RebalancePolicy::Never),
+            1000000,
+            1000000,
+        )
+        .unwrap();
+        assert_eq!(result.tick_lower_index, -100);
+        assert_eq!(result.tick_upper_index, 100);
+        assert_eq!(result.fee_a, 0);
+        assert_eq!(result.fee_b, 0);
+        assert_eq!(result.time_in_range, 20);
+        assert_eq!(result.rebalance_count, 0);
+        assert_eq!(result.hold_value, result.initial_value);
+        // Depositing and withdrawing rounds in favor of the pool
+        assert!(result.pnl <= 0 && result.pnl > -4);
+    }
+
+    #[test]
+    fn test_price_moves_in_range() {
+        let result = simulate_strategy(
+            test_pool(),
+            test_path(&[0, 50, -50, 0]),
+            test_candidate(RebalancePolicy::Never),
+            1000000,
+            1000000,
+        )
+        .unwrap();
+        assert!(result.fee_a > 0);
+        assert!(result.fee_b > 0);
+        assert_eq!(result.time_in_range, 30);
+        assert_eq!(result.hold_value, result.initial_value);
+        assert!(result.pnl > 0);
+        assert_eq!(
+            result.final_value,
+            try_get_value(result.token_a, result.token_b, 1 << 64).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_value_overflow() {
+        assert_eq!(try_get_value(1000, 1000, 1 << 64), Ok(2000));
+        assert_eq!(
+            try_get_value(u64::MAX, 0, crate::MAX_SQRT_PRICE),
+            Err(AMOUNT_EXCEEDS_MAX_U64)
+        );
+        assert_eq!(
+            try_get_value(0, u64::MAX, crate::MAX_SQRT_PRICE),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            try_get_value(1, u64::MAX, 1 << 64),
+            Err(AMOUNT_EXCEEDS_MAX_U64)
+        );
+    }
+
+    #[test]
+    fn test_difference_overflow() {
+        assert_eq!(try_get_difference(1000, 3000), Ok(-2000));
+        assert_eq!(
+            try_get_difference(0, u64::MAX.into()),
+            Err(ARITHMETIC_OVERFLOW)
+        );
+        assert_eq!(
+            try_get_difference(u64::MAX.into(), 0),
+            Err(ARITHMETIC_OVERFLOW)
+        );
+    }
+
+    #[test]
+    fn test_price_leaves_range() {
+        let result = simulate_strategy(
+            test_pool(),
+            test_path(&[0, 50, 200, 250]),
+            test_candidate(RebalancePolicy::Never),
+            1000000,
+            1000000,
+        )
+        .unwrap();
+        assert_eq!(result.time_in_range, 20);
+        assert_eq!(result.rebalance_count, 0);
+        assert!(result.impermanent_loss > 0);
+        assert_eq!(result.tick_lower_index, -100);
+
+        let result = simulate_strategy(
+            test_pool(),
+            test_path(&[0, 50, 200, 250]),
+            test_candidate(RebalancePolicy::WhenOutOfRange),
+            1000000,
+            1000000,
+        )
+        .unwrap();
+        assert_eq!(result.time_in_range, 30);
+        assert_eq!(result.rebalance_count, 1);
+        assert_eq!(result.tick_lower_index, 100);
+        assert_eq!(result.tick_upper_index, 300);
+    }
+
+    #[test]
+    fn test_invalid_candidate() {
+        let candidate = StrategyCandidate {
+            tick_lower_offset: 0,
+            tick_upper_offset: 0,
+            rebalance_policy: RebalancePolicy::Never,
+        };
+        let result = simulate_strategy(test_pool(), test_path(&[0, 0]), candidate, 1000, 1000);
+        assert_eq!(result, Err(INVALID_TICK_INDEX));
+    }
+
+    #[test]
+    #[cfg(feature = "floats")]
+    fn test_generate_price_path() {
+        let params = PricePathParams {
+            start_timestamp: 100,
+            interval: 60,
+            steps: 100,
+            drift: 0.0,
+            volatility: 0.5,
+            period: 31536000,
+        };
+        let path = generate_price_path(1000, params, 42).unwrap();
+        assert_eq!(path.snapshots.len(), 101);
+        assert_eq!(path.snapshots[0].tick_index, 1000);
+        assert_eq!(path.snapshots[100].timestamp, 6100);
+        assert_eq!(path, generate_price_path(1000, params, 42).unwrap());
+        assert_ne!(path, generate_price_path(1000, params, 43).unwrap());
+
+        let params = PricePathParams {
+            volatility: 0.0,
+            ..params
+        };
+        let path = generate_price_path(1000, params, 42).unwrap();
+        assert!(path
+            .snapshots
+            .iter()
+            .all(|snapshot| snapshot.tick_index == 1000));
+
+        let params = PricePathParams {
+            interval: 0,
+            ..params
+        };
+        assert_eq!(
+            generate_price_path(1000, params, 42),
+            Err(INVALID_TIMESTAMP)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "floats")]
+    fn test_monte_carlo_strategy() {
+        let params = PricePathParams {
+            start_timestamp: 0,
+            interval: 3600,
+            steps: 24,
+            drift: 0.0,
+            volatility: 1.0,
+            period: 31536000,
+        };
+        let candidate = test_candidate(RebalancePolicy::WhenOutOfRange);
+        let results =
+            monte_carlo_strategy(test_pool(), candidate, params, 1000000, 1000000, 5, 7).unwrap();
+        assert_eq!(results.results.len(), 5);
+        assert_eq!(
+            results,
+            monte_carlo_strategy(test_pool(), candidate, params, 1000000, 1000000, 5, 7).unwrap()
+        );
+        for result in results.results {
+            assert!(result.time_in_range <= 24 * 3600);
+        }
+    }
+}
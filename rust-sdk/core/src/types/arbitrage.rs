@@ -0,0 +1,29 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct ArbitrageHop {
+    pub whirlpool: [u8; 32],
+    pub a_to_b: bool,
+    pub token_est_in: u64,
+    pub token_est_out: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct ArbitrageOpportunity {
+    pub token_mint: [u8; 32],
+    pub hops: Vec<ArbitrageHop>,
+    pub token_in: u64,
+    pub token_out: u64,
+    pub profit: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct ArbitrageOpportunities {
+    pub opportunities: Vec<ArbitrageOpportunity>,
+}
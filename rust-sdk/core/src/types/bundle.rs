@@ -0,0 +1,11 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PositionBundleDiff {
+    pub opened_positions: Vec<u32>,
+    pub closed_positions: Vec<u32>,
+}
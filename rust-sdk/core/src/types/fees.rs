@@ -3,9 +3,33 @@
 #[cfg(feature = "wasm")]
 use orca_whirlpools_macros::wasm_expose;
 
+use crate::{PendingSwap, PositionFacade, TickFacade, WhirlpoolFacade};
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
 pub struct CollectFeesQuote {
     pub fee_owed_a: u64,
     pub fee_owed_b: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct CollectFeesPosition {
+    pub position: PositionFacade,
+    pub tick_lower: TickFacade,
+    pub tick_upper: TickFacade,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct CollectFeesSimulation {
+    pub swaps: Vec<PendingSwap>,
+    pub positions: Vec<CollectFeesPosition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct CollectFeesSimulationQuote {
+    pub whirlpool: WhirlpoolFacade,
+    pub fees: Vec<CollectFeesQuote>,
+}
@@ -5,7 +5,6 @@ use orca_whirlpools_macros::wasm_expose;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
-
 pub struct DecreaseLiquidityQuote {
     pub liquidity_delta: u128,
     pub token_est_a: u64,
@@ -16,7 +15,6 @@ pub struct DecreaseLiquidityQuote {
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
-
 pub struct IncreaseLiquidityQuote {
     pub liquidity_delta: u128,
     pub token_est_a: u64,
@@ -24,3 +22,21 @@ pub struct IncreaseLiquidityQuote {
     pub token_max_a: u64,
     pub token_max_b: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct InitializePoolQuote {
+    pub initial_sqrt_price: u128,
+    pub liquidity_delta: u128,
+    pub token_est_a: u64,
+    pub token_est_b: u64,
+    pub token_max_a: u64,
+    pub token_max_b: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PoolReserves {
+    pub token_a: u64,
+    pub token_b: u64,
+}
@@ -1,26 +1,38 @@
+mod arbitrage;
+mod bundle;
 mod fees;
 mod liquidity;
 mod pool;
 mod position;
+mod replay;
 mod rewards;
+mod route;
+mod strategy;
 mod swap;
 mod tick;
 mod tick_array;
 mod token;
+mod twap;
 mod u128;
 
 #[cfg(feature = "wasm")]
 mod u64;
 
+pub use arbitrage::*;
+pub use bundle::*;
 pub use fees::*;
 pub use liquidity::*;
 pub use pool::*;
 pub use position::*;
+pub use replay::*;
 pub use rewards::*;
+pub use route::*;
+pub use strategy::*;
 pub use swap::*;
 pub use tick::*;
 pub use tick_array::*;
 pub use token::*;
+pub use twap::*;
 pub use u128::*;
 
 #[cfg(feature = "wasm")]
@@ -3,7 +3,7 @@
 #[cfg(feature = "wasm")]
 use orca_whirlpools_macros::wasm_expose;
 
-use crate::NUM_REWARDS;
+use crate::{CollectRewardQuote, NUM_REWARDS};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
@@ -41,3 +41,36 @@ pub struct PositionRewardInfoFacade {
     pub growth_inside_checkpoint: u128,
     pub amount_owed: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub enum PositionTokenType {
+    Token,
+    TokenWithMetadata,
+    Token2022,
+    Token2022WithMetadata,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct OpenPositionCost {
+    pub tick_array_start_indexes: Vec<i32>,
+    pub tick_array_rent: u64,
+    pub position_rent: u64,
+    pub position_mint_rent: u64,
+    pub position_token_account_rent: u64,
+    pub metadata_rent: u64,
+    pub total_rent: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct ClosePositionQuote {
+    pub liquidity_delta: u128,
+    pub token_est_a: u64,
+    pub token_est_b: u64,
+    pub token_min_a: u64,
+    pub token_min_b: u64,
+    #[cfg_attr(feature = "wasm", tsify(type = "CollectRewardQuote[]"))]
+    pub rewards: [CollectRewardQuote; NUM_REWARDS],
+}
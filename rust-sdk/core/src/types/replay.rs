@@ -0,0 +1,48 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{ErrorCode, TickArrayFacade, TransferFee, WhirlpoolFacade};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct HistoricalSwap {
+    pub slot: u64,
+    pub timestamp: u64,
+    pub amount: u64,
+    pub amount_specified_is_input: bool,
+    pub a_to_b: bool,
+    pub sqrt_price_limit: u128,
+    pub observed_sqrt_price: u128,
+    pub observed_tick_current_index: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct SwapReplay {
+    pub whirlpool: WhirlpoolFacade,
+    pub tick_arrays: Vec<TickArrayFacade>,
+    pub swaps: Vec<HistoricalSwap>,
+    pub transfer_fee_a: Option<TransferFee>,
+    pub transfer_fee_b: Option<TransferFee>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct SwapDivergence {
+    pub index: u32,
+    pub slot: u64,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub observed_sqrt_price: u128,
+    pub observed_tick_current_index: i32,
+    pub error: Option<ErrorCode>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct SwapReplayReport {
+    pub whirlpool: WhirlpoolFacade,
+    pub divergences: Vec<SwapDivergence>,
+}
@@ -0,0 +1,46 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{TickArrayFacade, TransferFee, WhirlpoolFacade};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct RoutePool {
+    pub address: [u8; 32],
+    pub token_mint_a: [u8; 32],
+    pub token_mint_b: [u8; 32],
+    pub whirlpool: WhirlpoolFacade,
+    pub tick_arrays: Vec<TickArrayFacade>,
+    pub transfer_fee_a: Option<TransferFee>,
+    pub transfer_fee_b: Option<TransferFee>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct RouteGraph {
+    pub pools: Vec<RoutePool>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct RouteLeg {
+    pub whirlpool_one: [u8; 32],
+    pub whirlpool_two: Option<[u8; 32]>,
+    pub a_to_b_one: bool,
+    pub a_to_b_two: bool,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub amount_specified_is_input: bool,
+    pub token_est_in: u64,
+    pub token_est_out: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct SwapRoute {
+    pub legs: Vec<RouteLeg>,
+    pub token_est_in: u64,
+    pub token_est_out: u64,
+}
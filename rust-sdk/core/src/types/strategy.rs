@@ -0,0 +1,66 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::{TickArrayFacade, WhirlpoolFacade};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub enum RebalancePolicy {
+    Never,
+    WhenOutOfRange,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct StrategyCandidate {
+    pub tick_lower_offset: i32,
+    pub tick_upper_offset: i32,
+    pub rebalance_policy: RebalancePolicy,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct StrategyPool {
+    pub whirlpool: WhirlpoolFacade,
+    pub tick_arrays: Vec<TickArrayFacade>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct StrategyResult {
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub token_a: u64,
+    pub token_b: u64,
+    pub fee_a: u64,
+    pub fee_b: u64,
+    pub initial_value: u64,
+    pub hold_value: u64,
+    pub final_value: u64,
+    pub fee_value: u64,
+    pub pnl: i64,
+    pub impermanent_loss: i64,
+    pub time_in_range: u64,
+    pub rebalance_count: u32,
+}
+
+#[cfg(feature = "floats")]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PricePathParams {
+    pub start_timestamp: u64,
+    pub interval: u64,
+    pub steps: u32,
+    pub drift: f64,
+    pub volatility: f64,
+    pub period: u64,
+}
+
+#[cfg(feature = "floats")]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct StrategyResults {
+    pub results: Vec<StrategyResult>,
+}
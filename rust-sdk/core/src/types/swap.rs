@@ -5,7 +5,6 @@ use orca_whirlpools_macros::wasm_expose;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
-
 pub struct ExactInSwapQuote {
     pub token_in: u64,
     pub token_est_out: u64,
@@ -15,10 +14,26 @@ pub struct ExactInSwapQuote {
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "wasm", wasm_expose)]
-
 pub struct ExactOutSwapQuote {
     pub token_out: u64,
     pub token_est_in: u64,
     pub token_max_in: u64,
     pub trade_fee: u64,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PendingSwap {
+    pub amount: u64,
+    pub amount_specified_is_input: bool,
+    pub a_to_b: bool,
+    pub sqrt_price_limit: u128,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct SimulatedSwap {
+    pub token_in: u64,
+    pub token_out: u64,
+    pub trade_fee: u64,
+}
@@ -0,0 +1,28 @@
+#![allow(non_snake_case)]
+
+#[cfg(feature = "wasm")]
+use orca_whirlpools_macros::wasm_expose;
+
+use crate::WhirlpoolFacade;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PriceSnapshot {
+    pub timestamp: u64,
+    pub tick_index: i32,
+}
+
+impl PriceSnapshot {
+    pub fn from_whirlpool(timestamp: u64, whirlpool: WhirlpoolFacade) -> Self {
+        Self {
+            timestamp,
+            tick_index: whirlpool.tick_current_index,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "wasm", wasm_expose)]
+pub struct PriceHistory {
+    pub snapshots: Vec<PriceSnapshot>,
+}
@@ -53,6 +53,6 @@ impl From<u128> for U128 {
 #[cfg(feature = "wasm")]
 impl PartialEq<u128> for U128 {
     fn eq(&self, other: &u128) -> bool {
-        self == &(*other).into()
+        **self == *other
     }
 }
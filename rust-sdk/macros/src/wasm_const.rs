@@ -67,10 +67,11 @@ pub fn wasm_const_impl(item: ItemConst, _attr: Nothing) -> Result<TokenStream> {
                 ))
             }
         },
+        Expr::Binary(binary) => Ok(quote! { #binary }),
         _ => {
             return Err(syn::Error::new_spanned(
                 &item.expr,
-                "Expected a literal or array",
+                "Expected a literal, array or binary expression",
             ))
         }
     }?;
@@ -186,4 +187,16 @@ pub mod tests {
         let output = result.unwrap().to_string();
         assert_eq!(output, "# [:: wasm_bindgen :: prelude :: wasm_bindgen (skip_jsdoc)] pub fn _BOOLS () -> [bool ; 2] { [true , false] } # [existing_attr] pub const BOOLS : [bool ; 2] = [true , false] ;");
     }
+
+    #[test]
+    fn test_binary() {
+        let item: ItemConst = parse_quote! {
+            #[existing_attr]
+            pub const SUM_TS: usize = BASE_TS + 4;
+        };
+        let attr = Nothing {};
+        let result = wasm_const_impl(item, attr);
+        let output = result.unwrap().to_string();
+        assert_eq!(output, "# [:: wasm_bindgen :: prelude :: wasm_bindgen (skip_jsdoc)] pub fn _SUM_TS () -> usize { BASE_TS + 4 } # [existing_attr] pub const SUM_TS : usize = BASE_TS + 4 ;");
+    }
 }
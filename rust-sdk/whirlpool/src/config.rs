@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::sync::Mutex;
+
+use solana_program::pubkey::Pubkey;
+
+/// The default (null) address.
+pub const DEFAULT_ADDRESS: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// The tick spacing for the Splash pools.
+pub const SPLASH_POOL_TICK_SPACING: u16 = 32896;
+
+/// The default funder for transactions. No explicit funder specified.
+pub const DEFAULT_FUNDER: Pubkey = DEFAULT_ADDRESS;
+
+/// The currently selected funder for transactions.
+pub static FUNDER: Mutex<Pubkey> = Mutex::new(DEFAULT_FUNDER);
+
+/// Sets the default funder for transactions.
+///
+/// # Parameters
+/// - `funder` - The funder to be set as default
+pub fn set_funder(funder: Pubkey) {
+    *FUNDER.lock().unwrap() = funder;
+}
+
+/// Resolves the funder of a transaction, falling back to the default funder.
+///
+/// # Parameters
+/// - `funder` - The explicitly supplied funder, if any
+///
+/// # Returns
+/// - The funder, or an error if neither a funder is supplied nor a default funder is set
+pub(crate) fn get_funder(funder: Option<Pubkey>) -> Result<Pubkey, Box<dyn Error>> {
+    let funder = match funder {
+        Some(funder) => funder,
+        None => *FUNDER.lock().map_err(|_| "Funder is not available")?,
+    };
+    if funder == DEFAULT_ADDRESS {
+        return Err("Either supply a funder or set the default funder".into());
+    }
+    Ok(funder)
+}
+
+/// The default slippage tolerance, expressed in basis points. Value of 100 is equivalent to 1%.
+pub const DEFAULT_SLIPPAGE_TOLERANCE_BPS: u16 = 100;
+
+/// The currently selected slippage tolerance, expressed in basis points. Value of 100 is equivalent to 1%.
+pub static SLIPPAGE_TOLERANCE_BPS: Mutex<u16> = Mutex::new(DEFAULT_SLIPPAGE_TOLERANCE_BPS);
+
+/// Sets the default slippage tolerance for transactions.
+///
+/// # Parameters
+/// - `slippage_tolerance_bps` - The slippage tolerance, expressed in basis points
+pub fn set_slippage_tolerance_bps(slippage_tolerance_bps: u16) {
+    *SLIPPAGE_TOLERANCE_BPS.lock().unwrap() = slippage_tolerance_bps;
+}
+
+/// Resolves the slippage tolerance of a transaction, falling back to the default slippage
+/// tolerance.
+///
+/// # Parameters
+/// - `slippage_tolerance_bps` - The explicitly supplied slippage tolerance, if any
+///
+/// # Returns
+/// - The slippage tolerance, expressed in basis points
+pub(crate) fn get_slippage_tolerance_bps(
+    slippage_tolerance_bps: Option<u16>,
+) -> Result<u16, Box<dyn Error>> {
+    match slippage_tolerance_bps {
+        Some(slippage_tolerance_bps) => Ok(slippage_tolerance_bps),
+        None => Ok(*SLIPPAGE_TOLERANCE_BPS
+            .lock()
+            .map_err(|_| "Slippage tolerance is not available")?),
+    }
+}
+
+/// Defines the strategy for handling Native Mint wrapping in a transaction.
+///
+/// - **Keypair**:
+///   Creates an auxiliary token account using a keypair.
+///   Optionally adds funds to the account.
+///   Closes it at the end of the transaction.
+///
+/// - **Seed**:
+///   Functions similarly to Keypair, but uses a seed account instead.
+///
+/// - **Ata**:
+///   Treats the native balance and associated token account (ATA) for `NATIVE_MINT` as one.
+///   Will create the ATA if it doesn't exist.
+///   Optionally adds funds to the account.
+///   Closes it at the end of the transaction if it did not exist before.
+///
+/// - **None**:
+///   Uses or creates the ATA without performing any Native Mint wrapping or unwrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NativeMintWrappingStrategy {
+    Keypair,
+    Seed,
+    Ata,
+    None,
+}
+
+/// The default native mint wrapping strategy.
+pub const DEFAULT_NATIVE_MINT_WRAPPING_STRATEGY: NativeMintWrappingStrategy =
+    NativeMintWrappingStrategy::Keypair;
+
+/// The currently selected native mint wrapping strategy.
+pub static NATIVE_MINT_WRAPPING_STRATEGY: Mutex<NativeMintWrappingStrategy> =
+    Mutex::new(DEFAULT_NATIVE_MINT_WRAPPING_STRATEGY);
+
+/// Sets the native mint wrapping strategy.
+///
+/// # Parameters
+/// - `strategy` - The native mint wrapping strategy
+pub fn set_native_mint_wrapping_strategy(strategy: NativeMintWrappingStrategy) {
+    *NATIVE_MINT_WRAPPING_STRATEGY.lock().unwrap() = strategy;
+}
+
+/// Resolves the native mint wrapping strategy of a transaction from the configuration.
+///
+/// # Returns
+/// - The currently selected native mint wrapping strategy
+pub(crate) fn get_native_mint_wrapping_strategy(
+) -> Result<NativeMintWrappingStrategy, Box<dyn Error>> {
+    Ok(*NATIVE_MINT_WRAPPING_STRATEGY
+        .lock()
+        .map_err(|_| "Native mint wrapping strategy is not available")?)
+}
+
+/// Resets the configuration to its default state.
+pub fn reset_configuration() {
+    set_funder(DEFAULT_FUNDER);
+    set_slippage_tolerance_bps(DEFAULT_SLIPPAGE_TOLERANCE_BPS);
+    set_native_mint_wrapping_strategy(DEFAULT_NATIVE_MINT_WRAPPING_STRATEGY);
+}
@@ -0,0 +1,450 @@
+use std::error::Error;
+
+use orca_whirlpools_client::instructions::{
+    CloseBundledPosition, CloseBundledPositionInstructionArgs, ClosePosition,
+    ClosePositionWithTokenExtensions,
+};
+use orca_whirlpools_client::{get_position_bundle_address, AccountFetcher};
+use orca_whirlpools_core::{
+    decrease_liquidity_quote, decrease_liquidity_quote_a, decrease_liquidity_quote_b,
+    CollectFeesQuote, CollectRewardsQuote, DecreaseLiquidityQuote, TransferFee,
+};
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use crate::config::{get_funder, get_native_mint_wrapping_strategy, get_slippage_tolerance_bps};
+use crate::harvest::FeesAndRewards;
+use crate::mint::get_current_transfer_fee;
+use crate::position::{fetch_position_info, PositionInfo, PositionKind};
+use crate::token::{get_sysvar, prepare_token_accounts_instructions, TokenAccountStrategy};
+
+/// The amount of liquidity or tokens to withdraw from a position. The other two are computed
+/// from the one that is specified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecreaseLiquidityParam {
+    Liquidity(u128),
+    TokenA(u64),
+    TokenB(u64),
+}
+
+/// The instructions and quote for withdrawing liquidity from a position.
+#[derive(Debug)]
+pub struct DecreaseLiquidityInstructions {
+    /// The instructions needed to withdraw the liquidity.
+    pub instructions: Vec<Instruction>,
+    /// The quote of the liquidity and token amounts.
+    pub quote: DecreaseLiquidityQuote,
+    /// The keypairs of accounts created for the transaction that need to sign it.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// The instructions and quotes for closing a position.
+#[derive(Debug)]
+pub struct ClosePositionInstructions {
+    /// The instructions needed to close the position.
+    pub instructions: Vec<Instruction>,
+    /// The quote of the liquidity and token amounts that are withdrawn.
+    pub quote: DecreaseLiquidityQuote,
+    /// The fees owed to the position, in token A and token B.
+    pub fees_quote: CollectFeesQuote,
+    /// The rewards owed to the position, for each of the rewards of the whirlpool.
+    pub rewards_quote: CollectRewardsQuote,
+    /// The keypairs of accounts created for the transaction that need to sign it.
+    pub additional_signers: Vec<Keypair>,
+}
+
+fn get_decrease_liquidity_quote(
+    param: DecreaseLiquidityParam,
+    slippage_tolerance_bps: u16,
+    sqrt_price: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<DecreaseLiquidityQuote, Box<dyn Error>> {
+    let quote = match param {
+        DecreaseLiquidityParam::Liquidity(liquidity) => decrease_liquidity_quote(
+            liquidity,
+            slippage_tolerance_bps,
+            sqrt_price,
+            tick_lower_index,
+            tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        ),
+        DecreaseLiquidityParam::TokenA(amount) => decrease_liquidity_quote_a(
+            amount,
+            slippage_tolerance_bps,
+            sqrt_price,
+            tick_lower_index,
+            tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        ),
+        DecreaseLiquidityParam::TokenB(amount) => decrease_liquidity_quote_b(
+            amount,
+            slippage_tolerance_bps,
+            sqrt_price,
+            tick_lower_index,
+            tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        ),
+    };
+    quote.map_err(|code| format!("Failed to quote the liquidity, error code {}", code).into())
+}
+
+/// Generates the instructions to withdraw liquidity from a position.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `position_address` - The address of the position
+/// - `param` - The amount of liquidity or tokens to withdraw
+/// - `slippage_tolerance_bps` - The maximum acceptable slippage tolerance, in basis points.
+///   Defaults to `SLIPPAGE_TOLERANCE_BPS`
+/// - `authority` - The owner of the position token. Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions and the quote for withdrawing the liquidity
+pub fn decrease_liquidity_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    position_address: Pubkey,
+    param: DecreaseLiquidityParam,
+    slippage_tolerance_bps: Option<u16>,
+    authority: Option<Pubkey>,
+) -> Result<DecreaseLiquidityInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = get_slippage_tolerance_bps(slippage_tolerance_bps)?;
+    let authority = get_funder(authority)?;
+
+    let PositionInfo {
+        position, accounts, ..
+    } = fetch_position_info(fetcher, position_address, authority)?;
+
+    let clock: Clock = get_sysvar(fetcher)?;
+    let quote = get_decrease_liquidity_quote(
+        param,
+        slippage_tolerance_bps,
+        accounts.whirlpool.sqrt_price,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        get_current_transfer_fee(&accounts.mint_a, clock.epoch),
+        get_current_transfer_fee(&accounts.mint_b, clock.epoch),
+    )?;
+
+    let token_accounts = prepare_token_accounts_instructions(
+        fetcher,
+        authority,
+        &[
+            TokenAccountStrategy::WithoutBalance(accounts.whirlpool.token_mint_a),
+            TokenAccountStrategy::WithoutBalance(accounts.whirlpool.token_mint_b),
+        ],
+        get_native_mint_wrapping_strategy()?,
+    )?;
+
+    let mut instructions = token_accounts.create_instructions;
+    instructions.push(accounts.decrease_liquidity_instruction(
+        fetcher,
+        &quote,
+        &token_accounts.token_account_addresses,
+        authority,
+    )?);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(DecreaseLiquidityInstructions {
+        instructions,
+        quote,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+/// Generates the instructions to close a position. All liquidity is withdrawn, the fees and
+/// rewards owed to the position are collected and the position is closed, which burns the
+/// position token of a position NFT.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `position_address` - The address of the position
+/// - `slippage_tolerance_bps` - The maximum acceptable slippage tolerance, in basis points.
+///   Defaults to `SLIPPAGE_TOLERANCE_BPS`
+/// - `authority` - The owner of the position token, who receives the rent of the position.
+///   Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions and the quotes of the liquidity, fees and rewards that are withdrawn
+pub fn close_position_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    position_address: Pubkey,
+    slippage_tolerance_bps: Option<u16>,
+    authority: Option<Pubkey>,
+) -> Result<ClosePositionInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = get_slippage_tolerance_bps(slippage_tolerance_bps)?;
+    let authority = get_funder(authority)?;
+
+    let PositionInfo {
+        position,
+        kind,
+        accounts,
+    } = fetch_position_info(fetcher, position_address, authority)?;
+
+    let quote = if position.liquidity > 0 {
+        let clock: Clock = get_sysvar(fetcher)?;
+        get_decrease_liquidity_quote(
+            DecreaseLiquidityParam::Liquidity(position.liquidity),
+            slippage_tolerance_bps,
+            accounts.whirlpool.sqrt_price,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            get_current_transfer_fee(&accounts.mint_a, clock.epoch),
+            get_current_transfer_fee(&accounts.mint_b, clock.epoch),
+        )?
+    } else {
+        DecreaseLiquidityQuote::default()
+    };
+    let fees_and_rewards = FeesAndRewards::fetch(fetcher, &position, &accounts)?;
+
+    let mut token_account_spec = Vec::new();
+    if quote.liquidity_delta > 0 || fees_and_rewards.has_fees() {
+        token_account_spec.push(TokenAccountStrategy::WithoutBalance(
+            accounts.whirlpool.token_mint_a,
+        ));
+        token_account_spec.push(TokenAccountStrategy::WithoutBalance(
+            accounts.whirlpool.token_mint_b,
+        ));
+    }
+    token_account_spec.extend(fees_and_rewards.reward_token_accounts(&accounts));
+    let token_accounts = prepare_token_accounts_instructions(
+        fetcher,
+        authority,
+        &token_account_spec,
+        get_native_mint_wrapping_strategy()?,
+    )?;
+
+    let mut instructions = token_accounts.create_instructions;
+    if position.liquidity > 0 {
+        instructions.push(accounts.update_fees_and_rewards_instruction());
+    }
+    if quote.liquidity_delta > 0 {
+        instructions.push(accounts.decrease_liquidity_instruction(
+            fetcher,
+            &quote,
+            &token_accounts.token_account_addresses,
+            authority,
+        )?);
+    }
+    instructions.extend(fees_and_rewards.collect_instructions(
+        fetcher,
+        &accounts,
+        &token_accounts.token_account_addresses,
+        authority,
+    )?);
+
+    instructions.push(match kind {
+        PositionKind::Token => ClosePosition {
+            position_authority: authority,
+            receiver: authority,
+            position: position_address,
+            position_mint: position.position_mint,
+            position_token_account: accounts.position_token_account,
+            token_program: spl_token::ID,
+        }
+        .instruction(),
+        PositionKind::Token2022 => ClosePositionWithTokenExtensions {
+            position_authority: authority,
+            receiver: authority,
+            position: position_address,
+            position_mint: position.position_mint,
+            position_token_account: accounts.position_token_account,
+            token2022_program: spl_token_2022::ID,
+        }
+        .instruction(),
+        PositionKind::Bundled {
+            position_bundle_mint,
+            bundle_index,
+        } => CloseBundledPosition {
+            bundled_position: position_address,
+            position_bundle: get_position_bundle_address(&position_bundle_mint)?.0,
+            position_bundle_token_account: accounts.position_token_account,
+            position_bundle_authority: authority,
+            receiver: authority,
+        }
+        .instruction(CloseBundledPositionInstructionArgs {
+            bundle_index: bundle_index.into(),
+        }),
+    });
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(ClosePositionInstructions {
+        instructions,
+        quote,
+        fees_quote: fees_and_rewards.fees_quote,
+        rewards_quote: fees_and_rewards.rewards_quote,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_pool;
+    use orca_whirlpools_client::accounts::Position;
+    use orca_whirlpools_client::get_bundled_position_address;
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+    use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+    #[test]
+    fn test_decrease_liquidity_instructions() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token2022, 1_000_000, 0, 0);
+        let result = decrease_liquidity_instructions(
+            &pool.accounts,
+            position_address,
+            DecreaseLiquidityParam::Liquidity(500_000),
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(result.quote.liquidity_delta, 500_000);
+        assert!(result.quote.token_min_a <= result.quote.token_est_a);
+        assert_eq!(result.instructions.len(), 1);
+        let instruction = &result.instructions[0];
+        assert_eq!(instruction.accounts[5].pubkey, position_address);
+        assert_eq!(instruction.accounts[11].pubkey, pool.vault_a);
+        assert_eq!(instruction.accounts[12].pubkey, pool.vault_b);
+    }
+
+    #[test]
+    fn test_close_position_instructions() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token2022, 1_000_000, 10, 20);
+        let result = close_position_instructions(
+            &pool.accounts,
+            position_address,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(result.quote.liquidity_delta, 1_000_000);
+        assert_eq!(result.fees_quote.fee_owed_a, 10);
+        assert_eq!(result.fees_quote.fee_owed_b, 20);
+        // Update fees and rewards, decrease liquidity, collect fees and close
+        assert_eq!(result.instructions.len(), 4);
+
+        let position_mint = Position::from_bytes(&pool.accounts[&position_address].data)
+            .unwrap()
+            .position_mint;
+        let expected = ClosePositionWithTokenExtensions {
+            position_authority: pool.signer,
+            receiver: pool.signer,
+            position: position_address,
+            position_mint,
+            position_token_account: get_associated_token_address_with_program_id(
+                &pool.signer,
+                &position_mint,
+                &spl_token_2022::ID,
+            ),
+            token2022_program: spl_token_2022::ID,
+        }
+        .instruction();
+        assert_eq!(result.instructions[3], expected);
+    }
+
+    #[test]
+    fn test_close_position_instructions_reward_mint_of_pool() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token2022, 1_000_000, 10, 20);
+        pool.add_reward(position_address, 0, pool.mint_a, 300);
+        let ata_a = get_associated_token_address_with_program_id(
+            &pool.signer,
+            &pool.mint_a,
+            &spl_token::ID,
+        );
+        pool.accounts.remove(&ata_a);
+        let result = close_position_instructions(
+            &pool.accounts,
+            position_address,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(result.rewards_quote.rewards[0].rewards_owed, 300);
+        // Create the ATA of token A once, update fees and rewards, decrease liquidity, collect
+        // fees, collect the reward and close
+        assert_eq!(result.instructions.len(), 6);
+        assert_eq!(
+            result.instructions[0],
+            create_associated_token_account_idempotent(
+                &pool.signer,
+                &pool.signer,
+                &pool.mint_a,
+                &spl_token::ID
+            )
+        );
+        assert_eq!(result.instructions[4].accounts[4].pubkey, ata_a);
+    }
+
+    #[test]
+    fn test_close_empty_position_instructions() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token, 0, 0, 0);
+        let result = close_position_instructions(
+            &pool.accounts,
+            position_address,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(result.quote, DecreaseLiquidityQuote::default());
+        assert_eq!(result.instructions.len(), 1);
+        assert_eq!(result.instructions[0].accounts[5].pubkey, spl_token::ID);
+    }
+
+    #[test]
+    fn test_close_bundled_position_instructions() {
+        let mut pool = test_pool();
+        let position_bundle_mint = Pubkey::new_unique();
+        let position_address = pool.add_position(
+            PositionKind::Bundled {
+                position_bundle_mint,
+                bundle_index: 42,
+            },
+            0,
+            0,
+            0,
+        );
+        let result = close_position_instructions(
+            &pool.accounts,
+            position_address,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(
+            position_address,
+            get_bundled_position_address(&position_bundle_mint, 42)
+                .unwrap()
+                .0
+        );
+        let expected = CloseBundledPosition {
+            bundled_position: position_address,
+            position_bundle: get_position_bundle_address(&position_bundle_mint)
+                .unwrap()
+                .0,
+            position_bundle_token_account: get_associated_token_address_with_program_id(
+                &pool.signer,
+                &position_bundle_mint,
+                &spl_token::ID,
+            ),
+            position_bundle_authority: pool.signer,
+            receiver: pool.signer,
+        }
+        .instruction(CloseBundledPositionInstructionArgs { bundle_index: 42 });
+        assert_eq!(result.instructions, vec![expected]);
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use orca_whirlpools_client::accounts::Position;
+use orca_whirlpools_client::{fetch_tick_array, AccountFetcher};
+use orca_whirlpools_core::{
+    collect_fees_quote, collect_rewards_quote, get_tick_array_start_tick_index,
+    get_tick_index_in_array, CollectFeesQuote, CollectRewardsQuote, NUM_REWARDS,
+};
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use solana_sdk::signature::Keypair;
+
+use crate::config::{get_funder, get_native_mint_wrapping_strategy};
+use crate::mint::get_current_transfer_fee;
+use crate::position::{fetch_position_info, PositionAccounts, PositionInfo};
+use crate::token::{get_sysvar, prepare_token_accounts_instructions, TokenAccountStrategy};
+use crate::DEFAULT_ADDRESS;
+
+/// The instructions and quotes for harvesting a position.
+#[derive(Debug)]
+pub struct HarvestPositionInstructions {
+    /// The instructions needed to harvest the position.
+    pub instructions: Vec<Instruction>,
+    /// The fees owed to the position, in token A and token B.
+    pub fees_quote: CollectFeesQuote,
+    /// The rewards owed to the position, for each of the rewards of the whirlpool.
+    pub rewards_quote: CollectRewardsQuote,
+    /// The keypairs of accounts created for the transaction that need to sign it.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// The fees and rewards owed to a position, together with the mints of the rewards.
+#[derive(Debug)]
+pub(crate) struct FeesAndRewards {
+    pub fees_quote: CollectFeesQuote,
+    pub rewards_quote: CollectRewardsQuote,
+    reward_mint_accounts: [Option<Account>; NUM_REWARDS],
+}
+
+impl FeesAndRewards {
+    /// Quote the fees and rewards owed to a position at the current time.
+    pub fn fetch<F: AccountFetcher>(
+        fetcher: &F,
+        position: &Position,
+        accounts: &PositionAccounts,
+    ) -> Result<Self, Box<dyn Error>> {
+        let whirlpool = &accounts.whirlpool;
+        let reward_mints: Vec<Pubkey> = whirlpool
+            .reward_infos
+            .iter()
+            .map(|reward_info| reward_info.mint)
+            .collect();
+        let reward_mint_accounts: [Option<Account>; NUM_REWARDS] = fetcher
+            .get_multiple_accounts(&reward_mints)?
+            .into_iter()
+            .zip(&reward_mints)
+            .map(|(account, mint)| {
+                if *mint == DEFAULT_ADDRESS {
+                    None
+                } else {
+                    account
+                }
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| "Failed to fetch the reward mints of the whirlpool")?;
+
+        let tick_array_lower = fetch_tick_array(fetcher, &accounts.tick_array_lower)?;
+        let tick_array_upper = fetch_tick_array(fetcher, &accounts.tick_array_upper)?;
+        let tick_lower_index = get_tick_index_in_array(
+            position.tick_lower_index,
+            get_tick_array_start_tick_index(position.tick_lower_index, whirlpool.tick_spacing),
+            whirlpool.tick_spacing,
+        );
+        let tick_upper_index = get_tick_index_in_array(
+            position.tick_upper_index,
+            get_tick_array_start_tick_index(position.tick_upper_index, whirlpool.tick_spacing),
+            whirlpool.tick_spacing,
+        );
+        let tick_lower = tick_array_lower.ticks[tick_lower_index as usize];
+        let tick_upper = tick_array_upper.ticks[tick_upper_index as usize];
+
+        let clock: Clock = get_sysvar(fetcher)?;
+        let fees_quote = collect_fees_quote(
+            whirlpool.clone().into(),
+            position.clone().into(),
+            tick_lower.into(),
+            tick_upper.into(),
+            get_current_transfer_fee(&accounts.mint_a, clock.epoch),
+            get_current_transfer_fee(&accounts.mint_b, clock.epoch),
+        )
+        .map_err(|code| format!("Failed to quote the fees, error code {}", code))?;
+        let reward_transfer_fee = |index: usize| {
+            reward_mint_accounts[index]
+                .as_ref()
+                .and_then(|account| get_current_transfer_fee(account, clock.epoch))
+        };
+        // The clock of the fetcher can lag behind the last reward update of the whirlpool
+        let current_timestamp =
+            (clock.unix_timestamp.max(0) as u64).max(whirlpool.reward_last_updated_timestamp);
+        let rewards_quote = collect_rewards_quote(
+            whirlpool.clone().into(),
+            position.clone().into(),
+            tick_lower.into(),
+            tick_upper.into(),
+            current_timestamp,
+            reward_transfer_fee(0),
+            reward_transfer_fee(1),
+            reward_transfer_fee(2),
+        )
+        .map_err(|code| format!("Failed to quote the rewards, error code {}", code))?;
+
+        Ok(Self {
+            fees_quote,
+            rewards_quote,
+            reward_mint_accounts,
+        })
+    }
+
+    /// Whether any fees are owed to the position.
+    pub fn has_fees(&self) -> bool {
+        self.fees_quote.fee_owed_a > 0 || self.fees_quote.fee_owed_b > 0
+    }
+
+    /// The token accounts that receive the rewards owed to the position.
+    pub fn reward_token_accounts(&self, accounts: &PositionAccounts) -> Vec<TokenAccountStrategy> {
+        (0..NUM_REWARDS)
+            .filter(|index| self.rewards_quote.rewards[*index].rewards_owed > 0)
+            .map(|index| {
+                TokenAccountStrategy::WithoutBalance(accounts.whirlpool.reward_infos[index].mint)
+            })
+            .collect()
+    }
+
+    /// Build the instructions that collect the fees and rewards owed to the position.
+    pub fn collect_instructions<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+        accounts: &PositionAccounts,
+        token_account_addresses: &HashMap<Pubkey, Pubkey>,
+        authority: Pubkey,
+    ) -> Result<Vec<Instruction>, Box<dyn Error>> {
+        let mut instructions = Vec::new();
+        if self.has_fees() {
+            instructions.push(accounts.collect_fees_instruction(
+                fetcher,
+                token_account_addresses,
+                authority,
+            )?);
+        }
+        for index in 0..NUM_REWARDS {
+            if self.rewards_quote.rewards[index].rewards_owed == 0 {
+                continue;
+            }
+            let reward_mint_account = self.reward_mint_accounts[index]
+                .as_ref()
+                .ok_or(format!("Reward mint {} not found", index))?;
+            instructions.push(accounts.collect_reward_instruction(
+                fetcher,
+                index,
+                reward_mint_account,
+                token_account_addresses,
+                authority,
+            )?);
+        }
+        Ok(instructions)
+    }
+}
+
+/// Generates the instructions to collect the fees and rewards owed to a position. The liquidity
+/// stays in place and the position remains open.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `position_address` - The address of the position
+/// - `authority` - The owner of the position token. Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions and the quotes of the fees and rewards that are collected
+pub fn harvest_position_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    position_address: Pubkey,
+    authority: Option<Pubkey>,
+) -> Result<HarvestPositionInstructions, Box<dyn Error>> {
+    let authority = get_funder(authority)?;
+    let PositionInfo {
+        position, accounts, ..
+    } = fetch_position_info(fetcher, position_address, authority)?;
+    let fees_and_rewards = FeesAndRewards::fetch(fetcher, &position, &accounts)?;
+
+    let mut token_account_spec = Vec::new();
+    if fees_and_rewards.has_fees() {
+        token_account_spec.push(TokenAccountStrategy::WithoutBalance(
+            accounts.whirlpool.token_mint_a,
+        ));
+        token_account_spec.push(TokenAccountStrategy::WithoutBalance(
+            accounts.whirlpool.token_mint_b,
+        ));
+    }
+    token_account_spec.extend(fees_and_rewards.reward_token_accounts(&accounts));
+    let token_accounts = prepare_token_accounts_instructions(
+        fetcher,
+        authority,
+        &token_account_spec,
+        get_native_mint_wrapping_strategy()?,
+    )?;
+
+    let mut instructions = token_accounts.create_instructions;
+    if position.liquidity > 0 {
+        instructions.push(accounts.update_fees_and_rewards_instruction());
+    }
+    instructions.extend(fees_and_rewards.collect_instructions(
+        fetcher,
+        &accounts,
+        &token_accounts.token_account_addresses,
+        authority,
+    )?);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(HarvestPositionInstructions {
+        instructions,
+        fees_quote: fees_and_rewards.fees_quote,
+        rewards_quote: fees_and_rewards.rewards_quote,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_pool;
+    use crate::PositionKind;
+    use orca_whirlpools_client::get_tick_array_address;
+    use orca_whirlpools_client::instructions::UpdateFeesAndRewards;
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+    use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+    #[test]
+    fn test_harvest_position_instructions() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token2022, 1000, 500, 0);
+        let result =
+            harvest_position_instructions(&pool.accounts, position_address, Some(pool.signer))
+                .unwrap();
+
+        assert_eq!(result.fees_quote.fee_owed_a, 500);
+        assert_eq!(result.fees_quote.fee_owed_b, 0);
+        assert!(result
+            .rewards_quote
+            .rewards
+            .iter()
+            .all(|reward| reward.rewards_owed == 0));
+        assert_eq!(result.instructions.len(), 2);
+        assert_eq!(
+            result.instructions[0],
+            UpdateFeesAndRewards {
+                whirlpool: pool.whirlpool,
+                position: position_address,
+                tick_array_lower: get_tick_array_address(&pool.whirlpool, -5632).unwrap().0,
+                tick_array_upper: get_tick_array_address(&pool.whirlpool, 0).unwrap().0,
+            }
+            .instruction()
+        );
+        let collect_fees = &result.instructions[1];
+        assert_eq!(collect_fees.accounts[2].pubkey, position_address);
+        assert_eq!(collect_fees.accounts[10].pubkey, spl_token::ID);
+        assert_eq!(collect_fees.accounts[11].pubkey, spl_token_2022::ID);
+    }
+
+    #[test]
+    fn test_harvest_position_instructions_reward_mint_of_pool() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token2022, 1000, 500, 0);
+        let reward_vault = pool.add_reward(position_address, 0, pool.mint_a, 300);
+        let ata_a = get_associated_token_address_with_program_id(
+            &pool.signer,
+            &pool.mint_a,
+            &spl_token::ID,
+        );
+        pool.accounts.remove(&ata_a);
+        let result =
+            harvest_position_instructions(&pool.accounts, position_address, Some(pool.signer))
+                .unwrap();
+
+        assert_eq!(result.fees_quote.fee_owed_a, 500);
+        assert_eq!(result.rewards_quote.rewards[0].rewards_owed, 300);
+        // Create the ATA of token A once, update fees and rewards, collect fees and collect the
+        // reward into the same ATA
+        assert_eq!(result.instructions.len(), 4);
+        assert_eq!(
+            result.instructions[0],
+            create_associated_token_account_idempotent(
+                &pool.signer,
+                &pool.signer,
+                &pool.mint_a,
+                &spl_token::ID
+            )
+        );
+        let collect_reward = &result.instructions[3];
+        assert_eq!(collect_reward.accounts[4].pubkey, ata_a);
+        assert_eq!(collect_reward.accounts[5].pubkey, pool.mint_a);
+        assert_eq!(collect_reward.accounts[6].pubkey, reward_vault);
+    }
+
+    #[test]
+    fn test_harvest_position_instructions_nothing_owed() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token, 0, 0, 0);
+        let result =
+            harvest_position_instructions(&pool.accounts, position_address, Some(pool.signer))
+                .unwrap();
+
+        assert_eq!(result.fees_quote, CollectFeesQuote::default());
+        assert!(result.instructions.is_empty());
+    }
+
+    #[test]
+    fn test_harvest_position_instructions_position_not_found() {
+        let pool = test_pool();
+        let result =
+            harvest_position_instructions(&pool.accounts, Pubkey::new_unique(), Some(pool.signer));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,695 @@
+use std::error::Error;
+
+use orca_whirlpools_client::accounts::{TickArray, Whirlpool};
+use orca_whirlpools_client::instructions::{
+    InitializeTickArray, InitializeTickArrayInstructionArgs, OpenBundledPosition,
+    OpenBundledPositionInstructionArgs, OpenPosition, OpenPositionInstructionArgs,
+    OpenPositionWithTokenExtensions, OpenPositionWithTokenExtensionsInstructionArgs,
+};
+use orca_whirlpools_client::types::OpenPositionBumps;
+use orca_whirlpools_client::{
+    fetch_whirlpool, get_bundled_position_address, get_position_address,
+    get_position_bundle_address, AccountFetcher,
+};
+use orca_whirlpools_core::{
+    get_full_range_tick_indexes, get_initializable_tick_index, get_tick_array_start_tick_index,
+    increase_liquidity_quote, increase_liquidity_quote_a, increase_liquidity_quote_b,
+    order_tick_indexes, price_to_tick_index, IncreaseLiquidityQuote, TickRange, TransferFee,
+};
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{pubkey, system_program, sysvar};
+use solana_sdk::account::Account;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Mint;
+
+use crate::config::{get_funder, get_native_mint_wrapping_strategy, get_slippage_tolerance_bps};
+use crate::mint::get_current_transfer_fee;
+use crate::position::{
+    fetch_position_info, get_position_tick_array_addresses, PositionAccounts, PositionInfo,
+    PositionKind,
+};
+use crate::token::{
+    get_mint_accounts, get_sysvar, prepare_token_accounts_instructions, TokenAccountStrategy,
+};
+use crate::SPLASH_POOL_TICK_SPACING;
+
+/// The update authority of the token metadata of Token-2022 position NFTs.
+const METADATA_UPDATE_AUTH: Pubkey = pubkey!("3axbTs2z5GBy6usVbNVoqEgZMng3vZvMnAoX29BFfwhr");
+
+/// The amount of liquidity or tokens to add to a position. The other two are computed from
+/// the one that is specified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncreaseLiquidityParam {
+    Liquidity(u128),
+    TokenA(u64),
+    TokenB(u64),
+}
+
+/// The instructions and quote for adding liquidity to a position.
+#[derive(Debug)]
+pub struct IncreaseLiquidityInstructions {
+    /// The instructions needed to add the liquidity.
+    pub instructions: Vec<Instruction>,
+    /// The quote of the liquidity and token amounts.
+    pub quote: IncreaseLiquidityQuote,
+    /// The address of the position.
+    pub position_address: Pubkey,
+    /// The mint of the token that represents the position. For a bundled position this is the
+    /// mint of the position bundle.
+    pub position_mint: Pubkey,
+    /// The non-refundable rent, in lamports, of the tick arrays that are initialized.
+    pub initialization_cost: u64,
+    /// The keypairs of accounts created for the transaction that need to sign it.
+    pub additional_signers: Vec<Keypair>,
+}
+
+fn get_increase_liquidity_quote(
+    param: IncreaseLiquidityParam,
+    slippage_tolerance_bps: u16,
+    whirlpool: &Whirlpool,
+    tick_range: TickRange,
+    transfer_fee_a: Option<TransferFee>,
+    transfer_fee_b: Option<TransferFee>,
+) -> Result<IncreaseLiquidityQuote, Box<dyn Error>> {
+    let quote = match param {
+        IncreaseLiquidityParam::Liquidity(liquidity) => increase_liquidity_quote(
+            liquidity,
+            slippage_tolerance_bps,
+            whirlpool.sqrt_price,
+            tick_range.tick_lower_index,
+            tick_range.tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        ),
+        IncreaseLiquidityParam::TokenA(amount) => increase_liquidity_quote_a(
+            amount,
+            slippage_tolerance_bps,
+            whirlpool.sqrt_price,
+            tick_range.tick_lower_index,
+            tick_range.tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        ),
+        IncreaseLiquidityParam::TokenB(amount) => increase_liquidity_quote_b(
+            amount,
+            slippage_tolerance_bps,
+            whirlpool.sqrt_price,
+            tick_range.tick_lower_index,
+            tick_range.tick_upper_index,
+            transfer_fee_a,
+            transfer_fee_b,
+        ),
+    };
+    quote.map_err(|code| format!("Failed to quote the liquidity, error code {}", code).into())
+}
+
+/// Generates the instructions to add liquidity to an existing position.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `position_address` - The address of the position
+/// - `param` - The amount of liquidity or tokens to add
+/// - `slippage_tolerance_bps` - The maximum acceptable slippage tolerance, in basis points.
+///   Defaults to `SLIPPAGE_TOLERANCE_BPS`
+/// - `authority` - The owner of the position token. Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions and the quote for adding the liquidity
+pub fn increase_liquidity_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    position_address: Pubkey,
+    param: IncreaseLiquidityParam,
+    slippage_tolerance_bps: Option<u16>,
+    authority: Option<Pubkey>,
+) -> Result<IncreaseLiquidityInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = get_slippage_tolerance_bps(slippage_tolerance_bps)?;
+    let authority = get_funder(authority)?;
+
+    let PositionInfo {
+        position, accounts, ..
+    } = fetch_position_info(fetcher, position_address, authority)?;
+
+    let clock: Clock = get_sysvar(fetcher)?;
+    let quote = get_increase_liquidity_quote(
+        param,
+        slippage_tolerance_bps,
+        &accounts.whirlpool,
+        TickRange {
+            tick_lower_index: position.tick_lower_index,
+            tick_upper_index: position.tick_upper_index,
+        },
+        get_current_transfer_fee(&accounts.mint_a, clock.epoch),
+        get_current_transfer_fee(&accounts.mint_b, clock.epoch),
+    )?;
+
+    let token_accounts = prepare_token_accounts_instructions(
+        fetcher,
+        authority,
+        &[
+            TokenAccountStrategy::WithBalance(accounts.whirlpool.token_mint_a, quote.token_max_a),
+            TokenAccountStrategy::WithBalance(accounts.whirlpool.token_mint_b, quote.token_max_b),
+        ],
+        get_native_mint_wrapping_strategy()?,
+    )?;
+
+    let mut instructions = token_accounts.create_instructions;
+    instructions.push(accounts.increase_liquidity_instruction(
+        fetcher,
+        &quote,
+        &token_accounts.token_account_addresses,
+        authority,
+    )?);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(IncreaseLiquidityInstructions {
+        instructions,
+        quote,
+        position_address,
+        position_mint: position.position_mint,
+        initialization_cost: 0,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn internal_open_position_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    whirlpool_address: Pubkey,
+    whirlpool: Whirlpool,
+    mint_accounts: [Account; 2],
+    param: IncreaseLiquidityParam,
+    tick_range: TickRange,
+    position_kind: PositionKind,
+    slippage_tolerance_bps: Option<u16>,
+    funder: Option<Pubkey>,
+) -> Result<IncreaseLiquidityInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = get_slippage_tolerance_bps(slippage_tolerance_bps)?;
+    let funder = get_funder(funder)?;
+    let [mint_a, mint_b] = mint_accounts;
+
+    let lower_tick_index = get_initializable_tick_index(
+        tick_range.tick_lower_index,
+        whirlpool.tick_spacing,
+        Some(false),
+    );
+    let upper_tick_index = get_initializable_tick_index(
+        tick_range.tick_upper_index,
+        whirlpool.tick_spacing,
+        Some(true),
+    );
+    let tick_range = order_tick_indexes(lower_tick_index, upper_tick_index);
+
+    let clock: Clock = get_sysvar(fetcher)?;
+    let quote = get_increase_liquidity_quote(
+        param,
+        slippage_tolerance_bps,
+        &whirlpool,
+        tick_range,
+        get_current_transfer_fee(&mint_a, clock.epoch),
+        get_current_transfer_fee(&mint_b, clock.epoch),
+    )?;
+
+    let mut additional_signers = Vec::new();
+    let (position_address, position_mint, position_token_program) = match position_kind {
+        PositionKind::Token | PositionKind::Token2022 => {
+            let position_mint = Keypair::new();
+            let (position_address, _) = get_position_address(&position_mint.pubkey())?;
+            let token_program = if position_kind == PositionKind::Token {
+                spl_token::ID
+            } else {
+                spl_token_2022::ID
+            };
+            let position_mint_address = position_mint.pubkey();
+            additional_signers.push(position_mint);
+            (position_address, position_mint_address, token_program)
+        }
+        PositionKind::Bundled {
+            position_bundle_mint,
+            bundle_index,
+        } => {
+            let (position_address, _) =
+                get_bundled_position_address(&position_bundle_mint, bundle_index)?;
+            (position_address, position_bundle_mint, spl_token::ID)
+        }
+    };
+    let position_token_account = get_associated_token_address_with_program_id(
+        &funder,
+        &position_mint,
+        &position_token_program,
+    );
+
+    let (tick_array_lower, tick_array_upper) = get_position_tick_array_addresses(
+        &whirlpool_address,
+        whirlpool.tick_spacing,
+        tick_range.tick_lower_index,
+        tick_range.tick_upper_index,
+    )?;
+
+    let token_accounts = prepare_token_accounts_instructions(
+        fetcher,
+        funder,
+        &[
+            TokenAccountStrategy::WithBalance(whirlpool.token_mint_a, quote.token_max_a),
+            TokenAccountStrategy::WithBalance(whirlpool.token_mint_b, quote.token_max_b),
+        ],
+        get_native_mint_wrapping_strategy()?,
+    )?;
+
+    let mut instructions = token_accounts.create_instructions;
+    additional_signers.extend(token_accounts.additional_signers);
+
+    let mut tick_arrays = vec![(tick_array_lower, tick_range.tick_lower_index)];
+    if tick_array_upper != tick_array_lower {
+        tick_arrays.push((tick_array_upper, tick_range.tick_upper_index));
+    }
+    let tick_array_addresses: Vec<Pubkey> =
+        tick_arrays.iter().map(|(address, _)| *address).collect();
+    let tick_array_accounts = fetcher.get_multiple_accounts(&tick_array_addresses)?;
+    let rent: Rent = get_sysvar(fetcher)?;
+    let mut initialization_cost = 0;
+    for ((tick_array, tick_index), account) in tick_arrays.into_iter().zip(tick_array_accounts) {
+        if account.is_some() {
+            continue;
+        }
+        instructions.push(
+            InitializeTickArray {
+                whirlpool: whirlpool_address,
+                funder,
+                tick_array,
+                system_program: system_program::ID,
+            }
+            .instruction(InitializeTickArrayInstructionArgs {
+                start_tick_index: get_tick_array_start_tick_index(
+                    tick_index,
+                    whirlpool.tick_spacing,
+                ),
+            }),
+        );
+        initialization_cost += rent.minimum_balance(TickArray::LEN);
+    }
+
+    instructions.push(match position_kind {
+        PositionKind::Token => {
+            let (_, position_bump) = get_position_address(&position_mint)?;
+            OpenPosition {
+                funder,
+                owner: funder,
+                position: position_address,
+                position_mint,
+                position_token_account,
+                whirlpool: whirlpool_address,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .instruction(OpenPositionInstructionArgs {
+                bumps: OpenPositionBumps { position_bump },
+                tick_lower_index: tick_range.tick_lower_index,
+                tick_upper_index: tick_range.tick_upper_index,
+            })
+        }
+        PositionKind::Token2022 => OpenPositionWithTokenExtensions {
+            funder,
+            owner: funder,
+            position: position_address,
+            position_mint,
+            position_token_account,
+            whirlpool: whirlpool_address,
+            token2022_program: spl_token_2022::ID,
+            system_program: system_program::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            metadata_update_auth: METADATA_UPDATE_AUTH,
+        }
+        .instruction(OpenPositionWithTokenExtensionsInstructionArgs {
+            tick_lower_index: tick_range.tick_lower_index,
+            tick_upper_index: tick_range.tick_upper_index,
+            with_token_metadata_extension: true,
+        }),
+        PositionKind::Bundled {
+            position_bundle_mint,
+            bundle_index,
+        } => OpenBundledPosition {
+            bundled_position: position_address,
+            position_bundle: get_position_bundle_address(&position_bundle_mint)?.0,
+            position_bundle_token_account: position_token_account,
+            position_bundle_authority: funder,
+            whirlpool: whirlpool_address,
+            funder,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .instruction(OpenBundledPositionInstructionArgs {
+            bundle_index: bundle_index.into(),
+            tick_lower_index: tick_range.tick_lower_index,
+            tick_upper_index: tick_range.tick_upper_index,
+        }),
+    });
+
+    let accounts = PositionAccounts {
+        whirlpool_address,
+        whirlpool,
+        mint_a,
+        mint_b,
+        position_address,
+        position_token_account,
+        tick_array_lower,
+        tick_array_upper,
+    };
+    instructions.push(accounts.increase_liquidity_instruction(
+        fetcher,
+        &quote,
+        &token_accounts.token_account_addresses,
+        funder,
+    )?);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(IncreaseLiquidityInstructions {
+        instructions,
+        quote,
+        position_address,
+        position_mint,
+        initialization_cost,
+        additional_signers,
+    })
+}
+
+/// Generates the instructions to open a full-range position in a pool and add liquidity to it.
+/// This is the only kind of position that Splash Pools support.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `pool_address` - The address of the pool
+/// - `param` - The amount of liquidity or tokens to add
+/// - `position_kind` - The kind of position to open
+/// - `slippage_tolerance_bps` - The maximum acceptable slippage tolerance, in basis points.
+///   Defaults to `SLIPPAGE_TOLERANCE_BPS`
+/// - `funder` - The wallet funding the transaction and owning the position. Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions, the quote and the position that is opened
+pub fn open_full_range_position_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    pool_address: Pubkey,
+    param: IncreaseLiquidityParam,
+    position_kind: PositionKind,
+    slippage_tolerance_bps: Option<u16>,
+    funder: Option<Pubkey>,
+) -> Result<IncreaseLiquidityInstructions, Box<dyn Error>> {
+    let whirlpool = fetch_whirlpool(fetcher, &pool_address)?;
+    let tick_range = get_full_range_tick_indexes(whirlpool.tick_spacing);
+    let mint_accounts: [Account; 2] =
+        get_mint_accounts(fetcher, &[whirlpool.token_mint_a, whirlpool.token_mint_b])?
+            .try_into()
+            .map_err(|_| "Failed to fetch the mints of the pool")?;
+    internal_open_position_instructions(
+        fetcher,
+        pool_address,
+        whirlpool,
+        mint_accounts,
+        param,
+        tick_range,
+        position_kind,
+        slippage_tolerance_bps,
+        funder,
+    )
+}
+
+/// Generates the instructions to open a position in a concentrated liquidity pool within a
+/// price range and add liquidity to it. Tick arrays that the position needs and that do not
+/// exist yet are initialized as well.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `pool_address` - The address of the pool
+/// - `param` - The amount of liquidity or tokens to add
+/// - `lower_price` - The lower bound of the price range of the position
+/// - `upper_price` - The upper bound of the price range of the position
+/// - `position_kind` - The kind of position to open
+/// - `slippage_tolerance_bps` - The maximum acceptable slippage tolerance, in basis points.
+///   Defaults to `SLIPPAGE_TOLERANCE_BPS`
+/// - `funder` - The wallet funding the transaction and owning the position. Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions, the quote and the position that is opened
+#[allow(clippy::too_many_arguments)]
+pub fn open_position_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    pool_address: Pubkey,
+    param: IncreaseLiquidityParam,
+    lower_price: f64,
+    upper_price: f64,
+    position_kind: PositionKind,
+    slippage_tolerance_bps: Option<u16>,
+    funder: Option<Pubkey>,
+) -> Result<IncreaseLiquidityInstructions, Box<dyn Error>> {
+    let whirlpool = fetch_whirlpool(fetcher, &pool_address)?;
+    if whirlpool.tick_spacing == SPLASH_POOL_TICK_SPACING {
+        return Err("Splash pools only support full range positions".into());
+    }
+    let mint_accounts: [Account; 2] =
+        get_mint_accounts(fetcher, &[whirlpool.token_mint_a, whirlpool.token_mint_b])?
+            .try_into()
+            .map_err(|_| "Failed to fetch the mints of the pool")?;
+    let decimals_a = StateWithExtensions::<Mint>::unpack(&mint_accounts[0].data)?
+        .base
+        .decimals;
+    let decimals_b = StateWithExtensions::<Mint>::unpack(&mint_accounts[1].data)?
+        .base
+        .decimals;
+    let lower_tick_index = price_to_tick_index(lower_price, decimals_a, decimals_b);
+    let upper_tick_index = price_to_tick_index(upper_price, decimals_a, decimals_b);
+    let tick_range = TickRange {
+        tick_lower_index: get_initializable_tick_index(
+            lower_tick_index,
+            whirlpool.tick_spacing,
+            None,
+        ),
+        tick_upper_index: get_initializable_tick_index(
+            upper_tick_index,
+            whirlpool.tick_spacing,
+            None,
+        ),
+    };
+    internal_open_position_instructions(
+        fetcher,
+        pool_address,
+        whirlpool,
+        mint_accounts,
+        param,
+        tick_range,
+        position_kind,
+        slippage_tolerance_bps,
+        funder,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_pool;
+    use orca_whirlpools_client::get_tick_array_address;
+    use orca_whirlpools_client::test_accounts::update_account;
+
+    #[test]
+    fn test_open_position_instructions_initializes_tick_arrays() {
+        let pool = test_pool();
+        let result = open_position_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            0.9,
+            1.1,
+            PositionKind::Token2022,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        let rent = Rent::default();
+        assert_eq!(
+            result.initialization_cost,
+            rent.minimum_balance(TickArray::LEN) * 2
+        );
+        assert_eq!(result.instructions.len(), 4);
+        assert_eq!(result.additional_signers.len(), 1);
+        assert_eq!(result.additional_signers[0].pubkey(), result.position_mint);
+        assert_eq!(
+            result.position_address,
+            get_position_address(&result.position_mint).unwrap().0
+        );
+        assert!(result.quote.liquidity_delta == 1_000_000);
+
+        let (tick_array_lower, _) = get_tick_array_address(&pool.whirlpool, -5632).unwrap();
+        let (tick_array_upper, _) = get_tick_array_address(&pool.whirlpool, 0).unwrap();
+        assert_eq!(
+            result.instructions[0],
+            InitializeTickArray {
+                whirlpool: pool.whirlpool,
+                funder: pool.signer,
+                tick_array: tick_array_lower,
+                system_program: system_program::ID,
+            }
+            .instruction(InitializeTickArrayInstructionArgs {
+                start_tick_index: -5632
+            })
+        );
+        assert_eq!(result.instructions[1].accounts[2].pubkey, tick_array_upper);
+        assert_eq!(
+            result.instructions[2].accounts[6].pubkey,
+            spl_token_2022::ID
+        );
+    }
+
+    #[test]
+    fn test_open_full_range_position_instructions() {
+        let pool = test_pool();
+        let result = open_full_range_position_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            IncreaseLiquidityParam::TokenA(1000),
+            PositionKind::Token,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert!(result.quote.token_est_a <= 1000);
+        assert!(result.quote.token_max_a >= result.quote.token_est_a);
+        let open_position = &result.instructions[2];
+        let (_, position_bump) = get_position_address(&result.position_mint).unwrap();
+        let expected = OpenPosition {
+            funder: pool.signer,
+            owner: pool.signer,
+            position: result.position_address,
+            position_mint: result.position_mint,
+            position_token_account: get_associated_token_address_with_program_id(
+                &pool.signer,
+                &result.position_mint,
+                &spl_token::ID,
+            ),
+            whirlpool: pool.whirlpool,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .instruction(OpenPositionInstructionArgs {
+            bumps: OpenPositionBumps { position_bump },
+            tick_lower_index: -443584,
+            tick_upper_index: 443584,
+        });
+        assert_eq!(*open_position, expected);
+    }
+
+    #[test]
+    fn test_open_bundled_position_instructions() {
+        let mut pool = test_pool();
+        let position_bundle_mint = Pubkey::new_unique();
+        let kind = PositionKind::Bundled {
+            position_bundle_mint,
+            bundle_index: 3,
+        };
+        // The tick arrays of an existing position in the same range are reused
+        pool.add_position(kind, 0, 0, 0);
+        let result = open_position_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            IncreaseLiquidityParam::TokenB(1000),
+            0.9,
+            1.1,
+            PositionKind::Bundled {
+                position_bundle_mint,
+                bundle_index: 4,
+            },
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(result.initialization_cost, 0);
+        assert_eq!(result.instructions.len(), 2);
+        assert!(result.additional_signers.is_empty());
+        assert_eq!(result.position_mint, position_bundle_mint);
+        assert_eq!(
+            result.position_address,
+            get_bundled_position_address(&position_bundle_mint, 4)
+                .unwrap()
+                .0
+        );
+        assert_eq!(
+            result.instructions[0].accounts[0].pubkey,
+            result.position_address
+        );
+    }
+
+    #[test]
+    fn test_open_position_instructions_splash_pool() {
+        let mut pool = test_pool();
+        let account = pool.accounts.get_mut(&pool.whirlpool).unwrap();
+        update_account(account, |whirlpool: &mut Whirlpool| {
+            whirlpool.tick_spacing = SPLASH_POOL_TICK_SPACING;
+        });
+        let result = open_position_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            0.9,
+            1.1,
+            PositionKind::Token2022,
+            Some(100),
+            Some(pool.signer),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_increase_liquidity_instructions() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token2022, 1000, 0, 0);
+        let result = increase_liquidity_instructions(
+            &pool.accounts,
+            position_address,
+            IncreaseLiquidityParam::Liquidity(1_000_000),
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        assert_eq!(result.instructions.len(), 1);
+        assert_eq!(result.initialization_cost, 0);
+        assert_eq!(result.quote.liquidity_delta, 1_000_000);
+        let instruction = &result.instructions[0];
+        assert_eq!(instruction.accounts[1].pubkey, spl_token::ID);
+        assert_eq!(instruction.accounts[2].pubkey, spl_token_2022::ID);
+        assert_eq!(instruction.accounts[5].pubkey, position_address);
+        assert_eq!(
+            instruction.accounts[6].pubkey,
+            get_associated_token_address_with_program_id(
+                &pool.signer,
+                &result.position_mint,
+                &spl_token_2022::ID,
+            )
+        );
+    }
+
+    #[test]
+    fn test_increase_liquidity_instructions_insufficient_balance() {
+        let mut pool = test_pool();
+        let position_address = pool.add_position(PositionKind::Token, 1000, 0, 0);
+        let result = increase_liquidity_instructions(
+            &pool.accounts,
+            position_address,
+            IncreaseLiquidityParam::TokenA(10_000_000),
+            Some(100),
+            Some(pool.signer),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,2 +1,26 @@
+mod config;
+mod decrease_liquidity;
+mod harvest;
+mod increase_liquidity;
+mod mint;
+mod position;
+mod remaining_accounts;
+mod swap;
+mod token;
+mod transfer_hook;
+
+#[cfg(test)]
+mod test_utils;
+
+pub use config::*;
+pub use decrease_liquidity::*;
+pub use harvest::*;
+pub use increase_liquidity::*;
+pub use mint::*;
+pub use position::*;
+pub use remaining_accounts::*;
+pub use swap::*;
+pub use transfer_hook::*;
+
 pub use orca_whirlpools_client::*;
 pub use orca_whirlpools_core::*;
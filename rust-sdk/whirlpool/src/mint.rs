@@ -0,0 +1,327 @@
+use std::error::Error;
+
+use orca_whirlpools_core::TransferFee;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use spl_token_2022::extension::default_account_state::DefaultAccountState;
+use spl_token_2022::extension::interest_bearing_mint::InterestBearingConfig;
+use spl_token_2022::extension::transfer_fee::{self, TransferFeeConfig};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::state::{AccountState, Mint};
+
+/// The older and newer transfer fees of a Token-2022 mint. The newer transfer fee applies from
+/// `newer_transfer_fee_epoch` onwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFeeSchedule {
+    pub older_transfer_fee: TransferFee,
+    pub newer_transfer_fee: TransferFee,
+    pub newer_transfer_fee_epoch: u64,
+}
+
+/// The interest-bearing parameters of a Token-2022 mint. Rates are in basis points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterestBearingInfo {
+    pub rate_authority: Option<Pubkey>,
+    pub initialization_timestamp: i64,
+    pub pre_update_average_rate: i16,
+    pub last_update_timestamp: i64,
+    pub current_rate: i16,
+}
+
+/// The information of a mint that matters to the whirlpool program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintInfo {
+    /// The token program that owns the mint.
+    pub token_program: Pubkey,
+    /// The number of decimals of the token.
+    pub decimals: u8,
+    /// The transfer fee at the requested epoch, `None` if the mint has no transfer fee.
+    pub transfer_fee: Option<TransferFee>,
+    /// The older and newer transfer fees, `None` if the mint has no transfer fee.
+    pub transfer_fee_schedule: Option<TransferFeeSchedule>,
+    /// The interest-bearing parameters, `None` if the mint does not bear interest.
+    pub interest_bearing: Option<InterestBearingInfo>,
+    /// Whether the whirlpool program supports the mint without a token badge.
+    pub is_supported: bool,
+    /// Whether the whirlpool program supports the mint once a token badge is initialized for it.
+    pub is_supported_with_token_badge: bool,
+}
+
+fn to_transfer_fee(transfer_fee: &transfer_fee::TransferFee) -> TransferFee {
+    TransferFee::new_with_max(
+        transfer_fee.transfer_fee_basis_points.into(),
+        transfer_fee.maximum_fee.into(),
+    )
+}
+
+/// Check a mint against the rules of the whirlpool program for the tokens of a pool or a reward.
+/// Mints of the SPL Token program are always supported.
+fn is_supported_token_mint(
+    mint: &Pubkey,
+    mint_account: &Account,
+    mint_state: &StateWithExtensions<Mint>,
+    is_token_badge_initialized: bool,
+) -> Result<bool, Box<dyn Error>> {
+    if mint_account.owner == spl_token::ID {
+        return Ok(true);
+    }
+    // The native mint of Token-2022 would fragment the liquidity of SOL
+    if spl_token_2022::native_mint::check_id(mint) {
+        return Ok(false);
+    }
+    if mint_state.base.freeze_authority.is_some() && !is_token_badge_initialized {
+        return Ok(false);
+    }
+
+    for extension_type in mint_state.get_extension_types()? {
+        match extension_type {
+            ExtensionType::TransferFeeConfig
+            | ExtensionType::InterestBearingConfig
+            | ExtensionType::TokenMetadata
+            | ExtensionType::MetadataPointer
+            | ExtensionType::ConfidentialTransferMint
+            | ExtensionType::ConfidentialTransferFeeConfig => {}
+            ExtensionType::PermanentDelegate
+            | ExtensionType::TransferHook
+            | ExtensionType::MintCloseAuthority => {
+                if !is_token_badge_initialized {
+                    return Ok(false);
+                }
+            }
+            ExtensionType::DefaultAccountState => {
+                if !is_token_badge_initialized {
+                    return Ok(false);
+                }
+                let default_account_state = mint_state.get_extension::<DefaultAccountState>()?;
+                if default_account_state.state != u8::from(AccountState::Initialized) {
+                    return Ok(false);
+                }
+            }
+            _ => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+/// Parse a mint of the SPL Token or Token-2022 program.
+///
+/// # Parameters
+/// - `mint` - The address of the mint
+/// - `mint_account` - The mint account
+/// - `current_epoch` - The epoch at which to determine the transfer fee
+///
+/// # Returns
+/// - The decimals, transfer fees and interest-bearing parameters of the mint, and whether the
+///   whirlpool program supports it with and without a token badge
+pub fn parse_mint(
+    mint: &Pubkey,
+    mint_account: &Account,
+    current_epoch: u64,
+) -> Result<MintInfo, Box<dyn Error>> {
+    if mint_account.owner != spl_token::ID && mint_account.owner != spl_token_2022::ID {
+        return Err(format!("Mint {} is not owned by a token program", mint).into());
+    }
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+
+    let transfer_fee_config = mint_state.get_extension::<TransferFeeConfig>().ok();
+    let transfer_fee =
+        transfer_fee_config.map(|config| to_transfer_fee(config.get_epoch_fee(current_epoch)));
+    let transfer_fee_schedule = transfer_fee_config.map(|config| TransferFeeSchedule {
+        older_transfer_fee: to_transfer_fee(&config.older_transfer_fee),
+        newer_transfer_fee: to_transfer_fee(&config.newer_transfer_fee),
+        newer_transfer_fee_epoch: config.newer_transfer_fee.epoch.into(),
+    });
+    let interest_bearing = mint_state
+        .get_extension::<InterestBearingConfig>()
+        .ok()
+        .map(|config| InterestBearingInfo {
+            rate_authority: config.rate_authority.into(),
+            initialization_timestamp: config.initialization_timestamp.into(),
+            pre_update_average_rate: config.pre_update_average_rate.into(),
+            last_update_timestamp: config.last_update_timestamp.into(),
+            current_rate: config.current_rate.into(),
+        });
+
+    Ok(MintInfo {
+        token_program: mint_account.owner,
+        decimals: mint_state.base.decimals,
+        transfer_fee,
+        transfer_fee_schedule,
+        interest_bearing,
+        is_supported: is_supported_token_mint(mint, mint_account, &mint_state, false)?,
+        is_supported_with_token_badge: is_supported_token_mint(
+            mint,
+            mint_account,
+            &mint_state,
+            true,
+        )?,
+    })
+}
+
+/// Retrieves the current transfer fee configuration for a given token mint based on the current
+/// epoch.
+///
+/// # Parameters
+/// - `mint_account` - The mint account of the token
+/// - `current_epoch` - The current epoch to determine the applicable transfer fee
+///
+/// # Returns
+/// - The transfer fee, `None` if the mint has no transfer fee configured
+pub(crate) fn get_current_transfer_fee(
+    mint_account: &Account,
+    current_epoch: u64,
+) -> Option<TransferFee> {
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).ok()?;
+    let transfer_fee_config = mint.get_extension::<TransferFeeConfig>().ok()?;
+    Some(to_transfer_fee(
+        transfer_fee_config.get_epoch_fee(current_epoch),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mint_account, token_2022_mint_account, transfer_hook_mint_account};
+    use solana_program::program_option::COption;
+    use spl_token_2022::extension::BaseStateWithExtensionsMut;
+
+    fn transfer_fee_mint_account() -> Account {
+        token_2022_mint_account(&[ExtensionType::TransferFeeConfig], |state| {
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.older_transfer_fee = transfer_fee::TransferFee {
+                epoch: 0.into(),
+                maximum_fee: 1000.into(),
+                transfer_fee_basis_points: 100.into(),
+            };
+            config.newer_transfer_fee = transfer_fee::TransferFee {
+                epoch: 10.into(),
+                maximum_fee: 2000.into(),
+                transfer_fee_basis_points: 200.into(),
+            };
+        })
+    }
+
+    #[test]
+    fn test_parse_mint_token() {
+        let mint_info =
+            parse_mint(&Pubkey::new_unique(), &mint_account(spl_token::ID, 9), 0).unwrap();
+        assert_eq!(
+            mint_info,
+            MintInfo {
+                token_program: spl_token::ID,
+                decimals: 9,
+                transfer_fee: None,
+                transfer_fee_schedule: None,
+                interest_bearing: None,
+                is_supported: true,
+                is_supported_with_token_badge: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_mint_transfer_fee() {
+        let account = transfer_fee_mint_account();
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 5).unwrap();
+        assert_eq!(mint_info.decimals, 6);
+        assert_eq!(
+            mint_info.transfer_fee,
+            Some(TransferFee::new_with_max(100, 1000))
+        );
+        assert_eq!(
+            mint_info.transfer_fee_schedule,
+            Some(TransferFeeSchedule {
+                older_transfer_fee: TransferFee::new_with_max(100, 1000),
+                newer_transfer_fee: TransferFee::new_with_max(200, 2000),
+                newer_transfer_fee_epoch: 10,
+            })
+        );
+        assert!(mint_info.is_supported);
+
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 10).unwrap();
+        assert_eq!(
+            mint_info.transfer_fee,
+            Some(TransferFee::new_with_max(200, 2000))
+        );
+        assert_eq!(
+            get_current_transfer_fee(&account, 10),
+            Some(TransferFee::new_with_max(200, 2000))
+        );
+    }
+
+    #[test]
+    fn test_parse_mint_interest_bearing() {
+        let rate_authority = Pubkey::new_unique();
+        let account = token_2022_mint_account(&[ExtensionType::InterestBearingConfig], |state| {
+            let config = state.init_extension::<InterestBearingConfig>(true).unwrap();
+            config.rate_authority = Some(rate_authority).try_into().unwrap();
+            config.initialization_timestamp = 1000.into();
+            config.pre_update_average_rate = 50.into();
+            config.last_update_timestamp = 2000.into();
+            config.current_rate = (-25).into();
+        });
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 0).unwrap();
+        assert_eq!(
+            mint_info.interest_bearing,
+            Some(InterestBearingInfo {
+                rate_authority: Some(rate_authority),
+                initialization_timestamp: 1000,
+                pre_update_average_rate: 50,
+                last_update_timestamp: 2000,
+                current_rate: -25,
+            })
+        );
+        assert!(mint_info.is_supported);
+    }
+
+    #[test]
+    fn test_parse_mint_requires_token_badge() {
+        let account = transfer_hook_mint_account(Pubkey::new_unique());
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 0).unwrap();
+        assert!(!mint_info.is_supported);
+        assert!(mint_info.is_supported_with_token_badge);
+
+        let account = token_2022_mint_account(&[], |state| {
+            state.base.freeze_authority = COption::Some(Pubkey::new_unique());
+        });
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 0).unwrap();
+        assert!(!mint_info.is_supported);
+        assert!(mint_info.is_supported_with_token_badge);
+    }
+
+    #[test]
+    fn test_parse_mint_unsupported() {
+        let account = token_2022_mint_account(&[ExtensionType::NonTransferable], |state| {
+            state
+                .init_extension::<spl_token_2022::extension::non_transferable::NonTransferable>(
+                    true,
+                )
+                .unwrap();
+        });
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 0).unwrap();
+        assert!(!mint_info.is_supported);
+        assert!(!mint_info.is_supported_with_token_badge);
+
+        let account = token_2022_mint_account(&[ExtensionType::DefaultAccountState], |state| {
+            let default_account_state = state.init_extension::<DefaultAccountState>(true).unwrap();
+            default_account_state.state = AccountState::Frozen.into();
+        });
+        let mint_info = parse_mint(&Pubkey::new_unique(), &account, 0).unwrap();
+        assert!(!mint_info.is_supported_with_token_badge);
+
+        let mint_info = parse_mint(
+            &spl_token_2022::native_mint::ID,
+            &token_2022_mint_account(&[], |_| {}),
+            0,
+        )
+        .unwrap();
+        assert!(!mint_info.is_supported_with_token_badge);
+    }
+
+    #[test]
+    fn test_parse_mint_invalid_owner() {
+        let mut account = mint_account(spl_token::ID, 6);
+        account.owner = Pubkey::new_unique();
+        assert!(parse_mint(&Pubkey::new_unique(), &account, 0).is_err());
+    }
+}
@@ -0,0 +1,625 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use orca_whirlpools_client::accounts::{Position, PositionBundle, Whirlpool};
+use orca_whirlpools_client::instructions::{
+    CollectFeesV2, CollectFeesV2InstructionArgs, CollectRewardV2, CollectRewardV2InstructionArgs,
+    DecreaseLiquidityV2, DecreaseLiquidityV2InstructionArgs, IncreaseLiquidityV2,
+    IncreaseLiquidityV2InstructionArgs, UpdateFeesAndRewards,
+};
+use orca_whirlpools_client::types::{AccountsType, RemainingAccountsInfo};
+use orca_whirlpools_client::{
+    fetch_position, fetch_position_bundle, fetch_whirlpool, get_bundled_position_address,
+    get_position_address, get_position_bundle_address, get_tick_array_address, AccountFetcher, ID,
+};
+use orca_whirlpools_core::{
+    get_tick_array_start_tick_index, DecreaseLiquidityQuote, IncreaseLiquidityQuote,
+};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccount;
+
+use crate::remaining_accounts::RemainingAccountsBuilder;
+use crate::token::get_mint_accounts;
+use crate::transfer_hook::get_transfer_hook_accounts;
+
+/// The number of positions a position bundle can hold.
+const POSITION_BUNDLE_SIZE: usize = 256;
+
+/// The kind of a position, which determines the token that represents it in a wallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionKind {
+    /// A position represented by an NFT minted with the SPL Token program.
+    Token,
+    /// A position represented by an NFT minted with the Token-2022 program.
+    #[default]
+    Token2022,
+    /// A position in a position bundle, represented by the NFT of the bundle.
+    Bundled {
+        position_bundle_mint: Pubkey,
+        bundle_index: u8,
+    },
+}
+
+/// A position held by a wallet, tagged with the kind of token that represents it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedPosition {
+    /// The address of the position.
+    pub address: Pubkey,
+    /// The position.
+    pub position: Position,
+    /// The kind of the position.
+    pub kind: PositionKind,
+}
+
+/// The accounts that the instructions of a position operate on.
+#[derive(Debug)]
+pub(crate) struct PositionAccounts {
+    pub whirlpool_address: Pubkey,
+    pub whirlpool: Whirlpool,
+    pub mint_a: Account,
+    pub mint_b: Account,
+    pub position_address: Pubkey,
+    pub position_token_account: Pubkey,
+    pub tick_array_lower: Pubkey,
+    pub tick_array_upper: Pubkey,
+}
+
+/// An existing position together with the accounts its instructions operate on.
+#[derive(Debug)]
+pub(crate) struct PositionInfo {
+    pub position: Position,
+    pub kind: PositionKind,
+    pub accounts: PositionAccounts,
+}
+
+/// Derive the addresses of the tick arrays that contain the ticks of a position.
+///
+/// # Parameters
+/// - `whirlpool_address` - The address of the whirlpool
+/// - `tick_spacing` - The tick spacing of the whirlpool
+/// - `tick_lower_index` - The lower tick index of the position
+/// - `tick_upper_index` - The upper tick index of the position
+///
+/// # Returns
+/// - The addresses of the lower and upper tick arrays
+pub(crate) fn get_position_tick_array_addresses(
+    whirlpool_address: &Pubkey,
+    tick_spacing: u16,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<(Pubkey, Pubkey), ProgramError> {
+    let lower_start_tick_index = get_tick_array_start_tick_index(tick_lower_index, tick_spacing);
+    let upper_start_tick_index = get_tick_array_start_tick_index(tick_upper_index, tick_spacing);
+    let (tick_array_lower, _) = get_tick_array_address(whirlpool_address, lower_start_tick_index)?;
+    let (tick_array_upper, _) = get_tick_array_address(whirlpool_address, upper_start_tick_index)?;
+    Ok((tick_array_lower, tick_array_upper))
+}
+
+/// The indexes of the positions that are open in a position bundle.
+fn get_bundle_indexes(position_bundle: &PositionBundle) -> impl Iterator<Item = u8> + '_ {
+    (0..POSITION_BUNDLE_SIZE)
+        .filter(|index| position_bundle.position_bitmap[index / 8] & (1 << (index % 8)) != 0)
+        .map(|index| index as u8)
+}
+
+/// Determine the kind of an existing position from its address and the mint of its token.
+fn get_position_kind<F: AccountFetcher>(
+    fetcher: &F,
+    position_address: &Pubkey,
+    position_mint: &Pubkey,
+    position_mint_account: &Account,
+) -> Result<PositionKind, Box<dyn Error>> {
+    let (nft_position_address, _) = get_position_address(position_mint)?;
+    if nft_position_address == *position_address {
+        return if position_mint_account.owner == spl_token::ID {
+            Ok(PositionKind::Token)
+        } else if position_mint_account.owner == spl_token_2022::ID {
+            Ok(PositionKind::Token2022)
+        } else {
+            Err(format!(
+                "Position mint {} is not owned by a token program",
+                position_mint
+            )
+            .into())
+        };
+    }
+
+    // A bundled position holds the mint of its bundle, so the bundle index is found by
+    // matching the address against the positions that are open in the bundle
+    let (position_bundle_address, _) = get_position_bundle_address(position_mint)?;
+    let position_bundle = fetch_position_bundle(fetcher, &position_bundle_address)?;
+    for bundle_index in get_bundle_indexes(&position_bundle) {
+        let (bundled_position_address, _) =
+            get_bundled_position_address(position_mint, bundle_index)?;
+        if bundled_position_address == *position_address {
+            return Ok(PositionKind::Bundled {
+                position_bundle_mint: *position_mint,
+                bundle_index,
+            });
+        }
+    }
+    Err(format!(
+        "Position {} is not part of its position bundle",
+        position_address
+    )
+    .into())
+}
+
+/// Fetch an existing position together with the accounts its instructions operate on.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `position_address` - The address of the position
+/// - `authority` - The owner of the token that represents the position
+///
+/// # Returns
+/// - The position, its kind and its accounts
+pub(crate) fn fetch_position_info<F: AccountFetcher>(
+    fetcher: &F,
+    position_address: Pubkey,
+    authority: Pubkey,
+) -> Result<PositionInfo, Box<dyn Error>> {
+    let position = fetch_position(fetcher, &position_address)?;
+    let whirlpool = fetch_whirlpool(fetcher, &position.whirlpool)?;
+    let [mint_a, mint_b, position_mint]: [Account; 3] = get_mint_accounts(
+        fetcher,
+        &[
+            whirlpool.token_mint_a,
+            whirlpool.token_mint_b,
+            position.position_mint,
+        ],
+    )?
+    .try_into()
+    .map_err(|_| "Failed to fetch the mints of the position")?;
+
+    let kind = get_position_kind(
+        fetcher,
+        &position_address,
+        &position.position_mint,
+        &position_mint,
+    )?;
+    let position_token_account = get_associated_token_address_with_program_id(
+        &authority,
+        &position.position_mint,
+        &position_mint.owner,
+    );
+    let (tick_array_lower, tick_array_upper) = get_position_tick_array_addresses(
+        &position.whirlpool,
+        whirlpool.tick_spacing,
+        position.tick_lower_index,
+        position.tick_upper_index,
+    )?;
+
+    Ok(PositionInfo {
+        accounts: PositionAccounts {
+            whirlpool_address: position.whirlpool,
+            whirlpool,
+            mint_a,
+            mint_b,
+            position_address,
+            position_token_account,
+            tick_array_lower,
+            tick_array_upper,
+        },
+        position,
+        kind,
+    })
+}
+
+/// Fetch all the positions held by a wallet, given the token accounts of the wallet. Positions
+/// are found for the NFTs of the SPL Token and Token-2022 programs, and for the position
+/// bundles, whose open positions are found through the bitmap of the bundle.
+///
+/// The token accounts of a wallet can be fetched with the `getTokenAccountsByOwner` RPC method,
+/// once for each token program. Accounts that are not token accounts holding a single token
+/// are skipped.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `token_accounts` - The token accounts of the wallet
+///
+/// # Returns
+/// - The positions held by the wallet, tagged with their kind
+pub fn fetch_positions_for_token_accounts<F: AccountFetcher>(
+    fetcher: &F,
+    token_accounts: &[Account],
+) -> Result<Vec<OwnedPosition>, Box<dyn Error>> {
+    let mut candidates = Vec::new();
+    for token_account in token_accounts {
+        if token_account.owner != spl_token::ID && token_account.owner != spl_token_2022::ID {
+            continue;
+        }
+        let Ok(state) = StateWithExtensions::<TokenAccount>::unpack(&token_account.data) else {
+            continue;
+        };
+        if state.base.amount == 1 {
+            candidates.push((state.base.mint, token_account.owner));
+        }
+    }
+
+    let mut addresses = Vec::with_capacity(candidates.len() * 2);
+    for (mint, _) in &candidates {
+        addresses.push(get_position_address(mint)?.0);
+        addresses.push(get_position_bundle_address(mint)?.0);
+    }
+    let accounts = fetcher.get_multiple_accounts(&addresses)?;
+
+    let mut owned_positions = Vec::new();
+    let mut bundled_positions = Vec::new();
+    for (((mint, token_program), addresses), accounts) in candidates
+        .iter()
+        .zip(addresses.chunks(2))
+        .zip(accounts.chunks(2))
+    {
+        if let Some(account) = accounts[0].as_ref().filter(|account| account.owner == ID) {
+            let kind = if *token_program == spl_token::ID {
+                PositionKind::Token
+            } else {
+                PositionKind::Token2022
+            };
+            owned_positions.push(OwnedPosition {
+                address: addresses[0],
+                position: Position::from_bytes(&account.data)?,
+                kind,
+            });
+        }
+        if let Some(account) = accounts[1].as_ref().filter(|account| account.owner == ID) {
+            let position_bundle = PositionBundle::from_bytes(&account.data)?;
+            for bundle_index in get_bundle_indexes(&position_bundle) {
+                let (address, _) = get_bundled_position_address(mint, bundle_index)?;
+                bundled_positions.push((address, bundle_index, *mint));
+            }
+        }
+    }
+
+    let bundled_addresses: Vec<Pubkey> = bundled_positions
+        .iter()
+        .map(|(address, _, _)| *address)
+        .collect();
+    let bundled_accounts = fetcher.get_multiple_accounts(&bundled_addresses)?;
+    for ((address, bundle_index, position_bundle_mint), account) in
+        bundled_positions.into_iter().zip(bundled_accounts)
+    {
+        let Some(account) = account.filter(|account| account.owner == ID) else {
+            continue;
+        };
+        owned_positions.push(OwnedPosition {
+            address,
+            position: Position::from_bytes(&account.data)?,
+            kind: PositionKind::Bundled {
+                position_bundle_mint,
+                bundle_index,
+            },
+        });
+    }
+
+    Ok(owned_positions)
+}
+
+impl PositionAccounts {
+    /// Resolve the transfer hook accounts of both tokens of the whirlpool, for a transfer from
+    /// the owner token accounts into the vaults (`deposit`) or the other way around.
+    fn transfer_hook_remaining_accounts<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+        token_owner_account_a: Pubkey,
+        token_owner_account_b: Pubkey,
+        authority: Pubkey,
+        deposit: bool,
+    ) -> Result<(Option<RemainingAccountsInfo>, Vec<AccountMeta>), Box<dyn Error>> {
+        let transfer_hook_accounts_a = if deposit {
+            get_transfer_hook_accounts(
+                fetcher,
+                self.whirlpool.token_mint_a,
+                &self.mint_a,
+                token_owner_account_a,
+                self.whirlpool.token_vault_a,
+                authority,
+            )?
+        } else {
+            get_transfer_hook_accounts(
+                fetcher,
+                self.whirlpool.token_mint_a,
+                &self.mint_a,
+                self.whirlpool.token_vault_a,
+                token_owner_account_a,
+                self.whirlpool_address,
+            )?
+        };
+        let transfer_hook_accounts_b = if deposit {
+            get_transfer_hook_accounts(
+                fetcher,
+                self.whirlpool.token_mint_b,
+                &self.mint_b,
+                token_owner_account_b,
+                self.whirlpool.token_vault_b,
+                authority,
+            )?
+        } else {
+            get_transfer_hook_accounts(
+                fetcher,
+                self.whirlpool.token_mint_b,
+                &self.mint_b,
+                self.whirlpool.token_vault_b,
+                token_owner_account_b,
+                self.whirlpool_address,
+            )?
+        };
+        Ok(RemainingAccountsBuilder::default()
+            .add_slice(
+                AccountsType::TransferHookA,
+                &transfer_hook_accounts_a.unwrap_or_default(),
+            )?
+            .add_slice(
+                AccountsType::TransferHookB,
+                &transfer_hook_accounts_b.unwrap_or_default(),
+            )?
+            .build())
+    }
+
+    /// Build the instruction that adds the liquidity of a quote to the position.
+    pub fn increase_liquidity_instruction<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+        quote: &IncreaseLiquidityQuote,
+        token_account_addresses: &HashMap<Pubkey, Pubkey>,
+        authority: Pubkey,
+    ) -> Result<Instruction, Box<dyn Error>> {
+        let token_owner_account_a = token_account_addresses[&self.whirlpool.token_mint_a];
+        let token_owner_account_b = token_account_addresses[&self.whirlpool.token_mint_b];
+        let (remaining_accounts_info, remaining_accounts) = self.transfer_hook_remaining_accounts(
+            fetcher,
+            token_owner_account_a,
+            token_owner_account_b,
+            authority,
+            true,
+        )?;
+
+        Ok(IncreaseLiquidityV2 {
+            whirlpool: self.whirlpool_address,
+            token_program_a: self.mint_a.owner,
+            token_program_b: self.mint_b.owner,
+            memo_program: spl_memo::ID,
+            position_authority: authority,
+            position: self.position_address,
+            position_token_account: self.position_token_account,
+            token_mint_a: self.whirlpool.token_mint_a,
+            token_mint_b: self.whirlpool.token_mint_b,
+            token_owner_account_a,
+            token_owner_account_b,
+            token_vault_a: self.whirlpool.token_vault_a,
+            token_vault_b: self.whirlpool.token_vault_b,
+            tick_array_lower: self.tick_array_lower,
+            tick_array_upper: self.tick_array_upper,
+        }
+        .instruction_with_remaining_accounts(
+            IncreaseLiquidityV2InstructionArgs {
+                liquidity_amount: quote.liquidity_delta,
+                token_max_a: quote.token_max_a,
+                token_max_b: quote.token_max_b,
+                remaining_accounts_info,
+            },
+            &remaining_accounts,
+        ))
+    }
+
+    /// Build the instruction that withdraws the liquidity of a quote from the position.
+    pub fn decrease_liquidity_instruction<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+        quote: &DecreaseLiquidityQuote,
+        token_account_addresses: &HashMap<Pubkey, Pubkey>,
+        authority: Pubkey,
+    ) -> Result<Instruction, Box<dyn Error>> {
+        let token_owner_account_a = token_account_addresses[&self.whirlpool.token_mint_a];
+        let token_owner_account_b = token_account_addresses[&self.whirlpool.token_mint_b];
+        let (remaining_accounts_info, remaining_accounts) = self.transfer_hook_remaining_accounts(
+            fetcher,
+            token_owner_account_a,
+            token_owner_account_b,
+            authority,
+            false,
+        )?;
+
+        Ok(DecreaseLiquidityV2 {
+            whirlpool: self.whirlpool_address,
+            token_program_a: self.mint_a.owner,
+            token_program_b: self.mint_b.owner,
+            memo_program: spl_memo::ID,
+            position_authority: authority,
+            position: self.position_address,
+            position_token_account: self.position_token_account,
+            token_mint_a: self.whirlpool.token_mint_a,
+            token_mint_b: self.whirlpool.token_mint_b,
+            token_owner_account_a,
+            token_owner_account_b,
+            token_vault_a: self.whirlpool.token_vault_a,
+            token_vault_b: self.whirlpool.token_vault_b,
+            tick_array_lower: self.tick_array_lower,
+            tick_array_upper: self.tick_array_upper,
+        }
+        .instruction_with_remaining_accounts(
+            DecreaseLiquidityV2InstructionArgs {
+                liquidity_amount: quote.liquidity_delta,
+                token_min_a: quote.token_min_a,
+                token_min_b: quote.token_min_b,
+                remaining_accounts_info,
+            },
+            &remaining_accounts,
+        ))
+    }
+
+    /// Build the instruction that updates the fees and rewards owed to the position.
+    pub fn update_fees_and_rewards_instruction(&self) -> Instruction {
+        UpdateFeesAndRewards {
+            whirlpool: self.whirlpool_address,
+            position: self.position_address,
+            tick_array_lower: self.tick_array_lower,
+            tick_array_upper: self.tick_array_upper,
+        }
+        .instruction()
+    }
+
+    /// Build the instruction that collects the fees owed to the position.
+    pub fn collect_fees_instruction<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+        token_account_addresses: &HashMap<Pubkey, Pubkey>,
+        authority: Pubkey,
+    ) -> Result<Instruction, Box<dyn Error>> {
+        let token_owner_account_a = token_account_addresses[&self.whirlpool.token_mint_a];
+        let token_owner_account_b = token_account_addresses[&self.whirlpool.token_mint_b];
+        let (remaining_accounts_info, remaining_accounts) = self.transfer_hook_remaining_accounts(
+            fetcher,
+            token_owner_account_a,
+            token_owner_account_b,
+            authority,
+            false,
+        )?;
+
+        Ok(CollectFeesV2 {
+            whirlpool: self.whirlpool_address,
+            position_authority: authority,
+            position: self.position_address,
+            position_token_account: self.position_token_account,
+            token_mint_a: self.whirlpool.token_mint_a,
+            token_mint_b: self.whirlpool.token_mint_b,
+            token_owner_account_a,
+            token_vault_a: self.whirlpool.token_vault_a,
+            token_owner_account_b,
+            token_vault_b: self.whirlpool.token_vault_b,
+            token_program_a: self.mint_a.owner,
+            token_program_b: self.mint_b.owner,
+            memo_program: spl_memo::ID,
+        }
+        .instruction_with_remaining_accounts(
+            CollectFeesV2InstructionArgs {
+                remaining_accounts_info,
+            },
+            &remaining_accounts,
+        ))
+    }
+
+    /// Build the instruction that collects one of the rewards owed to the position.
+    pub fn collect_reward_instruction<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+        reward_index: usize,
+        reward_mint_account: &Account,
+        token_account_addresses: &HashMap<Pubkey, Pubkey>,
+        authority: Pubkey,
+    ) -> Result<Instruction, Box<dyn Error>> {
+        let reward_info = &self.whirlpool.reward_infos[reward_index];
+        let reward_owner_account = token_account_addresses[&reward_info.mint];
+        let transfer_hook_accounts = get_transfer_hook_accounts(
+            fetcher,
+            reward_info.mint,
+            reward_mint_account,
+            reward_info.vault,
+            reward_owner_account,
+            self.whirlpool_address,
+        )?;
+        let (remaining_accounts_info, remaining_accounts) = RemainingAccountsBuilder::default()
+            .add_slice(
+                AccountsType::TransferHookReward,
+                &transfer_hook_accounts.unwrap_or_default(),
+            )?
+            .build();
+
+        Ok(CollectRewardV2 {
+            whirlpool: self.whirlpool_address,
+            position_authority: authority,
+            position: self.position_address,
+            position_token_account: self.position_token_account,
+            reward_owner_account,
+            reward_mint: reward_info.mint,
+            reward_vault: reward_info.vault,
+            reward_token_program: reward_mint_account.owner,
+            memo_program: spl_memo::ID,
+        }
+        .instruction_with_remaining_accounts(
+            CollectRewardV2InstructionArgs {
+                reward_index: reward_index as u8,
+                remaining_accounts_info,
+            },
+            &remaining_accounts,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{test_pool, token_account};
+
+    #[test]
+    fn test_fetch_positions_for_token_accounts() {
+        let mut pool = test_pool();
+        let position_bundle_mint = Pubkey::new_unique();
+        let token_position = pool.add_position(PositionKind::Token, 1000, 0, 0);
+        let token_2022_position = pool.add_position(PositionKind::Token2022, 2000, 0, 0);
+        let bundled_positions: Vec<Pubkey> = [200, 3]
+            .into_iter()
+            .map(|bundle_index| {
+                pool.add_position(
+                    PositionKind::Bundled {
+                        position_bundle_mint,
+                        bundle_index,
+                    },
+                    3000,
+                    0,
+                    0,
+                )
+            })
+            .collect();
+        let token_accounts: Vec<Account> = pool.accounts.values().cloned().collect();
+
+        let mut positions =
+            fetch_positions_for_token_accounts(&pool.accounts, &token_accounts).unwrap();
+        positions.sort_by_key(|position| position.position.liquidity);
+
+        let summary: Vec<(Pubkey, PositionKind)> = positions
+            .iter()
+            .map(|position| (position.address, position.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (token_position, PositionKind::Token),
+                (token_2022_position, PositionKind::Token2022),
+                (
+                    bundled_positions[1],
+                    PositionKind::Bundled {
+                        position_bundle_mint,
+                        bundle_index: 3,
+                    },
+                ),
+                (
+                    bundled_positions[0],
+                    PositionKind::Bundled {
+                        position_bundle_mint,
+                        bundle_index: 200,
+                    },
+                ),
+            ]
+        );
+        assert_eq!(positions[0].position.whirlpool, pool.whirlpool);
+    }
+
+    #[test]
+    fn test_fetch_positions_for_token_accounts_without_positions() {
+        let pool = test_pool();
+        let token_accounts = vec![
+            token_account(spl_token::ID, pool.mint_a, pool.signer, 1),
+            token_account(spl_token_2022::ID, pool.mint_b, pool.signer, 1_000_000),
+        ];
+        let positions =
+            fetch_positions_for_token_accounts(&pool.accounts, &token_accounts).unwrap();
+        assert!(positions.is_empty());
+    }
+}
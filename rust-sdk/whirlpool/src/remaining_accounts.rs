@@ -0,0 +1,99 @@
+use std::error::Error;
+
+use orca_whirlpools_client::types::{AccountsType, RemainingAccountsInfo, RemainingAccountsSlice};
+use solana_program::instruction::AccountMeta;
+
+/// Collects the remaining accounts of a v2 instruction together with the slices that describe
+/// them to the program. Empty slices are left out.
+#[derive(Debug, Default)]
+pub struct RemainingAccountsBuilder {
+    remaining_accounts: Vec<AccountMeta>,
+    slices: Vec<RemainingAccountsSlice>,
+}
+
+impl RemainingAccountsBuilder {
+    /// Append a slice of accounts of the given type. The program reads the length of a slice
+    /// as a `u8`, so a slice can hold at most 255 accounts.
+    pub fn add_slice(
+        mut self,
+        accounts_type: AccountsType,
+        accounts: &[AccountMeta],
+    ) -> Result<Self, Box<dyn Error>> {
+        if accounts.is_empty() {
+            return Ok(self);
+        }
+        let length = u8::try_from(accounts.len()).map_err(|_| {
+            format!(
+                "Slice of {:?} has {} accounts, more than the maximum of {}",
+                accounts_type,
+                accounts.len(),
+                u8::MAX
+            )
+        })?;
+        self.slices.push(RemainingAccountsSlice {
+            accounts_type,
+            length,
+        });
+        self.remaining_accounts.extend_from_slice(accounts);
+        Ok(self)
+    }
+
+    /// Build the `remaining_accounts_info` argument and the remaining accounts of the instruction.
+    pub fn build(self) -> (Option<RemainingAccountsInfo>, Vec<AccountMeta>) {
+        if self.slices.is_empty() {
+            return (None, vec![]);
+        }
+        let remaining_accounts_info = RemainingAccountsInfo {
+            slices: self.slices,
+        };
+        (Some(remaining_accounts_info), self.remaining_accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::pubkey::Pubkey;
+
+    fn accounts(count: usize) -> Vec<AccountMeta> {
+        (0..count)
+            .map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false))
+            .collect()
+    }
+
+    #[test]
+    fn test_remaining_accounts_builder() {
+        let transfer_hook_accounts = accounts(2);
+        let (remaining_accounts_info, remaining_accounts) = RemainingAccountsBuilder::default()
+            .add_slice(AccountsType::TransferHookA, &[])
+            .unwrap()
+            .add_slice(AccountsType::TransferHookB, &transfer_hook_accounts)
+            .unwrap()
+            .build();
+        assert_eq!(
+            remaining_accounts_info,
+            Some(RemainingAccountsInfo {
+                slices: vec![RemainingAccountsSlice {
+                    accounts_type: AccountsType::TransferHookB,
+                    length: 2,
+                }],
+            })
+        );
+        assert_eq!(remaining_accounts, transfer_hook_accounts);
+
+        let (remaining_accounts_info, remaining_accounts) =
+            RemainingAccountsBuilder::default().build();
+        assert_eq!(remaining_accounts_info, None);
+        assert!(remaining_accounts.is_empty());
+    }
+
+    #[test]
+    fn test_remaining_accounts_builder_slice_too_long() {
+        assert!(RemainingAccountsBuilder::default()
+            .add_slice(AccountsType::TransferHookA, &accounts(255))
+            .is_ok());
+        assert!(RemainingAccountsBuilder::default()
+            .add_slice(AccountsType::TransferHookA, &accounts(256))
+            .is_err());
+    }
+}
@@ -0,0 +1,381 @@
+use std::error::Error;
+
+use orca_whirlpools_client::instructions::{SwapV2, SwapV2InstructionArgs};
+use orca_whirlpools_client::types::AccountsType;
+use orca_whirlpools_client::{
+    fetch_tick_arrays_or_default, fetch_whirlpool, get_oracle_address, AccountFetcher,
+};
+use orca_whirlpools_core::{
+    swap_quote_by_input_token, swap_quote_by_output_token, ExactInSwapQuote, ExactOutSwapQuote,
+};
+use solana_program::clock::Clock;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use crate::config::{get_funder, get_native_mint_wrapping_strategy, get_slippage_tolerance_bps};
+use crate::mint::get_current_transfer_fee;
+use crate::remaining_accounts::RemainingAccountsBuilder;
+use crate::token::{
+    get_mint_accounts, get_sysvar, prepare_token_accounts_instructions, TokenAccountStrategy,
+};
+use crate::transfer_hook::get_transfer_hook_accounts;
+
+/// The type of a swap, either with an exact input or an exact output amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapType {
+    ExactIn,
+    ExactOut,
+}
+
+/// The quote of a swap, corresponding to the type of the swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapQuote {
+    ExactIn(ExactInSwapQuote),
+    ExactOut(ExactOutSwapQuote),
+}
+
+/// The instructions and quote for executing a swap.
+#[derive(Debug)]
+pub struct SwapInstructions {
+    /// The instructions needed to perform the swap.
+    pub instructions: Vec<Instruction>,
+    /// The quote of the swap.
+    pub quote: SwapQuote,
+    /// The keypairs of accounts created for the swap that need to sign the transaction.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// Generates the instructions necessary to execute a token swap in an Orca Whirlpool.
+/// It handles both exact input and exact output swaps, fetching the required accounts and tick
+/// arrays, and determining the swap quote.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `whirlpool_address` - The address of the Whirlpool against which the swap will be made
+/// - `amount` - The exact input or output amount of the swap
+/// - `specified_mint` - The mint of the token of the specified amount
+/// - `swap_type` - Whether the amount is the input or the output amount of the swap
+/// - `slippage_tolerance_bps` - The maximum acceptable slippage tolerance, in basis points.
+///   Defaults to `SLIPPAGE_TOLERANCE_BPS`
+/// - `signer` - The wallet executing the swap. Defaults to `FUNDER`
+///
+/// # Returns
+/// - The instructions and the quote of the swap
+pub fn swap_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    whirlpool_address: Pubkey,
+    amount: u64,
+    specified_mint: Pubkey,
+    swap_type: SwapType,
+    slippage_tolerance_bps: Option<u16>,
+    signer: Option<Pubkey>,
+) -> Result<SwapInstructions, Box<dyn Error>> {
+    let slippage_tolerance_bps = get_slippage_tolerance_bps(slippage_tolerance_bps)?;
+    let signer = get_funder(signer)?;
+
+    let whirlpool = fetch_whirlpool(fetcher, &whirlpool_address)?;
+    if specified_mint != whirlpool.token_mint_a && specified_mint != whirlpool.token_mint_b {
+        return Err(format!("Mint {} is not part of the whirlpool", specified_mint).into());
+    }
+    let specified_token_a = specified_mint == whirlpool.token_mint_a;
+    let specified_input = swap_type == SwapType::ExactIn;
+    let a_to_b = specified_token_a == specified_input;
+
+    let mint_accounts =
+        get_mint_accounts(fetcher, &[whirlpool.token_mint_a, whirlpool.token_mint_b])?;
+    let (mint_a, mint_b) = (&mint_accounts[0], &mint_accounts[1]);
+
+    let tick_arrays = fetch_tick_arrays_or_default(fetcher, &whirlpool_address, &whirlpool)?;
+    let (oracle_address, _) = get_oracle_address(&whirlpool_address)?;
+
+    let clock: Clock = get_sysvar(fetcher)?;
+    let transfer_fee_a = get_current_transfer_fee(mint_a, clock.epoch);
+    let transfer_fee_b = get_current_transfer_fee(mint_b, clock.epoch);
+
+    let quote = match swap_type {
+        SwapType::ExactIn => SwapQuote::ExactIn(
+            swap_quote_by_input_token(
+                amount,
+                specified_token_a,
+                slippage_tolerance_bps,
+                whirlpool.clone().into(),
+                tick_arrays.map(|(_, tick_array)| tick_array).into(),
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map_err(|code| format!("Failed to quote the swap, error code {}", code))?,
+        ),
+        SwapType::ExactOut => SwapQuote::ExactOut(
+            swap_quote_by_output_token(
+                amount,
+                specified_token_a,
+                slippage_tolerance_bps,
+                whirlpool.clone().into(),
+                tick_arrays.map(|(_, tick_array)| tick_array).into(),
+                transfer_fee_a,
+                transfer_fee_b,
+            )
+            .map_err(|code| format!("Failed to quote the swap, error code {}", code))?,
+        ),
+    };
+    let (max_in_amount, other_amount_threshold) = match quote {
+        SwapQuote::ExactIn(quote) => (quote.token_in, quote.token_min_out),
+        SwapQuote::ExactOut(quote) => (quote.token_max_in, quote.token_max_in),
+    };
+
+    let token_account_spec = if a_to_b {
+        [
+            TokenAccountStrategy::WithBalance(whirlpool.token_mint_a, max_in_amount),
+            TokenAccountStrategy::WithoutBalance(whirlpool.token_mint_b),
+        ]
+    } else {
+        [
+            TokenAccountStrategy::WithoutBalance(whirlpool.token_mint_a),
+            TokenAccountStrategy::WithBalance(whirlpool.token_mint_b, max_in_amount),
+        ]
+    };
+    let token_accounts = prepare_token_accounts_instructions(
+        fetcher,
+        signer,
+        &token_account_spec,
+        get_native_mint_wrapping_strategy()?,
+    )?;
+    let token_owner_account_a = token_accounts.token_account_addresses[&whirlpool.token_mint_a];
+    let token_owner_account_b = token_accounts.token_account_addresses[&whirlpool.token_mint_b];
+
+    let transfer_hook_accounts_a = if a_to_b {
+        get_transfer_hook_accounts(
+            fetcher,
+            whirlpool.token_mint_a,
+            mint_a,
+            token_owner_account_a,
+            whirlpool.token_vault_a,
+            signer,
+        )?
+    } else {
+        get_transfer_hook_accounts(
+            fetcher,
+            whirlpool.token_mint_a,
+            mint_a,
+            whirlpool.token_vault_a,
+            token_owner_account_a,
+            whirlpool_address,
+        )?
+    };
+    let transfer_hook_accounts_b = if a_to_b {
+        get_transfer_hook_accounts(
+            fetcher,
+            whirlpool.token_mint_b,
+            mint_b,
+            whirlpool.token_vault_b,
+            token_owner_account_b,
+            whirlpool_address,
+        )?
+    } else {
+        get_transfer_hook_accounts(
+            fetcher,
+            whirlpool.token_mint_b,
+            mint_b,
+            token_owner_account_b,
+            whirlpool.token_vault_b,
+            signer,
+        )?
+    };
+
+    // The tick arrays on the other side of the current tick array are passed as supplemental
+    // tick arrays, so the swap can use them if the price moves in that direction
+    let supplemental_tick_arrays = [
+        AccountMeta::new(tick_arrays[3].0, false),
+        AccountMeta::new(tick_arrays[4].0, false),
+    ];
+    let (remaining_accounts_info, remaining_accounts) = RemainingAccountsBuilder::default()
+        .add_slice(
+            AccountsType::TransferHookA,
+            &transfer_hook_accounts_a.unwrap_or_default(),
+        )?
+        .add_slice(
+            AccountsType::TransferHookB,
+            &transfer_hook_accounts_b.unwrap_or_default(),
+        )?
+        .add_slice(
+            AccountsType::SupplementalTickArrays,
+            &supplemental_tick_arrays,
+        )?
+        .build();
+
+    let swap_instruction = SwapV2 {
+        token_program_a: mint_a.owner,
+        token_program_b: mint_b.owner,
+        memo_program: spl_memo::ID,
+        token_authority: signer,
+        whirlpool: whirlpool_address,
+        token_mint_a: whirlpool.token_mint_a,
+        token_mint_b: whirlpool.token_mint_b,
+        token_owner_account_a,
+        token_vault_a: whirlpool.token_vault_a,
+        token_owner_account_b,
+        token_vault_b: whirlpool.token_vault_b,
+        tick_array0: tick_arrays[0].0,
+        tick_array1: tick_arrays[1].0,
+        tick_array2: tick_arrays[2].0,
+        oracle: oracle_address,
+    }
+    .instruction_with_remaining_accounts(
+        SwapV2InstructionArgs {
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit: 0,
+            amount_specified_is_input: specified_input,
+            a_to_b,
+            remaining_accounts_info,
+        },
+        &remaining_accounts,
+    );
+
+    let mut instructions = token_accounts.create_instructions;
+    instructions.push(swap_instruction);
+    instructions.extend(token_accounts.cleanup_instructions);
+
+    Ok(SwapInstructions {
+        instructions,
+        quote,
+        additional_signers: token_accounts.additional_signers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_pool;
+    use orca_whirlpools_client::{get_tick_array_address, types::RemainingAccountsInfo};
+    use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+    #[test]
+    fn test_swap_instructions_exact_in() {
+        let pool = test_pool();
+        let result = swap_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            1000,
+            pool.mint_a,
+            SwapType::ExactIn,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        let SwapQuote::ExactIn(quote) = result.quote else {
+            panic!("Expected an exact input quote");
+        };
+        assert_eq!(quote.token_in, 1000);
+        assert_eq!(result.instructions.len(), 1);
+        assert!(result.additional_signers.is_empty());
+
+        let instruction = &result.instructions[0];
+        let ata_a = get_associated_token_address_with_program_id(
+            &pool.signer,
+            &pool.mint_a,
+            &spl_token::ID,
+        );
+        let ata_b = get_associated_token_address_with_program_id(
+            &pool.signer,
+            &pool.mint_b,
+            &spl_token_2022::ID,
+        );
+        let tick_array = |start_tick_index| {
+            get_tick_array_address(&pool.whirlpool, start_tick_index)
+                .unwrap()
+                .0
+        };
+        let expected = SwapV2 {
+            token_program_a: spl_token::ID,
+            token_program_b: spl_token_2022::ID,
+            memo_program: spl_memo::ID,
+            token_authority: pool.signer,
+            whirlpool: pool.whirlpool,
+            token_mint_a: pool.mint_a,
+            token_mint_b: pool.mint_b,
+            token_owner_account_a: ata_a,
+            token_vault_a: pool.vault_a,
+            token_owner_account_b: ata_b,
+            token_vault_b: pool.vault_b,
+            tick_array0: tick_array(0),
+            tick_array1: tick_array(5632),
+            tick_array2: tick_array(11264),
+            oracle: get_oracle_address(&pool.whirlpool).unwrap().0,
+        }
+        .instruction_with_remaining_accounts(
+            SwapV2InstructionArgs {
+                amount: 1000,
+                other_amount_threshold: quote.token_min_out,
+                sqrt_price_limit: 0,
+                amount_specified_is_input: true,
+                a_to_b: true,
+                remaining_accounts_info: Some(RemainingAccountsInfo {
+                    slices: vec![orca_whirlpools_client::types::RemainingAccountsSlice {
+                        accounts_type: AccountsType::SupplementalTickArrays,
+                        length: 2,
+                    }],
+                }),
+            },
+            &[
+                AccountMeta::new(tick_array(-5632), false),
+                AccountMeta::new(tick_array(-11264), false),
+            ],
+        );
+        assert_eq!(*instruction, expected);
+    }
+
+    #[test]
+    fn test_swap_instructions_exact_out() {
+        let pool = test_pool();
+        let result = swap_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            1000,
+            pool.mint_a,
+            SwapType::ExactOut,
+            Some(100),
+            Some(pool.signer),
+        )
+        .unwrap();
+
+        let SwapQuote::ExactOut(quote) = result.quote else {
+            panic!("Expected an exact output quote");
+        };
+        assert_eq!(quote.token_out, 1000);
+        assert!(quote.token_max_in >= quote.token_est_in);
+        assert_eq!(result.instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_swap_instructions_insufficient_balance() {
+        let pool = test_pool();
+        let result = swap_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            2_000_000,
+            pool.mint_a,
+            SwapType::ExactIn,
+            Some(100),
+            Some(pool.signer),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_swap_instructions_invalid_mint() {
+        let pool = test_pool();
+        let result = swap_instructions(
+            &pool.accounts,
+            pool.whirlpool,
+            1000,
+            Pubkey::new_unique(),
+            SwapType::ExactIn,
+            Some(100),
+            Some(pool.signer),
+        );
+        assert!(result.is_err());
+    }
+}
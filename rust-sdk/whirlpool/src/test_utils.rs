@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use orca_whirlpools_client::accounts::{Position, PositionBundle, TickArray, Whirlpool};
+use orca_whirlpools_client::test_accounts::{test_account, update_account, zeroed_account};
+use orca_whirlpools_client::{
+    get_bundled_position_address, get_position_address, get_position_bundle_address,
+    get_tick_array_address, POSITION_BUNDLE_DISCRIMINATOR, POSITION_DISCRIMINATOR,
+    TICK_ARRAY_DISCRIMINATOR, WHIRLPOOL_DISCRIMINATOR,
+};
+use solana_program::clock::Clock;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::rent::Rent;
+use solana_program::sysvar::SysvarId;
+use solana_sdk::account::{create_account_for_test, Account};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use spl_token_2022::extension::transfer_hook::TransferHook;
+use spl_token_2022::extension::{
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+};
+use spl_token_2022::state::Mint as Token2022Mint;
+
+use crate::PositionKind;
+
+pub fn program_account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn mint_account(token_program: Pubkey, decimals: u8) -> Account {
+    let mint = Mint {
+        mint_authority: COption::None,
+        supply: u64::MAX,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let mut data = vec![0u8; Mint::LEN];
+    Mint::pack(mint, &mut data).unwrap();
+    program_account(token_program, data)
+}
+
+pub fn token_2022_mint_account(
+    extension_types: &[ExtensionType],
+    init_extensions: impl FnOnce(&mut StateWithExtensionsMut<Token2022Mint>),
+) -> Account {
+    let len = ExtensionType::try_calculate_account_len::<Token2022Mint>(extension_types).unwrap();
+    let mut data = vec![0u8; len];
+    let mut state =
+        StateWithExtensionsMut::<Token2022Mint>::unpack_uninitialized(&mut data).unwrap();
+    state.base = Token2022Mint {
+        supply: u64::MAX,
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    };
+    init_extensions(&mut state);
+    state.pack_base();
+    state.init_account_type().unwrap();
+    program_account(spl_token_2022::ID, data)
+}
+
+pub fn transfer_hook_mint_account(program_id: Pubkey) -> Account {
+    token_2022_mint_account(&[ExtensionType::TransferHook], |state| {
+        let transfer_hook = state.init_extension::<TransferHook>(true).unwrap();
+        transfer_hook.program_id = Some(program_id).try_into().unwrap();
+    })
+}
+
+pub fn token_account(token_program: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let token_account = TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount::pack(token_account, &mut data).unwrap();
+    program_account(token_program, data)
+}
+
+pub fn rent_account() -> Account {
+    create_account_for_test(&Rent::default())
+}
+
+pub fn clock_account() -> Account {
+    create_account_for_test(&Clock::default())
+}
+
+pub fn whirlpool_account(
+    token_mint_a: Pubkey,
+    token_vault_a: Pubkey,
+    token_mint_b: Pubkey,
+    token_vault_b: Pubkey,
+    sqrt_price: u128,
+    tick_current_index: i32,
+) -> Account {
+    let mut whirlpool: Whirlpool = zeroed_account(WHIRLPOOL_DISCRIMINATOR, Whirlpool::LEN);
+    whirlpool.tick_spacing = 64;
+    whirlpool.tick_spacing_seed = 64u16.to_le_bytes();
+    whirlpool.fee_rate = 3000;
+    whirlpool.protocol_fee_rate = 300;
+    whirlpool.liquidity = 1_000_000_000;
+    whirlpool.sqrt_price = sqrt_price;
+    whirlpool.tick_current_index = tick_current_index;
+    whirlpool.token_mint_a = token_mint_a;
+    whirlpool.token_vault_a = token_vault_a;
+    whirlpool.token_mint_b = token_mint_b;
+    whirlpool.token_vault_b = token_vault_b;
+    test_account(&whirlpool, Whirlpool::LEN)
+}
+
+pub fn tick_array_account(whirlpool: Pubkey, start_tick_index: i32) -> Account {
+    let mut tick_array: TickArray = zeroed_account(TICK_ARRAY_DISCRIMINATOR, TickArray::LEN);
+    tick_array.start_tick_index = start_tick_index;
+    tick_array.whirlpool = whirlpool;
+    test_account(&tick_array, TickArray::LEN)
+}
+
+pub fn position_account(
+    whirlpool: Pubkey,
+    position_mint: Pubkey,
+    liquidity: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    fee_owed_a: u64,
+    fee_owed_b: u64,
+) -> Account {
+    let mut position: Position = zeroed_account(POSITION_DISCRIMINATOR, Position::LEN);
+    position.whirlpool = whirlpool;
+    position.position_mint = position_mint;
+    position.liquidity = liquidity;
+    position.tick_lower_index = tick_lower_index;
+    position.tick_upper_index = tick_upper_index;
+    position.fee_owed_a = fee_owed_a;
+    position.fee_owed_b = fee_owed_b;
+    test_account(&position, Position::LEN)
+}
+
+pub fn position_bundle_account(position_bundle_mint: Pubkey, bundle_indexes: &[u8]) -> Account {
+    let mut position_bundle: PositionBundle =
+        zeroed_account(POSITION_BUNDLE_DISCRIMINATOR, PositionBundle::LEN);
+    position_bundle.position_bundle_mint = position_bundle_mint;
+    for bundle_index in bundle_indexes {
+        position_bundle.position_bitmap[*bundle_index as usize / 8] |= 1 << (bundle_index % 8);
+    }
+    test_account(&position_bundle, PositionBundle::LEN)
+}
+
+pub struct TestPool {
+    pub accounts: HashMap<Pubkey, Account>,
+    pub whirlpool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub signer: Pubkey,
+}
+
+/// A pool at price 1 between an SPL Token and a Token-2022 mint, with a signer that holds
+/// 1_000_000 of both tokens.
+pub fn test_pool() -> TestPool {
+    let whirlpool = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let vault_a = Pubkey::new_unique();
+    let vault_b = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let ata_a = get_associated_token_address_with_program_id(&signer, &mint_a, &spl_token::ID);
+    let ata_b = get_associated_token_address_with_program_id(&signer, &mint_b, &spl_token_2022::ID);
+    let accounts = HashMap::from([
+        (
+            whirlpool,
+            whirlpool_account(mint_a, vault_a, mint_b, vault_b, 1 << 64, 0),
+        ),
+        (mint_a, mint_account(spl_token::ID, 6)),
+        (mint_b, mint_account(spl_token_2022::ID, 6)),
+        (
+            ata_a,
+            token_account(spl_token::ID, mint_a, signer, 1_000_000),
+        ),
+        (
+            ata_b,
+            token_account(spl_token_2022::ID, mint_b, signer, 1_000_000),
+        ),
+        (Clock::id(), clock_account()),
+        (Rent::id(), rent_account()),
+    ]);
+    TestPool {
+        accounts,
+        whirlpool,
+        mint_a,
+        mint_b,
+        vault_a,
+        vault_b,
+        signer,
+    }
+}
+
+impl TestPool {
+    /// Adds a position of the signer between ticks -640 and 640, together with the token
+    /// that represents it and the tick arrays that contain its ticks.
+    pub fn add_position(
+        &mut self,
+        kind: PositionKind,
+        liquidity: u128,
+        fee_owed_a: u64,
+        fee_owed_b: u64,
+    ) -> Pubkey {
+        let (position_address, position_mint, token_program) = match kind {
+            PositionKind::Token | PositionKind::Token2022 => {
+                let position_mint = Pubkey::new_unique();
+                let token_program = if kind == PositionKind::Token {
+                    spl_token::ID
+                } else {
+                    spl_token_2022::ID
+                };
+                let (position_address, _) = get_position_address(&position_mint).unwrap();
+                (position_address, position_mint, token_program)
+            }
+            PositionKind::Bundled {
+                position_bundle_mint,
+                bundle_index,
+            } => {
+                let (position_bundle_address, _) =
+                    get_position_bundle_address(&position_bundle_mint).unwrap();
+                let position_bundle = self
+                    .accounts
+                    .entry(position_bundle_address)
+                    .or_insert_with(|| position_bundle_account(position_bundle_mint, &[]));
+                update_account(position_bundle, |position_bundle: &mut PositionBundle| {
+                    position_bundle.position_bitmap[bundle_index as usize / 8] |=
+                        1 << (bundle_index % 8);
+                });
+                let (position_address, _) =
+                    get_bundled_position_address(&position_bundle_mint, bundle_index).unwrap();
+                (position_address, position_bundle_mint, spl_token::ID)
+            }
+        };
+        let position_token_account = get_associated_token_address_with_program_id(
+            &self.signer,
+            &position_mint,
+            &token_program,
+        );
+        self.accounts.insert(
+            position_address,
+            position_account(
+                self.whirlpool,
+                position_mint,
+                liquidity,
+                -640,
+                640,
+                fee_owed_a,
+                fee_owed_b,
+            ),
+        );
+        self.accounts
+            .insert(position_mint, mint_account(token_program, 0));
+        self.accounts.insert(
+            position_token_account,
+            token_account(token_program, position_mint, self.signer, 1),
+        );
+        for start_tick_index in [-5632, 0] {
+            let (tick_array_address, _) =
+                get_tick_array_address(&self.whirlpool, start_tick_index).unwrap();
+            self.accounts.insert(
+                tick_array_address,
+                tick_array_account(self.whirlpool, start_tick_index),
+            );
+        }
+        position_address
+    }
+
+    /// Initializes a reward of the whirlpool with a new vault and sets the amount of it that
+    /// is owed to a position.
+    pub fn add_reward(
+        &mut self,
+        position_address: Pubkey,
+        reward_index: usize,
+        mint: Pubkey,
+        amount_owed: u64,
+    ) -> Pubkey {
+        let vault = Pubkey::new_unique();
+        let whirlpool = self.accounts.get_mut(&self.whirlpool).unwrap();
+        update_account(whirlpool, |whirlpool: &mut Whirlpool| {
+            whirlpool.reward_infos[reward_index].mint = mint;
+            whirlpool.reward_infos[reward_index].vault = vault;
+        });
+        let position = self.accounts.get_mut(&position_address).unwrap();
+        update_account(position, |position: &mut Position| {
+            position.reward_infos[reward_index].amount_owed = amount_owed;
+        });
+        vault
+    }
+}
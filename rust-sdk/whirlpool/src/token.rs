@@ -0,0 +1,578 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use orca_whirlpools_client::AccountFetcher;
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::system_instruction::{create_account, create_account_with_seed, transfer};
+use solana_program::sysvar::rent::Rent;
+use solana_program::sysvar::Sysvar;
+use solana_sdk::account::{from_account, Account};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::instruction::{close_account, initialize_account3, sync_native};
+use spl_token::native_mint;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccount;
+
+use crate::NativeMintWrappingStrategy;
+
+/// The mint of wrapped SOL.
+pub(crate) const NATIVE_MINT: Pubkey = native_mint::ID;
+
+/// The mint of a token account that needs to be prepared, optionally with the balance it
+/// should hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenAccountStrategy {
+    WithoutBalance(Pubkey),
+    WithBalance(Pubkey, u64),
+}
+
+impl TokenAccountStrategy {
+    fn mint(&self) -> Pubkey {
+        match self {
+            TokenAccountStrategy::WithoutBalance(mint) => *mint,
+            TokenAccountStrategy::WithBalance(mint, _) => *mint,
+        }
+    }
+
+    fn balance(&self) -> Option<u64> {
+        match self {
+            TokenAccountStrategy::WithoutBalance(_) => None,
+            TokenAccountStrategy::WithBalance(_, balance) => Some(*balance),
+        }
+    }
+
+    // Combine two strategies for the same mint into one, adding up the required balances.
+    fn try_merge(&self, other: &TokenAccountStrategy) -> Result<Self, Box<dyn Error>> {
+        match (self.balance(), other.balance()) {
+            (None, None) => Ok(*self),
+            (balance, other_balance) => {
+                let balance = balance
+                    .unwrap_or(0)
+                    .checked_add(other_balance.unwrap_or(0))
+                    .ok_or(format!("Required balance of {} overflows", self.mint()))?;
+                Ok(TokenAccountStrategy::WithBalance(self.mint(), balance))
+            }
+        }
+    }
+}
+
+/// Represents the instructions and associated addresses for preparing token accounts during a
+/// transaction.
+#[derive(Debug, Default)]
+pub(crate) struct TokenAccountInstructions {
+    /// The instructions required to create the necessary token accounts.
+    pub create_instructions: Vec<Instruction>,
+    /// The instructions to clean up (e.g., close) token accounts after the transaction is complete.
+    pub cleanup_instructions: Vec<Instruction>,
+    /// The token account address for each mint.
+    pub token_account_addresses: HashMap<Pubkey, Pubkey>,
+    /// The keypairs of newly created token accounts that need to sign the transaction.
+    pub additional_signers: Vec<Keypair>,
+}
+
+/// Fetch and decode a sysvar account.
+pub(crate) fn get_sysvar<S: Sysvar, F: AccountFetcher>(fetcher: &F) -> Result<S, Box<dyn Error>> {
+    let account = fetcher
+        .get_account(&S::id())?
+        .ok_or(format!("Sysvar {} not found", S::id()))?;
+    from_account::<S, _>(&account).ok_or(format!("Sysvar {} could not be decoded", S::id()).into())
+}
+
+/// Fetch the mint accounts of a list of mints.
+pub(crate) fn get_mint_accounts<F: AccountFetcher>(
+    fetcher: &F,
+    mints: &[Pubkey],
+) -> Result<Vec<Account>, Box<dyn Error>> {
+    fetcher
+        .get_multiple_accounts(mints)?
+        .into_iter()
+        .zip(mints)
+        .map(|(account, mint)| account.ok_or(format!("Mint {} not found", mint).into()))
+        .collect()
+}
+
+/// Prepare the token accounts of an owner for a transaction. This creates ATAs for the supplied
+/// mints. The native mint is a special case, which is optionally wrapped and unwrapped based on
+/// the native mint wrapping strategy. A mint that is supplied more than once gets a single
+/// token account that holds the sum of the required balances.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `owner` - The owner to create token accounts for
+/// - `spec` - The mints (and balances) required in the token accounts
+/// - `strategy` - The native mint wrapping strategy
+///
+/// # Returns
+/// - The instructions and addresses of the token accounts
+pub(crate) fn prepare_token_accounts_instructions<F: AccountFetcher>(
+    fetcher: &F,
+    owner: Pubkey,
+    spec: &[TokenAccountStrategy],
+    strategy: NativeMintWrappingStrategy,
+) -> Result<TokenAccountInstructions, Box<dyn Error>> {
+    let wraps_native_mint = matches!(
+        strategy,
+        NativeMintWrappingStrategy::Keypair | NativeMintWrappingStrategy::Seed
+    );
+
+    let mut merged_spec: Vec<TokenAccountStrategy> = Vec::with_capacity(spec.len());
+    let mut spec_indexes: HashMap<Pubkey, usize> = HashMap::new();
+    for x in spec {
+        match spec_indexes.entry(x.mint()) {
+            Entry::Occupied(entry) => {
+                let i = *entry.get();
+                merged_spec[i] = merged_spec[i].try_merge(x)?;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(merged_spec.len());
+                merged_spec.push(*x);
+            }
+        }
+    }
+
+    let native_mint_balance = merged_spec
+        .iter()
+        .find(|x| x.mint() == NATIVE_MINT)
+        .map(|x| x.balance().unwrap_or(0));
+
+    let spec: Vec<TokenAccountStrategy> = merged_spec
+        .into_iter()
+        .filter(|x| !wraps_native_mint || x.mint() != NATIVE_MINT)
+        .collect();
+    let mint_addresses: Vec<Pubkey> = spec.iter().map(|x| x.mint()).collect();
+    let mint_accounts = get_mint_accounts(fetcher, &mint_addresses)?;
+    let token_addresses: Vec<Pubkey> = mint_addresses
+        .iter()
+        .zip(&mint_accounts)
+        .map(|(mint, mint_account)| {
+            get_associated_token_address_with_program_id(&owner, mint, &mint_account.owner)
+        })
+        .collect();
+    let token_accounts = fetcher.get_multiple_accounts(&token_addresses)?;
+
+    let mut instructions = TokenAccountInstructions::default();
+    let mut native_mint_ata_balance = None;
+
+    for i in 0..spec.len() {
+        let mint = mint_addresses[i];
+        instructions
+            .token_account_addresses
+            .insert(mint, token_addresses[i]);
+
+        let existing_balance = match &token_accounts[i] {
+            Some(token_account) => Some(
+                StateWithExtensions::<TokenAccount>::unpack(&token_account.data)?
+                    .base
+                    .amount,
+            ),
+            None => {
+                instructions
+                    .create_instructions
+                    .push(create_associated_token_account_idempotent(
+                        &owner,
+                        &owner,
+                        &mint,
+                        &mint_accounts[i].owner,
+                    ));
+                None
+            }
+        };
+
+        if mint == NATIVE_MINT && strategy == NativeMintWrappingStrategy::Ata {
+            native_mint_ata_balance = Some(existing_balance);
+            continue;
+        }
+
+        if let Some(balance) = spec[i].balance() {
+            if balance > existing_balance.unwrap_or(0) {
+                return Err(format!(
+                    "Token account for {} does not have the required balance",
+                    mint
+                )
+                .into());
+            }
+        }
+    }
+
+    let Some(native_mint_balance) = native_mint_balance else {
+        return Ok(instructions);
+    };
+
+    match strategy {
+        NativeMintWrappingStrategy::Keypair => {
+            let keypair = Keypair::new();
+            let rent: Rent = get_sysvar(fetcher)?;
+            let lamports = rent.minimum_balance(TokenAccount::LEN) + native_mint_balance;
+            instructions.create_instructions.push(create_account(
+                &owner,
+                &keypair.pubkey(),
+                lamports,
+                TokenAccount::LEN as u64,
+                &spl_token::ID,
+            ));
+            instructions.create_instructions.push(initialize_account3(
+                &spl_token::ID,
+                &keypair.pubkey(),
+                &NATIVE_MINT,
+                &owner,
+            )?);
+            instructions.cleanup_instructions.push(close_account(
+                &spl_token::ID,
+                &keypair.pubkey(),
+                &owner,
+                &owner,
+                &[],
+            )?);
+            instructions
+                .token_account_addresses
+                .insert(NATIVE_MINT, keypair.pubkey());
+            instructions.additional_signers.push(keypair);
+        }
+        NativeMintWrappingStrategy::Seed => {
+            let rent: Rent = get_sysvar(fetcher)?;
+            let lamports = rent.minimum_balance(TokenAccount::LEN) + native_mint_balance;
+
+            // Generating a secure seed takes longer and is not really needed here. With the time,
+            // it should only create collisions if the same owner creates multiple accounts at
+            // exactly the same time (in ms)
+            let seed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)?
+                .as_millis()
+                .to_string();
+            let address = Pubkey::create_with_seed(&owner, &seed, &spl_token::ID)?;
+
+            instructions
+                .create_instructions
+                .push(create_account_with_seed(
+                    &owner,
+                    &address,
+                    &owner,
+                    &seed,
+                    lamports,
+                    TokenAccount::LEN as u64,
+                    &spl_token::ID,
+                ));
+            instructions.create_instructions.push(initialize_account3(
+                &spl_token::ID,
+                &address,
+                &NATIVE_MINT,
+                &owner,
+            )?);
+            instructions.cleanup_instructions.push(close_account(
+                &spl_token::ID,
+                &address,
+                &owner,
+                &owner,
+                &[],
+            )?);
+            instructions
+                .token_account_addresses
+                .insert(NATIVE_MINT, address);
+        }
+        NativeMintWrappingStrategy::Ata => {
+            let address = instructions.token_account_addresses[&NATIVE_MINT];
+            let existing_balance = native_mint_ata_balance.flatten();
+            let balance = existing_balance.unwrap_or(0);
+            if balance < native_mint_balance {
+                instructions.create_instructions.push(transfer(
+                    &owner,
+                    &address,
+                    native_mint_balance - balance,
+                ));
+                instructions
+                    .create_instructions
+                    .push(sync_native(&spl_token::ID, &address)?);
+            }
+            if existing_balance.is_none() {
+                instructions.cleanup_instructions.push(close_account(
+                    &spl_token::ID,
+                    &address,
+                    &owner,
+                    &owner,
+                    &[],
+                )?);
+            }
+        }
+        NativeMintWrappingStrategy::None => {}
+    }
+
+    Ok(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mint_account, rent_account, token_account};
+    use solana_program::system_program;
+    use solana_program::sysvar::SysvarId;
+
+    #[test]
+    fn test_prepare_token_accounts_existing() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = get_associated_token_address_with_program_id(&owner, &mint, &spl_token::ID);
+        let accounts = HashMap::from([
+            (mint, mint_account(spl_token::ID, 6)),
+            (ata, token_account(spl_token::ID, mint, owner, 1000)),
+        ]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(mint, 1000)],
+            NativeMintWrappingStrategy::Keypair,
+        )
+        .unwrap();
+        assert!(result.create_instructions.is_empty());
+        assert!(result.cleanup_instructions.is_empty());
+        assert_eq!(result.token_account_addresses[&mint], ata);
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_insufficient_balance() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = get_associated_token_address_with_program_id(&owner, &mint, &spl_token::ID);
+        let accounts = HashMap::from([
+            (mint, mint_account(spl_token::ID, 6)),
+            (ata, token_account(spl_token::ID, mint, owner, 999)),
+        ]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(mint, 1000)],
+            NativeMintWrappingStrategy::Keypair,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_creates_missing_ata() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = get_associated_token_address_with_program_id(&owner, &mint, &spl_token_2022::ID);
+        let accounts = HashMap::from([(mint, mint_account(spl_token_2022::ID, 6))]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithoutBalance(mint)],
+            NativeMintWrappingStrategy::Keypair,
+        )
+        .unwrap();
+        assert_eq!(result.create_instructions.len(), 1);
+        assert_eq!(
+            result.create_instructions[0],
+            create_associated_token_account_idempotent(&owner, &owner, &mint, &spl_token_2022::ID)
+        );
+        assert_eq!(result.token_account_addresses[&mint], ata);
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_duplicate_mints() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = get_associated_token_address_with_program_id(&owner, &mint, &spl_token::ID);
+        let accounts = HashMap::from([(mint, mint_account(spl_token::ID, 6))]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[
+                TokenAccountStrategy::WithoutBalance(mint),
+                TokenAccountStrategy::WithoutBalance(mint),
+            ],
+            NativeMintWrappingStrategy::Keypair,
+        )
+        .unwrap();
+        assert_eq!(result.create_instructions.len(), 1);
+        assert_eq!(result.token_account_addresses[&mint], ata);
+
+        let accounts = HashMap::from([
+            (mint, mint_account(spl_token::ID, 6)),
+            (ata, token_account(spl_token::ID, mint, owner, 1000)),
+        ]);
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[
+                TokenAccountStrategy::WithBalance(mint, 600),
+                TokenAccountStrategy::WithoutBalance(mint),
+                TokenAccountStrategy::WithBalance(mint, 500),
+            ],
+            NativeMintWrappingStrategy::Keypair,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_wraps_native_mint() {
+        let owner = Pubkey::new_unique();
+        let accounts = HashMap::from([
+            (NATIVE_MINT, mint_account(spl_token::ID, 9)),
+            (Rent::id(), rent_account()),
+        ]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(NATIVE_MINT, 1000)],
+            NativeMintWrappingStrategy::Keypair,
+        )
+        .unwrap();
+        assert_eq!(result.additional_signers.len(), 1);
+        let keypair = &result.additional_signers[0];
+        assert_eq!(
+            result.token_account_addresses[&NATIVE_MINT],
+            keypair.pubkey()
+        );
+        assert_eq!(result.create_instructions.len(), 2);
+        assert_eq!(result.create_instructions[0].program_id, system_program::ID);
+        assert_eq!(
+            result.create_instructions[0],
+            create_account(
+                &owner,
+                &keypair.pubkey(),
+                Rent::default().minimum_balance(TokenAccount::LEN) + 1000,
+                TokenAccount::LEN as u64,
+                &spl_token::ID,
+            )
+        );
+        assert_eq!(result.cleanup_instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_wraps_native_mint_with_seed() {
+        let owner = Pubkey::new_unique();
+        let accounts = HashMap::from([
+            (NATIVE_MINT, mint_account(spl_token::ID, 9)),
+            (Rent::id(), rent_account()),
+        ]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(NATIVE_MINT, 1000)],
+            NativeMintWrappingStrategy::Seed,
+        )
+        .unwrap();
+        assert!(result.additional_signers.is_empty());
+        let address = result.token_account_addresses[&NATIVE_MINT];
+        assert_ne!(
+            address,
+            get_associated_token_address_with_program_id(&owner, &NATIVE_MINT, &spl_token::ID)
+        );
+        assert_eq!(result.create_instructions.len(), 2);
+        assert_eq!(result.create_instructions[0].program_id, system_program::ID);
+        assert_eq!(result.create_instructions[0].accounts[1].pubkey, address);
+        assert_eq!(
+            result.create_instructions[1],
+            initialize_account3(&spl_token::ID, &address, &NATIVE_MINT, &owner).unwrap()
+        );
+        assert_eq!(
+            result.cleanup_instructions,
+            vec![close_account(&spl_token::ID, &address, &owner, &owner, &[]).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_wraps_native_mint_in_ata() {
+        let owner = Pubkey::new_unique();
+        let ata =
+            get_associated_token_address_with_program_id(&owner, &NATIVE_MINT, &spl_token::ID);
+        let accounts = HashMap::from([(NATIVE_MINT, mint_account(spl_token::ID, 9))]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(NATIVE_MINT, 1000)],
+            NativeMintWrappingStrategy::Ata,
+        )
+        .unwrap();
+        assert!(result.additional_signers.is_empty());
+        assert_eq!(result.token_account_addresses[&NATIVE_MINT], ata);
+        assert_eq!(
+            result.create_instructions,
+            vec![
+                create_associated_token_account_idempotent(
+                    &owner,
+                    &owner,
+                    &NATIVE_MINT,
+                    &spl_token::ID
+                ),
+                transfer(&owner, &ata, 1000),
+                sync_native(&spl_token::ID, &ata).unwrap(),
+            ]
+        );
+        assert_eq!(
+            result.cleanup_instructions,
+            vec![close_account(&spl_token::ID, &ata, &owner, &owner, &[]).unwrap()]
+        );
+
+        // An existing ATA is only topped up and is not closed
+        let accounts = HashMap::from([
+            (NATIVE_MINT, mint_account(spl_token::ID, 9)),
+            (ata, token_account(spl_token::ID, NATIVE_MINT, owner, 400)),
+        ]);
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(NATIVE_MINT, 1000)],
+            NativeMintWrappingStrategy::Ata,
+        )
+        .unwrap();
+        assert_eq!(
+            result.create_instructions,
+            vec![
+                transfer(&owner, &ata, 600),
+                sync_native(&spl_token::ID, &ata).unwrap(),
+            ]
+        );
+        assert!(result.cleanup_instructions.is_empty());
+    }
+
+    #[test]
+    fn test_prepare_token_accounts_does_not_wrap_native_mint() {
+        let owner = Pubkey::new_unique();
+        let ata =
+            get_associated_token_address_with_program_id(&owner, &NATIVE_MINT, &spl_token::ID);
+        let accounts = HashMap::from([(NATIVE_MINT, mint_account(spl_token::ID, 9))]);
+
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithoutBalance(NATIVE_MINT)],
+            NativeMintWrappingStrategy::None,
+        )
+        .unwrap();
+        assert!(result.additional_signers.is_empty());
+        assert_eq!(result.token_account_addresses[&NATIVE_MINT], ata);
+        assert_eq!(
+            result.create_instructions,
+            vec![create_associated_token_account_idempotent(
+                &owner,
+                &owner,
+                &NATIVE_MINT,
+                &spl_token::ID
+            )]
+        );
+        assert!(result.cleanup_instructions.is_empty());
+
+        // The balance has to be in the ATA already
+        let result = prepare_token_accounts_instructions(
+            &accounts,
+            owner,
+            &[TokenAccountStrategy::WithBalance(NATIVE_MINT, 1000)],
+            NativeMintWrappingStrategy::None,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use std::error::Error;
+
+use futures::executor::block_on;
+use orca_whirlpools_client::types::{AccountsType, RemainingAccountsInfo};
+use orca_whirlpools_client::AccountFetcher;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use spl_token_2022::extension::{transfer_hook, StateWithExtensions};
+use spl_token_2022::state::Mint;
+use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
+
+use crate::remaining_accounts::RemainingAccountsBuilder;
+
+/// A token transfer made by a v2 instruction of the whirlpool program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTransfer {
+    /// The slice of the remaining accounts that holds the transfer hook accounts of the transfer.
+    pub accounts_type: AccountsType,
+    /// The mint of the transferred token.
+    pub mint: Pubkey,
+    /// The token account the tokens are transferred from.
+    pub source: Pubkey,
+    /// The token account the tokens are transferred to.
+    pub destination: Pubkey,
+    /// The owner of the source token account, which is the whirlpool for transfers out of a vault.
+    pub owner: Pubkey,
+}
+
+/// Resolves the extra accounts that the transfer hook program of a mint needs for a transfer.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `mint` - The address of the mint
+/// - `mint_account` - The mint account
+/// - `source` - The token account the tokens are transferred from
+/// - `destination` - The token account the tokens are transferred to
+/// - `owner` - The owner of the source token account
+///
+/// # Returns
+/// - The extra accounts, `None` if the mint has no transfer hook
+pub fn get_transfer_hook_accounts<F: AccountFetcher>(
+    fetcher: &F,
+    mint: Pubkey,
+    mint_account: &Account,
+    source: Pubkey,
+    destination: Pubkey,
+    owner: Pubkey,
+) -> Result<Option<Vec<AccountMeta>>, Box<dyn Error>> {
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+    let Some(program_id) = transfer_hook::get_program_id(&mint_state) else {
+        return Ok(None);
+    };
+
+    let mut instruction = Instruction::new_with_bytes(
+        spl_token_2022::ID,
+        &[],
+        vec![
+            AccountMeta::new_readonly(source, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(destination, false),
+            AccountMeta::new_readonly(owner, false),
+            AccountMeta::new_readonly(owner, false),
+        ],
+    );
+
+    // The extra accounts must not depend on the amount, which changes with the slippage
+    block_on(add_extra_account_metas_for_execute(
+        &mut instruction,
+        &program_id,
+        &source,
+        &mint,
+        &destination,
+        &owner,
+        0,
+        |address| {
+            let data = fetcher
+                .get_account(&address)
+                .map(|account| account.map(|account| account.data))
+                .map_err(|error| error.into());
+            std::future::ready(data)
+        },
+    ))
+    .map_err(|error| error as Box<dyn Error>)?;
+
+    let extra_accounts = instruction.accounts.split_off(5);
+    Ok((!extra_accounts.is_empty()).then_some(extra_accounts))
+}
+
+/// Resolves the transfer hook accounts of the token transfers of a v2 instruction and orders
+/// them into the remaining accounts of the instruction. Transfers of mints without a transfer
+/// hook do not add a slice.
+///
+/// # Parameters
+/// - `fetcher` - The account fetcher
+/// - `transfers` - The token transfers of the instruction, in the order of their slices
+///
+/// # Returns
+/// - The `remaining_accounts_info` argument of the instruction and the remaining accounts
+pub fn get_transfer_hook_remaining_accounts<F: AccountFetcher>(
+    fetcher: &F,
+    transfers: &[TokenTransfer],
+) -> Result<(Option<RemainingAccountsInfo>, Vec<AccountMeta>), Box<dyn Error>> {
+    let mints: Vec<Pubkey> = transfers.iter().map(|transfer| transfer.mint).collect();
+    let mint_accounts = fetcher.get_multiple_accounts(&mints)?;
+
+    let mut builder = RemainingAccountsBuilder::default();
+    for (transfer, mint_account) in transfers.iter().zip(mint_accounts) {
+        let mint_account = mint_account.ok_or(format!("Mint {} not found", transfer.mint))?;
+        let transfer_hook_accounts = get_transfer_hook_accounts(
+            fetcher,
+            transfer.mint,
+            &mint_account,
+            transfer.source,
+            transfer.destination,
+            transfer.owner,
+        )?;
+        builder = builder.add_slice(
+            transfer.accounts_type,
+            &transfer_hook_accounts.unwrap_or_default(),
+        )?;
+    }
+    Ok(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mint_account, transfer_hook_mint_account};
+    use orca_whirlpools_client::types::RemainingAccountsSlice;
+    use spl_tlv_account_resolution::account::ExtraAccountMeta;
+    use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+    use spl_transfer_hook_interface::get_extra_account_metas_address;
+    use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+    use std::collections::HashMap;
+
+    fn extra_account_metas_account(program_id: Pubkey, extra_accounts: &[Pubkey]) -> Account {
+        let extra_account_metas: Vec<ExtraAccountMeta> = extra_accounts
+            .iter()
+            .map(|address| ExtraAccountMeta::new_with_pubkey(address, false, true).unwrap())
+            .collect();
+        let mut data = vec![0u8; ExtraAccountMetaList::size_of(extra_account_metas.len()).unwrap()];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas).unwrap();
+        crate::test_utils::program_account(program_id, data)
+    }
+
+    #[test]
+    fn test_get_transfer_hook_accounts_without_extension() {
+        let accounts: HashMap<Pubkey, Account> = HashMap::new();
+        let account = mint_account(spl_token_2022::ID, 6);
+        let result = get_transfer_hook_accounts(
+            &accounts,
+            Pubkey::new_unique(),
+            &account,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        )
+        .unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_get_transfer_hook_accounts() {
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let extra_account = Pubkey::new_unique();
+        let accounts = HashMap::from([(
+            get_extra_account_metas_address(&mint, &program_id),
+            extra_account_metas_account(program_id, &[extra_account]),
+        )]);
+        let result = get_transfer_hook_accounts(
+            &accounts,
+            mint,
+            &transfer_hook_mint_account(program_id),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Some(vec![
+                AccountMeta::new(extra_account, false),
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new_readonly(
+                    get_extra_account_metas_address(&mint, &program_id),
+                    false
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_get_transfer_hook_remaining_accounts() {
+        let program_id = Pubkey::new_unique();
+        let hook_mint = Pubkey::new_unique();
+        let plain_mint = Pubkey::new_unique();
+        let extra_account = Pubkey::new_unique();
+        let accounts = HashMap::from([
+            (hook_mint, transfer_hook_mint_account(program_id)),
+            (plain_mint, mint_account(spl_token_2022::ID, 6)),
+            (
+                get_extra_account_metas_address(&hook_mint, &program_id),
+                extra_account_metas_account(program_id, &[extra_account]),
+            ),
+        ]);
+        let transfer = |accounts_type, mint| TokenTransfer {
+            accounts_type,
+            mint,
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+        };
+        let (remaining_accounts_info, remaining_accounts) = get_transfer_hook_remaining_accounts(
+            &accounts,
+            &[
+                transfer(AccountsType::TransferHookInput, plain_mint),
+                transfer(AccountsType::TransferHookOutput, hook_mint),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            remaining_accounts_info,
+            Some(RemainingAccountsInfo {
+                slices: vec![RemainingAccountsSlice {
+                    accounts_type: AccountsType::TransferHookOutput,
+                    length: 3,
+                }],
+            })
+        );
+        assert_eq!(remaining_accounts[0].pubkey, extra_account);
+        assert_eq!(remaining_accounts[1].pubkey, program_id);
+    }
+
+    #[test]
+    fn test_get_transfer_hook_remaining_accounts_without_hooks() {
+        let mint = Pubkey::new_unique();
+        let accounts = HashMap::from([(mint, mint_account(spl_token::ID, 6))]);
+        let transfer = TokenTransfer {
+            accounts_type: AccountsType::TransferHookA,
+            mint,
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+        };
+        let result = get_transfer_hook_remaining_accounts(&accounts, &[transfer]).unwrap();
+        assert_eq!(result, (None, vec![]));
+    }
+}